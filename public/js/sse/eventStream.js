@@ -25,6 +25,7 @@ class EventStream {
     this.handleNamedEvent = this.handleNamedEvent.bind(this);
     this.handleOnMessage = this.handleOnMessage.bind(this);
     this.handleError = this.handleError.bind(this);
+    this.handleServerShutdown = this.handleServerShutdown.bind(this);
     this.handleNetworkOnline = this.handleNetworkOnline.bind(this);
     this.handleNetworkOffline = this.handleNetworkOffline.bind(this);
 
@@ -103,6 +104,7 @@ class EventStream {
     this.eventSource.addEventListener('announcement_status', this.handleNamedEvent);
     this.eventSource.addEventListener('tts_complete', this.handleNamedEvent);
     this.eventSource.addEventListener('translator_call', this.handleNamedEvent);
+    this.eventSource.addEventListener('server_shutdown', this.handleServerShutdown);
     this.eventSource.onerror = this.handleError;
   }
 
@@ -144,6 +146,13 @@ class EventStream {
     this.scheduleReconnect();
   }
 
+  handleServerShutdown() {
+    // The server is stopping or restarting and ends the stream; show the
+    // reconnecting state right away instead of waiting for the connection error.
+    this.closeSource();
+    this.scheduleReconnect();
+  }
+
   handleNetworkOnline() {
     if (!this.eventSource) {
       this.connect();
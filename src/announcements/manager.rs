@@ -5,11 +5,15 @@
 //! This module defines the `AnnouncementManager` which:
 //! - Scans a configured directory for announcement "slots" (subdirectories containing audio and banner files).
 //! - Cycles through these slots automatically at a configurable interval or manually.
+//! - Restricts slots to their configured schedules and plays fixed-time slots on time.
+//! - Applies optional per-slot manifests: titles, enable flag, weight, cooldown and audio order.
 //! - Maintains the current announcement status, including active slot, playlists, and manual trigger cooldown.
 //! - Broadcasts `AppEvent::AnnouncementStatus` updates to inform other parts of the application and SSE clients.
 
+use chrono::{Local, NaiveDateTime};
 use serde::Serialize;
 use std::{
+    collections::BTreeMap,
     fmt,
     path::{Path, PathBuf},
     sync::Arc,
@@ -23,7 +27,9 @@ use tokio::{
 };
 use tracing::{debug, error, info, trace, warn}; // Import tracing macros
 
-use crate::config::AppConfig;
+use super::manifest::{self, SlotManifest};
+use super::schedule::SlotSchedule;
+use crate::config::SharedConfig;
 use crate::AppEvent;
 
 /// Represents a single announcement slot, containing a collection of audio and banner files.
@@ -37,6 +43,13 @@ pub struct AnnouncementSlot {
     /// A list of paths to audio files (e.g., MP3, WAV) within this slot.
     /// These paths are relative to the project root for internal use.
     pub audio_files: Vec<PathBuf>,
+    /// When the slot may play; `None` lets it cycle at any time. An
+    /// `ANNOUNCEMENT_SCHEDULES` entry replaces the manifest's schedule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<SlotSchedule>,
+    /// Settings from the slot's `slot.toml`/`slot.json`, or the defaults.
+    #[serde(skip)]
+    pub manifest: SlotManifest,
 }
 
 /// Provides a lightweight summary of an announcement slot used for API responses and SSE updates.
@@ -46,6 +59,23 @@ pub struct AnnouncementSlotSummary {
     pub id: String,
     /// Web-accessible URLs for the audio files contained in this slot.
     pub audio_playlist: Vec<String>,
+    /// The slot's schedule as configured (e.g. `mon-fri 07:00-10:00`), if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Friendly name from the slot manifest, in the first announcement language
+    /// that has one; clients fall back to `id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Manifest titles by language code.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub titles: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Plays per auto-cycle round; `0` when the slot only plays on demand or at fixed times.
+    pub weight: u32,
+    /// Least time between two plays of this slot, if the manifest sets one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_seconds: Option<u64>,
 }
 
 /// Provides a snapshot of the current status of the announcement system.
@@ -115,14 +145,20 @@ impl std::error::Error for ManualTriggerError {}
 /// - Broadcasting status updates via the application's event bus.
 #[derive(Debug)]
 pub struct AnnouncementManager {
-    /// Application configuration, providing paths, intervals, and cooldowns.
-    config: Arc<AppConfig>,
+    /// Live application configuration, providing paths, intervals, and cooldowns.
+    config: SharedConfig,
     /// A sorted list of all discovered announcement slots.
     slots: Vec<AnnouncementSlot>,
     /// Collected banner media files served to clients.
     banner_files: Vec<PathBuf>,
     /// The index of the currently active announcement slot within the `slots` vector.
     current_slot_index: usize,
+    /// Slot indices in auto-cycle order, each repeated by its weight.
+    rotation: Vec<usize>,
+    /// Position within `rotation` the auto-cycle continues from.
+    rotation_position: usize,
+    /// When each slot was last shown, for per-slot cooldowns.
+    last_shown: Vec<Option<Instant>>,
     /// The `Instant` when the last manual announcement trigger occurred, used for cooldown.
     last_manual_trigger: Instant,
     /// Tracks when the auto-cycle timer was most recently reset.
@@ -138,6 +174,8 @@ pub struct AnnouncementManager {
 
 impl AnnouncementManager {
     const INITIAL_STATUS_BROADCAST_DELAY_SECS: u64 = 30;
+    /// How often the auto-cycle task re-checks schedule windows while waiting.
+    const SCHEDULE_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
 
     /// Creates a new `AnnouncementManager` instance.
     ///
@@ -145,7 +183,7 @@ impl AnnouncementManager {
     /// and optionally starts a background task for auto-cycling if configured.
     ///
     /// # Arguments
-    /// - `config`: An `Arc` to the shared application configuration.
+    /// - `shared_config`: The shared application configuration.
     /// - `event_bus_sender`: A `broadcast::Sender` for `AppEvent`s, used to
     ///   broadcast `AnnouncementStatus` updates.
     ///
@@ -154,10 +192,11 @@ impl AnnouncementManager {
     /// The `Mutex` wrapper is necessary as the manager's state is modified by both
     /// API routes and the internal auto-cycle task.
     pub async fn new(
-        config: Arc<AppConfig>,
+        shared_config: SharedConfig,
         event_bus_sender: broadcast::Sender<AppEvent>,
     ) -> Arc<Mutex<Self>> {
         info!("Initializing AnnouncementManager...");
+        let config = shared_config.get();
         debug!("AnnouncementManager new: Using config: {:?}", config);
 
         // Scan the configured base paths for announcement slot directories.
@@ -167,6 +206,23 @@ impl AnnouncementManager {
         let banner_files = Self::scan_banner_files(&banner_base_path).await;
         // Sort slots by their ID for consistent ordering.
         slots.sort_by(|a, b| a.id.cmp(&b.id));
+        for (slot_id, schedule) in config.announcement_schedules() {
+            match slots.iter_mut().find(|slot| slot.id == slot_id) {
+                Some(slot) => {
+                    if let Some(replaced) = &slot.schedule {
+                        debug!(
+                            "ANNOUNCEMENT_SCHEDULES replaces the manifest schedule '{}' of slot '{}'.",
+                            replaced, slot.id
+                        );
+                    }
+                    slot.schedule = Some(schedule);
+                }
+                None => warn!(
+                    "ANNOUNCEMENT_SCHEDULES names slot '{}', which was not found; ignoring its schedule.",
+                    slot_id
+                ),
+            }
+        }
 
         info!(
             "Found {} announcement slots (audio path: {:?}).",
@@ -177,14 +233,24 @@ impl AnnouncementManager {
         for slot in &slots {
             debug!("  - Slot: {}", slot.id);
             debug!("    Audio files: {}", slot.audio_files.len());
+            if let Some(schedule) = &slot.schedule {
+                debug!("    Schedule: {}", schedule);
+            }
+            if slot.manifest.weight != 1 {
+                debug!("    Weight: {}", slot.manifest.weight);
+            }
         }
+        let rotation = weighted_rotation(&slots);
 
         // Create the core manager instance.
         let manager_core = Self {
-            config: Arc::clone(&config),
-            slots,
+            config: shared_config,
             banner_files,
-            current_slot_index: 0, // Start with the first slot.
+            current_slot_index: rotation.first().copied().unwrap_or(0), // Start with the first slot.
+            rotation_position: 0,
+            last_shown: vec![None; slots.len()],
+            rotation,
+            slots,
             last_manual_trigger: {
                 let now = Instant::now();
                 let cooldown = config.announcement_manual_trigger_cooldown_seconds;
@@ -205,12 +271,23 @@ impl AnnouncementManager {
         let manager_arc_mutex = Arc::new(Mutex::new(manager_core));
 
         // Get configuration values relevant to auto-cycling.
-        // Acquire a temporary lock to read the number of slots from the newly created manager.
-        let num_slots = manager_arc_mutex.lock().await.slots.len();
+        // Acquire a temporary lock to read the slots from the newly created manager.
+        let (num_slots, has_fixed_times) = {
+            let manager = manager_arc_mutex.lock().await;
+            (
+                manager.slots.len(),
+                manager.slots.iter().any(|slot| {
+                    slot.schedule
+                        .as_ref()
+                        .is_some_and(SlotSchedule::has_fixed_times)
+                }),
+            )
+        };
         let auto_cycle_interval_seconds = config.announcement_auto_cycle_interval_seconds;
 
         // Start the auto-cycle background task if enabled and slots are available.
-        if auto_cycle_interval_seconds > 0 && num_slots > 0 {
+        // Fixed-time slots need the task even when round-robin cycling is off.
+        if (auto_cycle_interval_seconds > 0 || has_fixed_times) && num_slots > 0 {
             info!(
                 "Starting announcement auto-cycle task with interval {} seconds.",
                 auto_cycle_interval_seconds
@@ -288,6 +365,24 @@ impl AnnouncementManager {
                             .into_owned();
                         debug!("Found potential slot directory: {}", slot_id);
 
+                        let manifest = match manifest::load(&path) {
+                            Ok(manifest) => manifest.unwrap_or_default(),
+                            Err(e) => {
+                                error!(
+                                    "Invalid announcement slot manifest, skipping slot {}: {}",
+                                    slot_id, e
+                                );
+                                continue;
+                            }
+                        };
+                        if !manifest.enabled {
+                            info!(
+                                "Announcement slot {} is disabled by its manifest. Skipping.",
+                                slot_id
+                            );
+                            continue;
+                        }
+
                         let mut audio_files = Vec::new();
 
                         // Read files within the audio slot directory.
@@ -339,6 +434,12 @@ impl AnnouncementManager {
                             }
                         }
 
+                        audio_files.sort();
+                        let (audio_files, problems) = manifest.arrange_audio(audio_files);
+                        for problem in problems {
+                            warn!("Announcement slot {}: {}", slot_id, problem);
+                        }
+
                         if audio_files.is_empty() {
                             warn!(
                                 "Slot directory {} contains no supported audio files. Skipping.",
@@ -347,11 +448,11 @@ impl AnnouncementManager {
                             continue;
                         }
 
-                        audio_files.sort();
-
                         slots.push(AnnouncementSlot {
                             id: slot_id,
                             audio_files,
+                            schedule: manifest.schedule.clone(),
+                            manifest,
                         });
                     } else {
                         trace!(
@@ -478,7 +579,10 @@ impl AnnouncementManager {
     /// The index wraps around to 0 after reaching the last slot.
     /// After advancing, it broadcasts the new announcement status.
     fn manual_cooldown_remaining_seconds(&self) -> Option<u64> {
-        let cooldown_seconds = self.config.announcement_manual_trigger_cooldown_seconds;
+        let cooldown_seconds = self
+            .config
+            .get()
+            .announcement_manual_trigger_cooldown_seconds;
         if cooldown_seconds == 0 {
             return None;
         }
@@ -496,14 +600,88 @@ impl AnnouncementManager {
             warn!("Cannot advance slot, no announcement slots available.");
             return;
         }
-        self.auto_cycle_started = true;
-        self.current_slot_index = (self.current_slot_index + 1) % self.slots.len();
-        self.last_auto_cycle_reset = Instant::now();
+        let next_index = if self.rotation.is_empty() {
+            // Every slot has weight 0; step through them in order.
+            (self.current_slot_index + 1) % self.slots.len()
+        } else {
+            // Skip slots outside their schedule or cooldown; if none is eligible, advance anyway.
+            let start = self.rotation_position + 1;
+            let position = self
+                .next_cycled_position(start, Local::now().naive_local())
+                .unwrap_or(start % self.rotation.len());
+            self.rotation_position = position;
+            self.rotation[position]
+        };
         info!(
             "Advanced announcement slot to index {} (ID: {:?}).",
-            self.current_slot_index,
-            self.slots.get(self.current_slot_index).map(|s| &s.id)
+            next_index,
+            self.slots.get(next_index).map(|s| &s.id)
         );
+        self.show_slot(next_index).await;
+    }
+
+    /// Position of the first `rotation` entry from `start` onwards, wrapping
+    /// around, whose slot the round-robin may show at `now`.
+    fn next_cycled_position(&self, start: usize, now: NaiveDateTime) -> Option<usize> {
+        let len = self.rotation.len();
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&position| self.cycles_at(self.rotation[position], now))
+    }
+
+    /// Whether the slot at `index` is within its schedule and off its cooldown.
+    fn cycles_at(&self, index: usize, now: NaiveDateTime) -> bool {
+        self.slots[index]
+            .schedule
+            .as_ref()
+            .is_none_or(|schedule| schedule.cycles_at(now))
+            && self.slot_cooldown_remaining_seconds(index).is_none()
+    }
+
+    /// Seconds until the slot at `index` may play again, if its manifest
+    /// cooldown is still running.
+    fn slot_cooldown_remaining_seconds(&self, index: usize) -> Option<u64> {
+        let cooldown_seconds = self.slots[index].manifest.cooldown_seconds?;
+        let elapsed = self.last_shown[index]?.elapsed();
+        (elapsed < Duration::from_secs(cooldown_seconds))
+            .then(|| cooldown_seconds.saturating_sub(elapsed.as_secs()))
+    }
+
+    /// The slot whose fixed play time falls in `(after, now]`, with the time it
+    /// was due. When several are due together the earliest wins, then the first
+    /// slot in order.
+    fn due_fixed_slot(&self, after: NaiveDateTime, now: NaiveDateTime) -> Option<usize> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let due = slot.schedule.as_ref()?.next_fixed_time(after)?;
+                (due <= now).then_some((due, index))
+            })
+            .min()
+            .map(|(_, index)| index)
+    }
+
+    /// The earliest fixed play time of any slot after `now`.
+    fn next_fixed_time(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.schedule.as_ref()?.next_fixed_time(now))
+            .min()
+    }
+
+    /// Makes the slot at `index` current, restarts the cycle timer and broadcasts.
+    async fn show_slot(&mut self, index: usize) {
+        self.auto_cycle_started = true;
+        self.current_slot_index = index;
+        self.last_auto_cycle_reset = Instant::now();
+        self.last_shown[index] = Some(Instant::now());
+        // Continue the round-robin after the slot shown, wherever it came from.
+        if self.rotation.get(self.rotation_position) != Some(&index) {
+            if let Some(position) = self.rotation.iter().position(|&entry| entry == index) {
+                self.rotation_position = position;
+            }
+        }
         self.broadcast_status().await;
     }
 
@@ -563,16 +741,22 @@ impl AnnouncementManager {
                 remaining_seconds: remaining,
             });
         }
+        if let Some(remaining) = self.slot_cooldown_remaining_seconds(target_index) {
+            warn!(
+                "Announcement slot '{}' is on cooldown. {} seconds remaining.",
+                slot_id, remaining
+            );
+            return Err(ManualTriggerError::CooldownActive {
+                remaining_seconds: remaining,
+            });
+        }
 
         self.last_manual_trigger = Instant::now();
-        self.current_slot_index = target_index;
-        self.last_auto_cycle_reset = Instant::now();
-        self.auto_cycle_started = true;
         info!(
             "Manual trigger activated slot {} (index {}).",
             slot_id, target_index
         );
-        self.broadcast_status().await;
+        self.show_slot(target_index).await;
         Ok(())
     }
 
@@ -590,7 +774,8 @@ impl AnnouncementManager {
         } else {
             None
         };
-        let serve_dir_path = &self.config.serve_dir_path; // Path for static file serving.
+        let config = self.config.get();
+        let serve_dir_path = &config.serve_dir_path; // Path for static file serving.
 
         let current_slot_id = current_slot.map(|s| s.id.clone());
 
@@ -612,12 +797,13 @@ impl AnnouncementManager {
             .collect();
 
         // Calculate cooldown status.
-        let cooldown_seconds = self.config.announcement_manual_trigger_cooldown_seconds;
+        let cooldown_seconds = config.announcement_manual_trigger_cooldown_seconds;
         let cooldown_remaining = self.manual_cooldown_remaining_seconds();
         let cooldown_active = cooldown_remaining.is_some();
         let cooldown_remaining_seconds = cooldown_remaining.unwrap_or(0);
 
         // Build summaries for all available slots (audio only).
+        let languages = config.ordered_supported_language_codes_raw();
         let available_slots = self
             .slots
             .iter()
@@ -628,6 +814,12 @@ impl AnnouncementManager {
                     .iter()
                     .filter_map(|p| self.to_web_accessible_path(p, serve_dir_path))
                     .collect(),
+                schedule: slot.schedule.as_ref().map(ToString::to_string),
+                title: slot.manifest.display_title(&languages),
+                titles: slot.manifest.titles.clone(),
+                description: slot.manifest.description.clone(),
+                weight: slot.manifest.weight,
+                cooldown_seconds: slot.manifest.cooldown_seconds,
             })
             .collect();
 
@@ -635,7 +827,7 @@ impl AnnouncementManager {
             current_slot_id,
             current_audio_playlist,
             current_banner_playlist,
-            banner_cycle_interval_seconds: self.config.banner_rotation_interval_seconds,
+            banner_cycle_interval_seconds: config.banner_rotation_interval_seconds,
             cooldown_seconds,
             cooldown_remaining_seconds,
             cooldown_active,
@@ -664,84 +856,91 @@ impl AnnouncementManager {
 
     /// The background task that automatically cycles the announcement slot.
     ///
-    /// This task runs indefinitely, advancing the slot at the configured interval.
-    /// It acquires a lock on the `AnnouncementManager` to ensure safe mutable access.
+    /// This task runs indefinitely, advancing to the next slot whose schedule
+    /// allows it at the configured interval, and switching to fixed-time slots
+    /// when their time comes. It acquires a lock on the `AnnouncementManager`
+    /// to ensure safe mutable access.
     ///
     /// # Arguments
     /// - `manager_arc`: An `Arc<Mutex<AnnouncementManager>>` to the manager instance.
-    /// - `interval_seconds`: The duration in seconds between automatic slot advancements.
+    /// - `interval_seconds`: The duration in seconds between automatic slot
+    ///   advancements; `0` only plays fixed-time slots.
     async fn run_auto_cycle_task(
         manager_arc: Arc<Mutex<AnnouncementManager>>,
         interval_seconds: u64,
     ) {
-        if interval_seconds == 0 {
-            info!("Auto-cycle interval is 0, task will not run.");
-            return;
+        let interval_duration =
+            (interval_seconds > 0).then(|| Duration::from_secs(interval_seconds));
+        if interval_duration.is_none() {
+            info!("Auto-cycle interval is 0; only fixed-time announcement slots will play.");
         }
-        let interval_duration = Duration::from_secs(interval_seconds);
 
         // Reset the timer when the background task starts so the first cycle waits a full interval.
         {
             let mut manager = manager_arc.lock().await;
             manager.last_auto_cycle_reset = Instant::now();
         }
+        // Fixed times up to here have been handled (or were before startup).
+        let mut fixed_checked_until = Local::now().naive_local();
 
         loop {
-            // Determine how long to sleep based on when the timer was last reset.
-            let sleep_duration = {
-                let manager = manager_arc.lock().await;
-                if manager.slots.is_empty() {
-                    debug!(
-                        "Auto-cycle task found no announcement slots; sleeping for the configured interval."
-                    );
-                    interval_duration
-                } else {
-                    let elapsed = manager.last_auto_cycle_reset.elapsed();
-                    interval_duration.saturating_sub(elapsed)
-                }
-            };
-
-            if !sleep_duration.is_zero() {
-                trace!(
-                    "Auto-cycle timer sleeping for {:?} before checking announcements again.",
-                    sleep_duration
-                );
-                sleep(sleep_duration).await;
-                continue;
-            }
-
-            debug!("Auto-cycle interval elapsed; attempting to advance announcement slot.");
+            let now = Local::now().naive_local();
             let mut manager = manager_arc.lock().await;
 
-            if manager.slots.is_empty() {
-                debug!(
-                    "Auto-cycle wake found no available slots; sleeping for the configured interval."
+            if let Some(index) = manager.due_fixed_slot(fixed_checked_until, now) {
+                fixed_checked_until = now;
+                info!(
+                    "Playing announcement slot '{}' at its fixed time.",
+                    manager.slots[index].id
                 );
-                drop(manager);
-                sleep(interval_duration).await;
+                manager.show_slot(index).await;
                 continue;
             }
-
-            if manager.last_auto_cycle_reset.elapsed() < interval_duration {
-                trace!(
-                    "Auto-cycle timer was reset by a manual action after waking; restarting wait."
-                );
-                drop(manager);
-                continue;
+            fixed_checked_until = now;
+
+            let mut sleep_duration = Self::SCHEDULE_RECHECK_INTERVAL;
+            if let Some(interval_duration) = interval_duration {
+                let remaining =
+                    interval_duration.saturating_sub(manager.last_auto_cycle_reset.elapsed());
+                if !remaining.is_zero() {
+                    if manager.slots.iter().all(|slot| slot.schedule.is_none()) {
+                        sleep_duration = remaining;
+                    } else {
+                        sleep_duration = sleep_duration.min(remaining);
+                    }
+                } else {
+                    // The first tick shows the current slot; later ticks move on.
+                    let start = if manager.auto_cycle_started {
+                        manager.rotation_position + 1
+                    } else {
+                        manager.rotation_position
+                    };
+                    match manager.next_cycled_position(start, now) {
+                        Some(position) => {
+                            manager.rotation_position = position;
+                            let index = manager.rotation[position];
+                            manager.show_slot(index).await;
+                            debug!("Auto-cycle advanced announcement slot and reset timer.");
+                            continue;
+                        }
+                        None => trace!(
+                            "No announcement slot is within its schedule; checking again later."
+                        ),
+                    }
+                }
             }
-
-            if !manager.auto_cycle_started {
-                manager.auto_cycle_started = true;
-                manager.last_auto_cycle_reset = Instant::now();
-                manager.broadcast_status().await;
-                debug!("Auto-cycle started; broadcasting initial announcement slot.");
-                drop(manager);
-                continue;
+            if let Some(next_fixed) = manager.next_fixed_time(now) {
+                if let Ok(until_fixed) = (next_fixed - now).to_std() {
+                    sleep_duration = sleep_duration.min(until_fixed);
+                }
             }
-
-            manager.advance_slot().await;
-            debug!("Auto-cycle advanced announcement slot and reset timer.");
             drop(manager);
+
+            trace!(
+                "Auto-cycle timer sleeping for {:?} before checking announcements again.",
+                sleep_duration
+            );
+            sleep(sleep_duration).await;
         }
     }
 
@@ -792,3 +991,65 @@ impl AnnouncementManager {
         }
     }
 }
+
+/// Auto-cycle order of `slots`: each slot index repeated by its manifest
+/// weight, spread out over the round (smooth weighted round-robin). With equal
+/// weights this is simply every slot in order.
+fn weighted_rotation(slots: &[AnnouncementSlot]) -> Vec<usize> {
+    let weights: Vec<i64> = slots
+        .iter()
+        .map(|slot| i64::from(slot.manifest.weight))
+        .collect();
+    let total: i64 = weights.iter().sum();
+    let mut credit = vec![0i64; weights.len()];
+    let mut rotation = Vec::new();
+    for _ in 0..total {
+        for (credit, weight) in credit.iter_mut().zip(&weights) {
+            *credit += weight;
+        }
+        // The first slot wins ties, so equal weights keep the slot order.
+        let (chosen, _) = credit
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, credit)| **credit)
+            .expect("a positive total weight implies at least one slot");
+        credit[chosen] -= total;
+        rotation.push(chosen);
+    }
+    rotation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(id: &str, weight: u32) -> AnnouncementSlot {
+        AnnouncementSlot {
+            id: id.to_string(),
+            audio_files: Vec::new(),
+            schedule: None,
+            manifest: SlotManifest {
+                weight,
+                ..SlotManifest::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_weighted_rotation_spreads_heavier_slots() {
+        assert_eq!(
+            weighted_rotation(&[slot("a", 1), slot("b", 1), slot("c", 1)]),
+            [0, 1, 2]
+        );
+        assert_eq!(
+            weighted_rotation(&[slot("a", 2), slot("b", 1), slot("c", 1)]),
+            [0, 1, 2, 0]
+        );
+        assert_eq!(
+            weighted_rotation(&[slot("a", 0), slot("b", 3), slot("c", 1)]),
+            [1, 1, 2, 1]
+        );
+        assert!(weighted_rotation(&[slot("a", 0)]).is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+// src/announcements/manifest.rs
+
+//! Optional `slot.toml` or `slot.json` describing an announcement slot.
+//!
+//! ```toml
+//! title = { th = "กรุณาเตรียมบัตรประชาชน", en-GB = "Please have your Thai ID ready" }
+//! description = "Played during the morning registration rush"
+//! enabled = true
+//! weight = 2                  # plays twice per round
+//! cooldown_seconds = 600      # at most once every ten minutes
+//! schedule = "mon-fri 07:00-10:00"
+//! audio = ["th.mp3", "en.mp3"]
+//! ```
+//!
+//! Every key is optional; a slot without a manifest keeps the defaults: titled
+//! by its directory name, enabled, weight 1, no cooldown or schedule, and all
+//! audio files in name order.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::schedule::SlotSchedule;
+
+/// Manifest file names, in order of precedence.
+pub const MANIFEST_FILES: [&str; 2] = ["slot.toml", "slot.json"];
+
+/// Highest accepted `weight`; keeps a round short enough to reason about.
+pub const MAX_SLOT_WEIGHT: u32 = 20;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawManifest {
+    title: Option<RawTitle>,
+    description: Option<String>,
+    enabled: Option<bool>,
+    weight: Option<u32>,
+    cooldown_seconds: Option<u64>,
+    schedule: Option<String>,
+    audio: Option<Vec<String>>,
+}
+
+/// One title for every language, or a title per language code.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawTitle {
+    Text(String),
+    PerLanguage(BTreeMap<String, String>),
+}
+
+/// A validated slot manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotManifest {
+    /// Title shown for every language.
+    pub title: Option<String>,
+    /// Titles by language code, e.g. `th` and `en-GB`.
+    pub titles: BTreeMap<String, String>,
+    pub description: Option<String>,
+    /// Disabled slots are left out as if their directory did not exist.
+    pub enabled: bool,
+    /// How many times the slot plays per round; `0` leaves it out of the
+    /// round-robin, so it only plays when triggered or at a fixed time.
+    pub weight: u32,
+    /// Least time between two plays of the slot.
+    pub cooldown_seconds: Option<u64>,
+    pub schedule: Option<SlotSchedule>,
+    /// Audio file names in playback order; `None` plays every file in name order.
+    pub audio: Option<Vec<String>>,
+}
+
+impl Default for SlotManifest {
+    fn default() -> Self {
+        Self {
+            title: None,
+            titles: BTreeMap::new(),
+            description: None,
+            enabled: true,
+            weight: 1,
+            cooldown_seconds: None,
+            schedule: None,
+            audio: None,
+        }
+    }
+}
+
+/// Reads the manifest of the slot in `slot_dir`, if it has one.
+///
+/// The error names the manifest file and what is wrong with it.
+pub fn load(slot_dir: &Path) -> Result<Option<SlotManifest>, String> {
+    let present: Vec<PathBuf> = MANIFEST_FILES
+        .iter()
+        .map(|name| slot_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect();
+    let path = match present.as_slice() {
+        [] => return Ok(None),
+        [path] => path,
+        _ => {
+            return Err(format!(
+                "{:?} has both {}; keep one",
+                slot_dir,
+                MANIFEST_FILES.join(" and ")
+            ))
+        }
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{:?}: {}", path, e))?;
+    let raw: Result<RawManifest, String> = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&text).map_err(|e| e.to_string())
+    };
+    raw.and_then(RawManifest::validate)
+        .map(Some)
+        .map_err(|e| format!("{:?}: {}", path, e.trim_end()))
+}
+
+impl RawManifest {
+    fn validate(self) -> Result<SlotManifest, String> {
+        let mut manifest = SlotManifest {
+            description: self.description.filter(|text| !text.trim().is_empty()),
+            enabled: self.enabled.unwrap_or(true),
+            weight: self.weight.unwrap_or(1),
+            cooldown_seconds: self.cooldown_seconds.filter(|&seconds| seconds > 0),
+            ..SlotManifest::default()
+        };
+        match self.title {
+            Some(RawTitle::Text(title)) if !title.trim().is_empty() => {
+                manifest.title = Some(title.trim().to_string())
+            }
+            Some(RawTitle::PerLanguage(titles)) => {
+                manifest.titles = titles
+                    .into_iter()
+                    .map(|(code, title)| (code.trim().to_string(), title.trim().to_string()))
+                    .filter(|(code, title)| !code.is_empty() && !title.is_empty())
+                    .collect()
+            }
+            _ => {}
+        }
+        if manifest.weight > MAX_SLOT_WEIGHT {
+            return Err(format!(
+                "weight: {} is above the maximum of {}",
+                manifest.weight, MAX_SLOT_WEIGHT
+            ));
+        }
+        if let Some(schedule) = self.schedule {
+            manifest.schedule =
+                Some(SlotSchedule::parse(&schedule).map_err(|e| format!("schedule: {}", e))?);
+        }
+        if let Some(audio) = self.audio {
+            if audio.is_empty() {
+                return Err("audio: list at least one file, or leave the key out".to_string());
+            }
+            if let Some(name) = audio.iter().find(|name| name.contains(['/', '\\'])) {
+                return Err(format!(
+                    "audio: '{}' must be a file name in the slot directory",
+                    name
+                ));
+            }
+            manifest.audio = Some(audio);
+        }
+        Ok(manifest)
+    }
+}
+
+impl SlotManifest {
+    /// Title for an operator using the first of `languages`: the common title,
+    /// else the title in the first language that has one, else any title.
+    pub fn display_title(&self, languages: &[String]) -> Option<String> {
+        self.title.clone().or_else(|| {
+            languages
+                .iter()
+                .find_map(|code| self.titles.get(code))
+                .or_else(|| self.titles.values().next())
+                .cloned()
+        })
+    }
+
+    /// Puts a slot's audio `files` into playback order.
+    ///
+    /// With an `audio` list, only the listed files play, in that order; the
+    /// returned problems name listed files that do not exist and files that
+    /// are not listed.
+    pub fn arrange_audio(&self, files: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<String>) {
+        let Some(order) = &self.audio else {
+            return (files, Vec::new());
+        };
+        let file_name = |path: &PathBuf| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let mut problems = Vec::new();
+        let mut arranged = Vec::new();
+        for name in order {
+            match files.iter().find(|file| file_name(file) == *name) {
+                Some(file) => arranged.push(file.clone()),
+                None => problems.push(format!("audio lists '{}', which is not in the slot", name)),
+            }
+        }
+        for file in &files {
+            if !order.contains(&file_name(file)) {
+                problems.push(format!(
+                    "'{}' is not listed in audio and will not play",
+                    file_name(file)
+                ));
+            }
+        }
+        (arranged, problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("queue-manifest-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_toml_and_json_manifests() {
+        let dir = slot_dir("formats");
+        assert_eq!(load(&dir).unwrap(), None);
+
+        std::fs::write(
+            dir.join("slot.toml"),
+            "title = { th = \"บัตรประชาชน\", en-GB = \"Thai ID\" }\nweight = 0\nschedule = \"mon-fri 07:00-10:00\"\n",
+        )
+        .unwrap();
+        let manifest = load(&dir).unwrap().unwrap();
+        assert_eq!(manifest.weight, 0);
+        assert!(manifest.enabled);
+        assert_eq!(
+            manifest.display_title(&["en-GB".to_string(), "th".to_string()]),
+            Some("Thai ID".to_string())
+        );
+        assert_eq!(
+            manifest.schedule.map(|schedule| schedule.to_string()),
+            Some("mon-fri 07:00-10:00".to_string())
+        );
+
+        std::fs::write(dir.join("slot.json"), "{}").unwrap();
+        assert!(load(&dir).unwrap_err().contains("keep one"));
+        std::fs::remove_file(dir.join("slot.toml")).unwrap();
+        std::fs::write(
+            dir.join("slot.json"),
+            r#"{"title": "Lunch", "enabled": false}"#,
+        )
+        .unwrap();
+        let manifest = load(&dir).unwrap().unwrap();
+        assert_eq!(manifest.display_title(&[]), Some("Lunch".to_string()));
+        assert!(!manifest.enabled);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_manifests() {
+        let dir = slot_dir("invalid");
+        for (text, expected) in [
+            ("weight = 99\n", "weight: 99 is above the maximum"),
+            ("schedule = \"noon\"\n", "schedule: 'noon' is not a weekday"),
+            ("audio = [\"../x.mp3\"]\n", "must be a file name"),
+            ("titel = \"x\"\n", "unknown field `titel`"),
+        ] {
+            std::fs::write(dir.join("slot.toml"), text).unwrap();
+            let error = load(&dir).unwrap_err();
+            assert!(error.contains(expected), "{}", error);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_arrange_audio_follows_the_listed_order() {
+        let files = vec![PathBuf::from("slot/a.mp3"), PathBuf::from("slot/b.mp3")];
+        assert_eq!(
+            SlotManifest::default().arrange_audio(files.clone()).0,
+            files
+        );
+
+        let manifest = SlotManifest {
+            audio: Some(vec!["b.mp3".to_string(), "c.mp3".to_string()]),
+            ..SlotManifest::default()
+        };
+        let (arranged, problems) = manifest.arrange_audio(files);
+        assert_eq!(arranged, [PathBuf::from("slot/b.mp3")]);
+        assert_eq!(
+            problems,
+            [
+                "audio lists 'c.mp3', which is not in the slot",
+                "'a.mp3' is not listed in audio and will not play"
+            ]
+        );
+    }
+}
@@ -18,6 +18,12 @@
 // (or in a `manager/mod.rs` directory structure).
 pub mod manager;
 
+// Optional per-slot `slot.toml`/`slot.json` manifests.
+pub mod manifest;
+
+// Slot schedules: weekdays, time windows, date ranges and fixed play times.
+pub mod schedule;
+
 // Re-export the key structs from the `manager` submodule.
 // This makes these structs directly accessible from the `announcements` module,
 // simplifying imports in other parts of the application. For example:
@@ -32,3 +38,5 @@ pub use manager::AnnouncementSlot;
 pub use manager::AnnouncementSlotSummary;
 pub use manager::AnnouncementStatus;
 pub use manager::ManualTriggerError;
+pub use manifest::SlotManifest;
+pub use schedule::SlotSchedule;
@@ -0,0 +1,233 @@
+// src/announcements/schedule.rs
+
+//! When an announcement slot may play.
+//!
+//! A schedule is a whitespace-separated list of terms, all optional:
+//!
+//! - weekdays: `mon-fri`, `sat,sun`
+//! - time windows: `07:00-10:00,13:00-14:00` (end exclusive; `22:00-02:00`
+//!   runs past midnight)
+//! - dates: `2026-01-01..2026-03-31`, `2026-12-24` (inclusive)
+//! - fixed times: `at 12:00,15:30`
+//!
+//! For example `mon-fri 07:00-10:00` cycles a slot on weekday mornings only,
+//! and `mon-sat at 12:00` plays it once at noon. A slot with fixed times but
+//! no windows is only played at those times, never by the round-robin.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Serialize, Serializer};
+
+/// Days the lookahead for the next fixed time covers; enough to reach the
+/// next occurrence of any weekday/date combination within a year.
+const FIXED_TIME_LOOKAHEAD_DAYS: i64 = 367;
+
+/// A parsed slot schedule; see the module documentation for the syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotSchedule {
+    weekdays: Vec<Weekday>,
+    dates: Vec<RangeInclusive<NaiveDate>>,
+    windows: Vec<(NaiveTime, NaiveTime)>,
+    fixed_times: Vec<NaiveTime>,
+    source: String,
+}
+
+impl SlotSchedule {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut schedule = Self {
+            weekdays: Vec::new(),
+            dates: Vec::new(),
+            windows: Vec::new(),
+            fixed_times: Vec::new(),
+            source: raw.split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+        let mut terms = raw.split_whitespace();
+        while let Some(term) = terms.next() {
+            if term.eq_ignore_ascii_case("at") {
+                let times = terms
+                    .next()
+                    .ok_or_else(|| "'at' must be followed by times like 12:00".to_string())?;
+                for time in times.split(',') {
+                    schedule.fixed_times.push(parse_time(time)?);
+                }
+            } else if term.contains(':') {
+                for window in term.split(',') {
+                    let (start, end) = window
+                        .split_once('-')
+                        .ok_or_else(|| format!("'{}' is not a window like 07:00-10:00", window))?;
+                    let (start, end) = (parse_time(start)?, parse_time(end)?);
+                    if start == end {
+                        return Err(format!("window '{}' is empty", window));
+                    }
+                    schedule.windows.push((start, end));
+                }
+            } else if term.starts_with(|c: char| c.is_ascii_digit()) {
+                for range in term.split(',') {
+                    let (start, end) = match range.split_once("..") {
+                        Some((start, end)) => (parse_date(start)?, parse_date(end)?),
+                        None => (parse_date(range)?, parse_date(range)?),
+                    };
+                    if start > end {
+                        return Err(format!("date range '{}' ends before it starts", range));
+                    }
+                    schedule.dates.push(start..=end);
+                }
+            } else {
+                for days in term.split(',') {
+                    schedule.weekdays.extend(parse_weekdays(days)?);
+                }
+            }
+        }
+        if schedule.source.is_empty() {
+            return Err("schedule is empty".to_string());
+        }
+        schedule.fixed_times.sort();
+        schedule.fixed_times.dedup();
+        Ok(schedule)
+    }
+
+    /// Whether the round-robin may show the slot at `now`.
+    pub fn cycles_at(&self, now: NaiveDateTime) -> bool {
+        if self.windows.is_empty() {
+            return self.fixed_times.is_empty() && self.runs_on(now.date());
+        }
+        let time = now.time();
+        self.windows.iter().any(|&(start, end)| {
+            if start < end {
+                self.runs_on(now.date()) && start <= time && time < end
+            } else if time >= start {
+                self.runs_on(now.date())
+            } else {
+                // The part after midnight belongs to the day the window opened.
+                time < end && now.date().pred_opt().is_some_and(|day| self.runs_on(day))
+            }
+        })
+    }
+
+    /// The first fixed play time strictly after `after`, if there is one.
+    pub fn next_fixed_time(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.fixed_times.is_empty() {
+            return None;
+        }
+        (0..FIXED_TIME_LOOKAHEAD_DAYS)
+            .filter_map(|offset| after.date().checked_add_signed(Duration::days(offset)))
+            .filter(|day| self.runs_on(*day))
+            .flat_map(|day| self.fixed_times.iter().map(move |time| day.and_time(*time)))
+            .find(|at| *at > after)
+    }
+
+    pub fn has_fixed_times(&self) -> bool {
+        !self.fixed_times.is_empty()
+    }
+
+    fn runs_on(&self, day: NaiveDate) -> bool {
+        (self.weekdays.is_empty() || self.weekdays.contains(&day.weekday()))
+            && (self.dates.is_empty() || self.dates.iter().any(|range| range.contains(&day)))
+    }
+}
+
+impl fmt::Display for SlotSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Serializes as the schedule text, e.g. `"mon-fri 07:00-10:00"`.
+impl Serialize for SlotSchedule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+fn parse_time(raw: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(raw, "%H:%M")
+        .map_err(|_| format!("'{}' is not a time like 07:30", raw))
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|_| format!("'{}' is not a date like 2026-01-31", raw))
+}
+
+/// Parses `mon` or a wrapping range such as `fri-mon`.
+fn parse_weekdays(raw: &str) -> Result<Vec<Weekday>, String> {
+    let parse = |day: &str| {
+        day.parse::<Weekday>()
+            .map_err(|_| format!("'{}' is not a weekday like mon or mon-fri", raw))
+    };
+    match raw.split_once('-') {
+        Some((first, last)) => {
+            let (mut day, last) = (parse(first)?, parse(last)?);
+            let mut days = vec![day];
+            while day != last {
+                day = day.succ();
+                days.push(day);
+            }
+            Ok(days)
+        }
+        None => Ok(vec![parse(raw)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        parse_date(date)
+            .unwrap()
+            .and_time(parse_time(time).unwrap())
+    }
+
+    #[test]
+    fn test_weekday_window_schedule() {
+        // 2026-10-19 is a Monday.
+        let schedule = SlotSchedule::parse("mon-fri  07:00-10:00").unwrap();
+        assert_eq!(schedule.to_string(), "mon-fri 07:00-10:00");
+        assert!(schedule.cycles_at(at("2026-10-19", "07:00")));
+        assert!(!schedule.cycles_at(at("2026-10-19", "10:00")));
+        assert!(!schedule.cycles_at(at("2026-10-24", "08:00")));
+        assert!(!schedule.has_fixed_times());
+    }
+
+    #[test]
+    fn test_overnight_window_belongs_to_opening_day() {
+        let schedule = SlotSchedule::parse("fri 22:00-02:00").unwrap();
+        assert!(schedule.cycles_at(at("2026-10-23", "23:00")));
+        assert!(schedule.cycles_at(at("2026-10-24", "01:00")));
+        assert!(!schedule.cycles_at(at("2026-10-23", "01:00")));
+    }
+
+    #[test]
+    fn test_fixed_times_respect_days_and_dates() {
+        let schedule =
+            SlotSchedule::parse("sat-sun 2026-10-01..2026-10-31 at 15:30,12:00").unwrap();
+        assert!(!schedule.cycles_at(at("2026-10-24", "12:00")));
+        assert_eq!(
+            schedule.next_fixed_time(at("2026-10-19", "09:00")),
+            Some(at("2026-10-24", "12:00"))
+        );
+        assert_eq!(
+            schedule.next_fixed_time(at("2026-10-24", "12:00")),
+            Some(at("2026-10-24", "15:30"))
+        );
+        assert_eq!(schedule.next_fixed_time(at("2026-10-31", "16:00")), None);
+    }
+
+    #[test]
+    fn test_rejects_malformed_schedules() {
+        for raw in [
+            "",
+            "at",
+            "noon",
+            "25:00-26:00",
+            "07:00",
+            "2026-02-30",
+            "at 7pm",
+        ] {
+            assert!(SlotSchedule::parse(raw).is_err(), "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use tracing::{debug, error, info, warn};
+
+use crate::api::rooms::Room;
+use crate::config::ConfigReloadReport;
+use crate::settings::{Settings, SettingsError, SettingsOverrides};
+use crate::AppState;
+
+/// Response of the `/api/admin/settings` routes.
+#[derive(Serialize, Debug)]
+pub struct SettingsResponse {
+    /// Settings in effect.
+    pub settings: Settings,
+    /// Fields changed through this API, overriding the configured values.
+    pub overrides: SettingsOverrides,
+    /// Whether changes survive a restart (`SETTINGS_OVERRIDES_PATH` is set).
+    pub persisted: bool,
+}
+
+/// Axum route re-reading the configuration of every room, like `SIGHUP`.
+///
+/// Responds 200 with the applied changes, or 422 with the validation errors
+/// when the new configuration was rejected and the running one kept.
+pub async fn reload_config(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ConfigReloadReport>) {
+    info!("POST /api/admin/config/reload: Reloading configuration");
+    let report = state.reload_config().await;
+    let status = if report.applied {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    (status, Json(report))
+}
+
+/// Axum route returning the room's adjustable settings.
+pub async fn get_settings(
+    State(state): State<Arc<AppState>>,
+    Room(room): Room,
+) -> Json<SettingsResponse> {
+    debug!("GET /api/admin/settings: Room '{}'", room.id);
+    Json(SettingsResponse {
+        settings: Settings::from_config(&room.config.get()),
+        overrides: state.settings.overrides(&room.id).await,
+        persisted: state.settings.is_persisted(),
+    })
+}
+
+/// Axum route changing the room's adjustable settings.
+///
+/// Only the fields present in the body change. Responds 422 with every
+/// validation error when the settings would be invalid, leaving them as they were.
+pub async fn update_settings(
+    State(state): State<Arc<AppState>>,
+    Room(room): Room,
+    Json(patch): Json<SettingsOverrides>,
+) -> Result<Json<SettingsResponse>, (StatusCode, String)> {
+    info!("PUT /api/admin/settings: Room '{}': {:?}", room.id, patch);
+    match state.update_settings(&room, patch).await {
+        Ok(settings) => Ok(Json(SettingsResponse {
+            settings,
+            overrides: state.settings.overrides(&room.id).await,
+            persisted: state.settings.is_persisted(),
+        })),
+        Err(SettingsError::Invalid(errors)) => {
+            warn!("Settings of room '{}' rejected: {:?}", room.id, errors);
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("\n")))
+        }
+        Err(e) => {
+            error!("Failed to save settings of room '{}': {}", room.id, e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
+    }
+}
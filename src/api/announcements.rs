@@ -1,13 +1,11 @@
-use std::sync::Arc;
-
-use axum::extract::{Path, State};
+use axum::extract::Path;
 use axum::http::StatusCode;
 use axum::Json;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
 use crate::announcements::{self, ManualTriggerError};
-use crate::AppState;
+use crate::api::rooms::Room;
 
 /// Standard error response payload for announcement endpoints.
 #[derive(Serialize, Debug)]
@@ -17,8 +15,14 @@ pub struct ErrorResponse {
     remaining_seconds: Option<u64>,
 }
 
+/// Path parameters of `POST /announcements/trigger/:slot_id`.
+#[derive(Deserialize, Debug)]
+pub struct SlotPath {
+    slot_id: String,
+}
+
 pub async fn get_announcement_status(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
 ) -> Json<announcements::manager::AnnouncementStatus> {
     info!("GET /api/announcements/status: Fetching announcement status.");
     let status = state.announcements.current_status().await;
@@ -27,7 +31,7 @@ pub async fn get_announcement_status(
 }
 
 pub async fn manual_advance_announcement(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
 ) -> Result<(StatusCode, String), (StatusCode, Json<ErrorResponse>)> {
     info!("POST /api/announcements/next: Triggering manual announcement advancement.");
     match state.announcements.manual_advance().await {
@@ -46,8 +50,8 @@ pub async fn manual_advance_announcement(
 }
 
 pub async fn manual_trigger_specific_announcement(
-    State(state): State<Arc<AppState>>,
-    Path(slot_id): Path<String>,
+    Room(state): Room,
+    Path(SlotPath { slot_id }): Path<SlotPath>,
 ) -> Result<(StatusCode, String), (StatusCode, Json<ErrorResponse>)> {
     info!(
         "POST /api/announcements/trigger/{}: Triggering manual announcement by slot ID.",
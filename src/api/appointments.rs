@@ -0,0 +1,100 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::api::rooms::Room;
+use crate::application::tickets::IssuedTicket;
+use crate::application::AppointmentService;
+use crate::appointments::{
+    csv::parse_appointments_csv, Appointment, AppointmentError, AppointmentInput,
+};
+use crate::tickets::TicketError;
+
+/// Path parameters of routes addressing a single appointment.
+#[derive(Deserialize, Debug)]
+pub struct AppointmentPath {
+    id: String,
+}
+
+/// Response returned after importing appointments.
+#[derive(Serialize, Debug)]
+pub struct ImportAppointmentsResponse {
+    pub imported: usize,
+}
+
+fn error_response(err: AppointmentError) -> (StatusCode, String) {
+    let status = match &err {
+        AppointmentError::InvalidRecord(_) => StatusCode::BAD_REQUEST,
+        AppointmentError::NotFound(_) => StatusCode::NOT_FOUND,
+        AppointmentError::AlreadyCheckedIn(_) => StatusCode::CONFLICT,
+        AppointmentError::Ticket(TicketError::UnknownService(_)) => StatusCode::BAD_REQUEST,
+        AppointmentError::Ticket(TicketError::NoServices) => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, err.to_string())
+}
+
+/// Axum route listing appointments ordered by time.
+pub async fn list_appointments(Room(state): Room) -> Json<Vec<Appointment>> {
+    debug!("GET /api/appointments: Listing appointments.");
+    Json(state.appointments.list())
+}
+
+/// Axum route importing appointments from a JSON array.
+pub async fn import_appointments(
+    Room(state): Room,
+    Json(inputs): Json<Vec<AppointmentInput>>,
+) -> Result<Json<ImportAppointmentsResponse>, (StatusCode, String)> {
+    info!(
+        "POST /api/appointments: Importing {} appointments.",
+        inputs.len()
+    );
+    import(&state.appointments, inputs)
+}
+
+/// Axum route importing appointments from a CSV document (see README).
+pub async fn import_appointments_csv(
+    Room(state): Room,
+    body: String,
+) -> Result<Json<ImportAppointmentsResponse>, (StatusCode, String)> {
+    info!(
+        "POST /api/appointments/csv: Importing {} bytes of CSV.",
+        body.len()
+    );
+    let inputs = parse_appointments_csv(&body).map_err(|err| {
+        warn!("Appointment CSV rejected: {}", err);
+        error_response(err)
+    })?;
+    import(&state.appointments, inputs)
+}
+
+fn import(
+    appointments: &AppointmentService,
+    inputs: Vec<AppointmentInput>,
+) -> Result<Json<ImportAppointmentsResponse>, (StatusCode, String)> {
+    appointments
+        .import(inputs)
+        .map(|imported| Json(ImportAppointmentsResponse { imported }))
+        .map_err(|err| {
+            warn!("Appointment import rejected: {}", err);
+            error_response(err)
+        })
+}
+
+/// Axum route checking in an appointment and issuing its ticket.
+pub async fn check_in_appointment(
+    Room(state): Room,
+    Path(AppointmentPath { id }): Path<AppointmentPath>,
+) -> Result<(StatusCode, Json<IssuedTicket>), (StatusCode, String)> {
+    info!("POST /api/appointments/{}/check-in", id);
+    state
+        .appointments
+        .check_in(&id)
+        .await
+        .map(|issued| (StatusCode::CREATED, Json(issued)))
+        .map_err(|err| {
+            warn!("Check-in of appointment '{}' rejected: {}", id, err);
+            error_response(err)
+        })
+}
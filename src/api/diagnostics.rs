@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use tracing::debug;
+
+use crate::diagnostics::{self, Diagnostics};
+use crate::AppState;
+
+/// Axum route reporting the server's readiness checks together with uptime,
+/// TTS cache usage and the state of every room.
+pub async fn get_diagnostics(State(state): State<Arc<AppState>>) -> Json<Diagnostics> {
+    debug!("GET /api/diagnostics: Collecting diagnostics.");
+    Json(diagnostics::collect(state).await)
+}
@@ -0,0 +1,200 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, Path};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::Json;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use crate::api::rooms::Room;
+use crate::displays::{
+    validate_display_id, validate_zone, DisplayCommand, DisplayCommandError, DisplayCommandRecord,
+    DisplayCommandStatus, DisplayInfo, DisplayTarget,
+};
+
+/// Path parameters of routes addressing a single display.
+#[derive(Deserialize, Debug)]
+pub struct DisplayPath {
+    display_id: String,
+}
+
+/// Path parameters of routes addressing a single display command.
+#[derive(Deserialize, Debug)]
+pub struct CommandPath {
+    command_id: String,
+}
+
+/// Request body for issuing a display command.
+///
+/// Example: `{"target":{"scope":"zone","id":"lobby"},"command":{"action":"mute"}}`.
+/// Omitting `target` addresses every display.
+#[derive(Deserialize, Debug)]
+pub struct IssueDisplayCommandRequest {
+    #[serde(default = "default_command_target")]
+    pub target: DisplayTarget,
+    pub command: DisplayCommand,
+}
+
+fn default_command_target() -> DisplayTarget {
+    DisplayTarget::All
+}
+
+/// Request body sent by a display to acknowledge a command.
+#[derive(Deserialize, Debug)]
+pub struct AcknowledgeDisplayCommandRequest {
+    pub display_id: String,
+    #[serde(default = "default_ack_status")]
+    pub status: DisplayCommandStatus,
+    pub message: Option<String>,
+}
+
+fn default_ack_status() -> DisplayCommandStatus {
+    DisplayCommandStatus::Ok
+}
+
+/// Axum route listing every display that has connected since start-up.
+pub async fn list_displays(Room(state): Room) -> Json<Vec<DisplayInfo>> {
+    debug!("GET /api/displays: Listing known displays.");
+    Json(state.displays.list())
+}
+
+/// Axum route for an explicit heartbeat from a display.
+pub async fn display_heartbeat(
+    Room(state): Room,
+    Path(DisplayPath { display_id }): Path<DisplayPath>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let display_id = validate_display_id(&display_id).map_err(|message| {
+        warn!("Heartbeat rejected: {}", message);
+        (StatusCode::BAD_REQUEST, message)
+    })?;
+
+    if state.displays.heartbeat(&display_id) {
+        debug!("Heartbeat recorded for display '{}'.", display_id);
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        info!(
+            "Heartbeat received for display '{}' which has not connected yet.",
+            display_id
+        );
+        Err((
+            StatusCode::NOT_FOUND,
+            format!("Display '{}' is not registered.", display_id),
+        ))
+    }
+}
+
+/// Axum route issuing a remote control command to one display, a zone or all displays.
+pub async fn issue_display_command(
+    Room(state): Room,
+    Json(payload): Json<IssueDisplayCommandRequest>,
+) -> Result<(StatusCode, Json<DisplayCommandRecord>), (StatusCode, String)> {
+    info!(
+        "POST /api/displays/commands: {:?} for {}.",
+        payload.command, payload.target
+    );
+    let target = match payload.target {
+        DisplayTarget::All => DisplayTarget::All,
+        DisplayTarget::Display(id) => DisplayTarget::Display(
+            validate_display_id(&id).map_err(|message| (StatusCode::BAD_REQUEST, message))?,
+        ),
+        DisplayTarget::Zone(zone) => DisplayTarget::Zone(
+            validate_zone(&zone).map_err(|message| (StatusCode::BAD_REQUEST, message))?,
+        ),
+    };
+
+    state
+        .displays
+        .issue_command(target, payload.command)
+        .map(|record| (StatusCode::ACCEPTED, Json(record)))
+        .map_err(command_error_response)
+}
+
+/// Axum route listing recently issued display commands with their acknowledgements.
+pub async fn list_display_commands(Room(state): Room) -> Json<Vec<DisplayCommandRecord>> {
+    debug!("GET /api/displays/commands: Listing display commands.");
+    Json(state.displays.commands())
+}
+
+/// Axum route returning a single tracked display command.
+pub async fn get_display_command(
+    Room(state): Room,
+    Path(CommandPath { command_id }): Path<CommandPath>,
+) -> Result<Json<DisplayCommandRecord>, (StatusCode, String)> {
+    state
+        .displays
+        .command(&command_id)
+        .map(Json)
+        .ok_or_else(|| command_error_response(DisplayCommandError::CommandNotFound(command_id)))
+}
+
+/// Axum route for a display acknowledging a command it received over SSE.
+pub async fn acknowledge_display_command(
+    Room(state): Room,
+    Path(CommandPath { command_id }): Path<CommandPath>,
+    Json(payload): Json<AcknowledgeDisplayCommandRequest>,
+) -> Result<Json<DisplayCommandRecord>, (StatusCode, String)> {
+    let display_id = validate_display_id(&payload.display_id)
+        .map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+    debug!(
+        "POST /api/displays/commands/{}/ack from display '{}' ({:?}).",
+        command_id, display_id, payload.status
+    );
+
+    state
+        .displays
+        .acknowledge_command(&command_id, &display_id, payload.status, payload.message)
+        .map(Json)
+        .map_err(command_error_response)
+}
+
+fn command_error_response(err: DisplayCommandError) -> (StatusCode, String) {
+    let status = match &err {
+        DisplayCommandError::InvalidCommand(_) | DisplayCommandError::NotTargeted { .. } => {
+            StatusCode::BAD_REQUEST
+        }
+        DisplayCommandError::UnknownDisplay(_)
+        | DisplayCommandError::EmptyZone(_)
+        | DisplayCommandError::CommandNotFound(_) => StatusCode::NOT_FOUND,
+    };
+    warn!("Display command request rejected: {}", err);
+    (status, err.to_string())
+}
+
+/// Returns the `User-Agent` header as an owned string, if present and valid UTF-8.
+pub(crate) fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Client address for the display registry: the socket peer, or, when the
+/// peer is one of `trusted_proxies`, the nearest `X-Forwarded-For` hop that is
+/// not itself a trusted proxy. Other peers cannot choose the recorded address.
+pub(crate) fn client_ip(
+    headers: &HeaderMap,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    trusted_proxies: &[IpAddr],
+) -> Option<String> {
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip())?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer.to_string());
+    }
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .collect();
+    let client = hops
+        .iter()
+        .rev()
+        .find(|hop| {
+            hop.parse::<IpAddr>()
+                .map_or(true, |address| !trusted_proxies.contains(&address))
+        })
+        .or(hops.first());
+    Some(client.map_or_else(|| peer.to_string(), |hop| hop.to_string()))
+}
@@ -1,40 +1,140 @@
 use std::convert::Infallible;
-use std::sync::Arc;
+use std::net::SocketAddr;
+use std::time::Duration;
 
-use axum::extract::State;
+use axum::extract::{ConnectInfo, Query};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::sse::{Event, Sse};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::time::{self, MissedTickBehavior};
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
 use tokio_stream::{iter, Stream, StreamExt};
 use tracing::{debug, error, info, warn};
 
-use crate::sse::format_app_event;
-use crate::{AppEvent, AppState};
+use crate::api::displays::{client_ip, user_agent};
+use crate::api::rooms::Room;
+use crate::event_log::LoggedEvent;
+use crate::sse::{
+    format_app_event, format_logged_event, EndAfterShutdown, EventFilter, EventSubscriptionQuery,
+};
+use crate::AppEvent;
 
 /// Axum route for establishing a Server-Sent Events (SSE) connection.
+///
+/// Clients may narrow the stream with `types`, `locations` and `zone` query
+/// parameters (see [`EventSubscriptionQuery`]); unknown event types, and zones
+/// missing from `DISPLAY_ZONES` when zones are configured, are rejected with 400.
+/// Signage displays should also pass a `display_id` (and optionally a `zone`) so the
+/// connection is tracked in the display registry for as long as the stream stays
+/// open and can receive display commands.
+///
+/// Every event carries its [`crate::event_log::EventLog`] id; reconnecting
+/// clients that send `Last-Event-ID` first receive the buffered events they missed.
+/// The stream ends after a `server_shutdown` event.
 pub async fn sse_events(
-    State(state): State<Arc<AppState>>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let sender = state.event_bus_sender.clone();
-    let keep_alive_interval = state.config.sse_keep_alive_interval();
-    info!("New SSE client connected to /api/events.");
+    Room(state): Room,
+    Query(query): Query<EventSubscriptionQuery>,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let filter = EventFilter::from_query(&query).map_err(|message| {
+        warn!(
+            "SSE: Rejecting subscription with invalid filter: {}",
+            message
+        );
+        (StatusCode::BAD_REQUEST, message)
+    })?;
+
+    filter
+        .check_zone(&state.config.display_zones())
+        .map_err(|message| {
+            warn!("SSE: Rejecting subscription: {}", message);
+            (StatusCode::BAD_REQUEST, message)
+        })?;
+
+    let display_connection = filter.display_id().map(|display_id| {
+        state.displays.connect(
+            display_id,
+            filter.zone().map(str::to_string),
+            user_agent(&headers),
+            client_ip(
+                &headers,
+                connect_info.as_ref(),
+                &state.config.get().trusted_proxies(),
+            ),
+        )
+    });
+
+    let keep_alive_interval = state.config.get().sse_keep_alive_interval();
+    if filter.is_unrestricted() {
+        info!("New SSE client connected to /api/events.");
+    } else {
+        info!(
+            "New SSE client connected to /api/events with filter {:?}.",
+            filter
+        );
+    }
+
+    // Resuming clients get the buffered events they missed before the live stream.
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let (replayed, broadcast_receiver) = match last_event_id {
+        Some(since) => {
+            let (replay, receiver) = state.events.replay_and_subscribe(since);
+            if replay.truncated {
+                warn!(
+                    "SSE: Client resumed from event {} but older events were already evicted.",
+                    since
+                );
+            }
+            debug!(
+                "SSE: Replaying {} events after Last-Event-ID {}.",
+                replay.events.len(),
+                since
+            );
+            (replay.events, receiver)
+        }
+        None => (Vec::new(), state.events.subscribe()),
+    };
 
-    let broadcast_receiver = sender.subscribe();
     let mut interval = time::interval(keep_alive_interval);
     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
     interval.tick().await;
 
-    let event_stream = BroadcastStream::new(broadcast_receiver).filter_map(|event_result| {
+    let replay_filter = filter.clone();
+    let replay_stream = iter(
+        replayed
+            .into_iter()
+            .filter(move |logged| replay_filter.matches(&logged.event))
+            .filter_map(|logged| {
+                format_logged_event(&logged).map(|event| (event, is_shutdown(&logged.event)))
+            }),
+    );
+
+    let stream_filter = filter.clone();
+    let stats = state.events.stats().clone();
+    let event_stream = BroadcastStream::new(broadcast_receiver).filter_map(move |event_result| {
         match event_result {
-            Ok(event) => {
-                debug!("SSE: Received AppEvent for broadcast: {:?}", event);
-                match format_app_event(&event) {
-                    Some(axum_event) => Some(axum_event),
+            Ok(logged) => {
+                if !stream_filter.matches(&logged.event) {
+                    debug!(
+                        "SSE: Skipping '{}' event filtered out for this client.",
+                        logged.event.event_name()
+                    );
+                    return None;
+                }
+                debug!("SSE: Received AppEvent for broadcast: {:?}", logged);
+                match format_logged_event(&logged) {
+                    Some(axum_event) => Some((axum_event, is_shutdown(&logged.event))),
                     None => {
                         error!(
                             "SSE: Failed to format AppEvent for SSE client (event was: {:?}).",
-                            event
+                            logged.event
                         );
                         None
                     }
@@ -45,23 +145,149 @@ pub async fn sse_events(
                     "SSE: Client lagged, skipped {} events. Consider increasing SSE_EVENT_BUFFER_SIZE or client processing speed.",
                     skipped
                 );
+                stats.record_lag("sse", skipped);
                 None
             }
         }
     });
 
-    let event_stream = event_stream.map(|event| Ok::<Event, Infallible>(event));
-
-    let initial_status = state.announcements.current_status().await;
-    let initial_event = format_app_event(&AppEvent::AnnouncementStatus(initial_status));
-    let initial_stream = iter(initial_event.into_iter().map(Ok::<Event, Infallible>));
+    let initial_status = AppEvent::AnnouncementStatus(state.announcements.current_status().await);
+    let initial_event = if filter.matches(&initial_status) {
+        format_app_event(&initial_status)
+    } else {
+        None
+    };
+    let initial_stream = iter(initial_event.into_iter().map(|event| (event, false)));
 
-    let keep_alive_stream = IntervalStream::new(interval).map(|_| {
+    // The display connection and client guards live inside the keep-alive
+    // stream so the registry and client count are told about the disconnect as
+    // soon as Axum drops the response.
+    let client = state.events.stats().connect();
+    let keep_alive_stream = IntervalStream::new(interval).map(move |_| {
+        let _client = &client;
+        if let Some(connection) = &display_connection {
+            connection.heartbeat();
+        }
         debug!("SSE: Sending keep-alive comment.");
-        Ok::<Event, Infallible>(Event::default().comment("keep-alive"))
+        (Event::default().comment("keep-alive"), false)
     });
 
-    let combined_stream = initial_stream.chain(event_stream).merge(keep_alive_stream);
+    let combined_stream = initial_stream
+        .chain(replay_stream)
+        .chain(event_stream)
+        .merge(keep_alive_stream);
+
+    Ok(Sse::new(EndAfterShutdown::new(combined_stream)))
+}
+
+fn is_shutdown(event: &AppEvent) -> bool {
+    matches!(event, AppEvent::ServerShutdown)
+}
+
+/// Default time a long-poll request waits for new events.
+const DEFAULT_POLL_TIMEOUT_SECONDS: u64 = 30;
+/// Upper bound for the long-poll wait, kept below common proxy timeouts.
+const MAX_POLL_TIMEOUT_SECONDS: u64 = 60;
+
+/// Cursor parameters of `GET /api/events/poll`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PollParams {
+    /// Id of the last event the client has seen. Omit to wait for the next new event.
+    pub since: Option<u64>,
+    /// Seconds to wait for new events before returning an empty list.
+    pub timeout: Option<u64>,
+}
+
+/// Response of `GET /api/events/poll`.
+#[derive(Debug, Serialize)]
+pub struct PollResponse {
+    /// Matching events after the cursor, oldest first.
+    pub events: Vec<LoggedEvent>,
+    /// Cursor to pass as `since` in the next request.
+    pub last_event_id: u64,
+    /// True when some events after the cursor were evicted from the replay buffer.
+    pub truncated: bool,
+}
+
+/// Axum route for long-polling clients that cannot use `EventSource`.
+///
+/// Returns immediately when buffered events newer than `since` match the filter;
+/// otherwise holds the request until a matching event arrives or `timeout` elapses.
+/// Accepts the same filter parameters as `/api/events`.
+pub async fn poll_events(
+    Room(state): Room,
+    Query(query): Query<EventSubscriptionQuery>,
+    Query(params): Query<PollParams>,
+) -> Result<Json<PollResponse>, (StatusCode, String)> {
+    let filter = EventFilter::from_query(&query)
+        .and_then(|filter| {
+            filter
+                .check_zone(&state.config.display_zones())
+                .map(|_| filter)
+        })
+        .map_err(|message| {
+            warn!("Poll: Rejecting request with invalid filter: {}", message);
+            (StatusCode::BAD_REQUEST, message)
+        })?;
+
+    let since = params.since.unwrap_or_else(|| state.events.last_event_id());
+    let timeout = Duration::from_secs(
+        params
+            .timeout
+            .unwrap_or(DEFAULT_POLL_TIMEOUT_SECONDS)
+            .min(MAX_POLL_TIMEOUT_SECONDS),
+    );
+    debug!(
+        "GET /api/events/poll: since={} timeout={:?} filter={:?}",
+        since, timeout, filter
+    );
+
+    let (replay, mut receiver) = state.events.replay_and_subscribe(since);
+    let mut truncated = replay.truncated;
+    let mut last_event_id = replay.last_event_id;
+    let mut events: Vec<LoggedEvent> = replay
+        .events
+        .iter()
+        .filter(|logged| filter.matches(&logged.event))
+        .map(|logged| LoggedEvent::clone(logged))
+        .collect();
+
+    if events.is_empty() {
+        let deadline = time::Instant::now() + timeout;
+        loop {
+            match time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Ok(logged)) => {
+                    last_event_id = logged.id;
+                    if filter.matches(&logged.event) {
+                        events.push(LoggedEvent::clone(&logged));
+                        // Hand over anything else that is already queued.
+                        while let Ok(more) = receiver.try_recv() {
+                            last_event_id = more.id;
+                            if filter.matches(&more.event) {
+                                events.push(LoggedEvent::clone(&more));
+                            }
+                        }
+                        break;
+                    }
+                }
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    warn!("Poll: Request lagged, skipped {} events.", skipped);
+                    state.events.stats().record_lag("poll", skipped);
+                    truncated = true;
+                }
+                Ok(Err(RecvError::Closed)) | Err(_) => break,
+            }
+        }
+    }
 
-    Sse::new(combined_stream)
+    debug!(
+        "GET /api/events/poll: returning {} events (last_event_id={}).",
+        events.len(),
+        last_event_id
+    );
+    Ok(Json(PollResponse {
+        events,
+        last_event_id,
+        truncated,
+    }))
 }
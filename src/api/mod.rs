@@ -5,37 +5,83 @@ use std::sync::Arc;
 use axum::routing::{get, post};
 use axum::Router;
 
+pub mod admin;
 pub mod announcements;
+pub mod appointments;
+pub mod diagnostics;
+pub mod displays;
 pub mod events;
 pub mod queue;
+pub mod rooms;
+pub mod tickets;
 pub mod translator;
 pub mod tts;
+pub mod webhooks;
+pub mod ws;
 
+use admin::{get_settings, reload_config, update_settings};
 use announcements::{
     get_announcement_status, manual_advance_announcement, manual_trigger_specific_announcement,
 };
-use events::sse_events;
-use queue::{complete_call, force_skip_new_call, get_queue_state, queue_call, skip_call};
+use appointments::{
+    check_in_appointment, import_appointments, import_appointments_csv, list_appointments,
+};
+use diagnostics::get_diagnostics;
+use displays::{
+    acknowledge_display_command, display_heartbeat, get_display_command, issue_display_command,
+    list_display_commands, list_displays,
+};
+use events::{poll_events, sse_events};
+use queue::{
+    call_next, complete_call, force_skip_new_call, get_queue_history, get_queue_state, queue_call,
+    recall_call, skip_call,
+};
+use rooms::list_rooms;
+use tickets::{get_ticket, issue_ticket, list_tickets};
 use translator::{call_translator, get_translator_status};
-use tts::{get_ordered_supported_languages, get_supported_languages, trigger_tts};
+use tts::{
+    get_ordered_supported_languages, get_stem_inventory, get_supported_languages, trigger_tts,
+};
+use webhooks::list_webhook_deliveries;
+use ws::ws_events;
 
 use crate::AppState;
 
 /// Build the Axum router exposing all API routes under the `/api` prefix.
+///
+/// Every per-room route is served twice: directly (for the default room) and
+/// nested under `/rooms/:room`.
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
+        .route("/rooms", get(list_rooms))
+        .route("/admin/config/reload", post(reload_config))
+        .route("/diagnostics", get(get_diagnostics))
+        .merge(room_router())
+        .nest("/rooms/:room", room_router())
+}
+
+/// Routes operating on a single room, resolved by the [`rooms::Room`] extractor.
+fn room_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/settings", get(get_settings).put(update_settings))
         .route("/events", get(sse_events))
+        .route("/events/poll", get(poll_events))
+        .route("/ws", get(ws_events))
         .route("/tts/trigger", post(trigger_tts))
         .route("/tts/languages", get(get_supported_languages))
         .route(
             "/tts/ordered-languages",
             get(get_ordered_supported_languages),
         )
+        .route("/tts/stems", get(get_stem_inventory))
         .route("/queue/add", post(queue_call))
         .route("/queue/skip", post(skip_call))
         .route("/queue/complete", post(complete_call))
+        .route("/queue/recall", post(recall_call))
         .route("/queue/force_skip", post(force_skip_new_call))
+        .route("/queue/next", post(call_next))
         .route("/queue/state", get(get_queue_state))
+        .route("/queue/history", get(get_queue_history))
         .route("/announcements/status", get(get_announcement_status))
         .route("/announcements/next", post(manual_advance_announcement))
         .route(
@@ -44,4 +90,24 @@ pub fn router() -> Router<Arc<AppState>> {
         )
         .route("/translator/status", get(get_translator_status))
         .route("/translator/call", post(call_translator))
+        .route("/displays", get(list_displays))
+        .route("/displays/:display_id/heartbeat", post(display_heartbeat))
+        .route(
+            "/displays/commands",
+            get(list_display_commands).post(issue_display_command),
+        )
+        .route("/displays/commands/:command_id", get(get_display_command))
+        .route(
+            "/displays/commands/:command_id/ack",
+            post(acknowledge_display_command),
+        )
+        .route("/tickets", get(list_tickets).post(issue_ticket))
+        .route("/tickets/:number", get(get_ticket))
+        .route(
+            "/appointments",
+            get(list_appointments).post(import_appointments),
+        )
+        .route("/appointments/csv", post(import_appointments_csv))
+        .route("/appointments/:id/check-in", post(check_in_appointment))
+        .route("/webhooks/deliveries", get(list_webhook_deliveries))
 }
@@ -1,13 +1,16 @@
-use std::sync::{Arc, OnceLock};
+use std::sync::OnceLock;
 
-use axum::extract::State;
+use axum::extract::Query;
 use axum::http::StatusCode;
 use axum::Json;
 use regex::Regex;
 use serde::Deserialize;
 use tracing::{debug, error, info, warn};
 
-use crate::{AppState, QueueState};
+use crate::api::rooms::Room;
+use crate::queue::HistoryStatus;
+use crate::storage::{HistoryPage, DEFAULT_HISTORY_PAGE_SIZE, MAX_HISTORY_PAGE_SIZE};
+use crate::QueueState;
 
 /// Request data structure for adding or updating a call in the queue.
 #[derive(Deserialize, Debug)]
@@ -18,6 +21,27 @@ pub struct AddCallRequest {
     pub location: String,
 }
 
+/// Request data structure for calling the next waiting ticket.
+#[derive(Deserialize, Debug)]
+pub struct CallNextRequest {
+    /// Location the ticket is called to (digits only).
+    pub location: String,
+    /// Only consider tickets of this service prefix.
+    #[serde(default)]
+    pub service: Option<String>,
+}
+
+/// Query parameters of `GET /api/queue/history`.
+#[derive(Deserialize, Debug, Default)]
+pub struct HistoryQuery {
+    /// 1-based page number (default 1).
+    pub page: Option<usize>,
+    /// Records per page (default 50, at most 200).
+    pub page_size: Option<usize>,
+    /// `completed` or `skipped`; both when omitted.
+    pub status: Option<String>,
+}
+
 /// Request data structure for forcing a call into the skipped history.
 #[derive(Deserialize, Debug)]
 pub struct ForceSkipRequest {
@@ -29,7 +53,8 @@ const IDENTIFIER_FORMAT_MESSAGE: &str =
     "Invalid Identifier format. Must be an uppercase letter followed by digits (e.g., A1, Z99).";
 const NUMERIC_IDENTIFIER_FORMAT_MESSAGE: &str =
     "Invalid Identifier format. Must contain digits only (e.g., 1, 99).";
-const LOCATION_FORMAT_MESSAGE: &str = "Invalid Location format. Must be digits only (e.g., 5, 10).";
+pub(crate) const LOCATION_FORMAT_MESSAGE: &str =
+    "Invalid Location format. Must be digits only (e.g., 5, 10).";
 
 fn identifier_pattern() -> &'static Regex {
     static PATTERN: OnceLock<Regex> = OnceLock::new();
@@ -46,7 +71,10 @@ fn location_pattern() -> &'static Regex {
     PATTERN.get_or_init(|| Regex::new(r"^[0-9]+$").expect("location regex must compile"))
 }
 
-fn validate_identifier(original_id: &str, identifier_prefix_required: bool) -> Result<(), String> {
+pub(crate) fn validate_identifier(
+    original_id: &str,
+    identifier_prefix_required: bool,
+) -> Result<(), String> {
     let pattern = if identifier_prefix_required {
         identifier_pattern()
     } else {
@@ -65,7 +93,7 @@ fn validate_identifier(original_id: &str, identifier_prefix_required: bool) -> R
     }
 }
 
-fn validate_location(location: &str) -> Result<(), String> {
+pub(crate) fn validate_location(location: &str) -> Result<(), String> {
     if location_pattern().is_match(location) {
         Ok(())
     } else {
@@ -77,7 +105,7 @@ fn validate_location(location: &str) -> Result<(), String> {
 }
 
 pub async fn queue_call(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
     Json(call_info): Json<AddCallRequest>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     info!(
@@ -85,8 +113,9 @@ pub async fn queue_call(
         call_info.original_id, call_info.location
     );
 
-    let identifier_prefix_required = state.config.queue_identifier_prefix_required;
-    let identifier_message = state.config.queue_identifier_format_message();
+    let config = state.config.get();
+    let identifier_prefix_required = config.queue_identifier_prefix_required;
+    let identifier_message = config.queue_identifier_format_message();
 
     if let Err(message) = validate_identifier(&call_info.original_id, identifier_prefix_required) {
         warn!(
@@ -135,8 +164,63 @@ pub async fn queue_call(
     }
 }
 
+/// Axum route calling the next waiting ticket: a checked-in appointment whose
+/// time has come, otherwise the oldest walk-in.
+pub async fn call_next(
+    Room(state): Room,
+    Json(request): Json<CallNextRequest>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    info!(
+        "/api/queue/next: Calling next ticket at location '{}' (service {:?}).",
+        request.location, request.service
+    );
+
+    if let Err(message) = validate_location(&request.location) {
+        warn!(
+            "Invalid location format received for next: '{}'. {}",
+            request.location, LOCATION_FORMAT_MESSAGE
+        );
+        return Err((StatusCode::BAD_REQUEST, message));
+    }
+
+    let Some(number) = state
+        .tickets
+        .call_next(request.service.as_deref(), &request.location)
+    else {
+        warn!("/api/queue/next: No waiting tickets. Request had no effect.");
+        return Err((StatusCode::NOT_FOUND, "No waiting tickets.".to_string()));
+    };
+
+    match state.queue.add_call(&number, &request.location).await {
+        Ok(current_call) => {
+            info!(
+                "/api/queue/next: Ticket '{}' called to location '{}'.",
+                current_call.original_id, current_call.location
+            );
+            Ok((
+                StatusCode::ACCEPTED,
+                format!(
+                    "Call {} with location {} is now current. TTS initiated.",
+                    current_call.original_id, current_call.location
+                ),
+            ))
+        }
+        Err(err) => {
+            error!(
+                "/api/queue/next: Queue service failed for ticket '{}': {}",
+                number, err
+            );
+            state.tickets.return_to_waiting(&number);
+            Err((
+                StatusCode::BAD_REQUEST,
+                "Failed to process the call. An unexpected server error occurred.".to_string(),
+            ))
+        }
+    }
+}
+
 /// Axum route for skipping the `current_call`.
-pub async fn skip_call(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+pub async fn skip_call(Room(state): Room) -> (StatusCode, String) {
     info!("/api/queue/skip: Attempting to skip current call.");
     let message = if let Some(skipped_call) = state.queue.skip_current_call().await {
         info!("/api/queue/skip: Call '{}' was skipped.", skipped_call.id);
@@ -153,7 +237,7 @@ pub async fn skip_call(State(state): State<Arc<AppState>>) -> (StatusCode, Strin
 }
 
 /// Axum route for marking the `current_call` as completed.
-pub async fn complete_call(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+pub async fn complete_call(Room(state): Room) -> (StatusCode, String) {
     info!("/api/queue/complete: Attempting to complete current call.");
     let message = if let Some(completed_call) = state.queue.complete_current_call().await {
         info!(
@@ -172,9 +256,29 @@ pub async fn complete_call(State(state): State<Arc<AppState>>) -> (StatusCode, S
     (StatusCode::ACCEPTED, message)
 }
 
+/// Axum route for announcing the `current_call` again.
+pub async fn recall_call(Room(state): Room) -> (StatusCode, String) {
+    info!("/api/queue/recall: Attempting to recall current call.");
+    let message = if let Some(recalled_call) = state.queue.recall_current_call().await {
+        info!(
+            "/api/queue/recall: Call '{}' was recalled.",
+            recalled_call.id
+        );
+        format!(
+            "Call {} (Location {}) recalled. TTS initiated.",
+            recalled_call.original_id, recalled_call.location
+        )
+    } else {
+        warn!("/api/queue/recall: No current call to recall. Request had no effect.");
+        "No current call to recall.".to_string()
+    };
+
+    (StatusCode::ACCEPTED, message)
+}
+
 /// Axum route for adding a new call directly to the skipped history.
 pub async fn force_skip_new_call(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
     Json(call_info): Json<ForceSkipRequest>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     info!(
@@ -182,8 +286,9 @@ pub async fn force_skip_new_call(
         call_info.original_id, call_info.location
     );
 
-    let identifier_prefix_required = state.config.queue_identifier_prefix_required;
-    let identifier_message = state.config.queue_identifier_format_message();
+    let config = state.config.get();
+    let identifier_prefix_required = config.queue_identifier_prefix_required;
+    let identifier_message = config.queue_identifier_format_message();
 
     if let Err(message) = validate_identifier(&call_info.original_id, identifier_prefix_required) {
         warn!(
@@ -236,9 +341,42 @@ pub async fn force_skip_new_call(
 }
 
 /// Axum route for retrieving the current state of the call queue.
-pub async fn get_queue_state(State(state): State<Arc<AppState>>) -> Json<QueueState> {
+pub async fn get_queue_state(Room(state): Room) -> Json<QueueState> {
     debug!("GET /api/queue/state: Fetching current queue state.");
     let q_state = state.queue.snapshot().await;
     debug!("GET /api/queue/state: Returning state: {:?}", q_state);
     Json(q_state)
 }
+
+/// Axum route returning one page of the stored call history, newest first.
+pub async fn get_queue_history(
+    Room(state): Room,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryPage>, (StatusCode, String)> {
+    debug!("GET /api/queue/history: {:?}", query);
+    let status = match query.status.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(raw) => Some(
+            raw.parse::<HistoryStatus>()
+                .map_err(|message| (StatusCode::BAD_REQUEST, message))?,
+        ),
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE)
+        .clamp(1, MAX_HISTORY_PAGE_SIZE);
+
+    state
+        .history
+        .calls(status, page, page_size)
+        .await
+        .map(Json)
+        .map_err(|err| {
+            error!("GET /api/queue/history: {}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to read the call history.".to_string(),
+            )
+        })
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Path, State};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::state::RoomState;
+use crate::AppState;
+
+/// Extractor resolving the room a request targets.
+///
+/// Routes nested under `/api/rooms/:room` use the `room` path parameter; the
+/// un-prefixed `/api/...` routes resolve to the default room. Unknown rooms are
+/// rejected with 404.
+pub struct Room(pub Arc<RoomState>);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Room {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let params = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map(|Path(params)| params)
+            .unwrap_or_default();
+
+        match params.get("room") {
+            None => Ok(Room(state.default_room())),
+            Some(room_id) => state.room(room_id).map(Room).ok_or_else(|| {
+                warn!("Request for unknown room '{}'.", room_id);
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Room '{}' does not exist.", room_id),
+                )
+            }),
+        }
+    }
+}
+
+/// Summary of a room returned by `GET /api/rooms`.
+#[derive(Serialize, Debug)]
+pub struct RoomSummary {
+    pub id: String,
+    pub supported_languages: Vec<String>,
+}
+
+/// Axum route listing every room served by this process.
+pub async fn list_rooms(State(state): State<Arc<AppState>>) -> Json<Vec<RoomSummary>> {
+    debug!("GET /api/rooms: Listing rooms.");
+    let rooms = state
+        .room_ids()
+        .into_iter()
+        .filter_map(|id| state.room(&id))
+        .map(|room| RoomSummary {
+            id: room.id.clone(),
+            supported_languages: room.config.get().ordered_supported_language_codes(),
+        })
+        .collect();
+    Json(rooms)
+}
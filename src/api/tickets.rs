@@ -0,0 +1,84 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::api::rooms::Room;
+use crate::application::tickets::IssuedTicket;
+use crate::config::TicketServiceDefinition;
+use crate::tickets::{TicketError, TicketView};
+
+/// Request body for issuing a ticket. Omitting `service` uses the first
+/// configured service.
+#[derive(Deserialize, Debug, Default)]
+pub struct IssueTicketRequest {
+    #[serde(default)]
+    pub service: Option<String>,
+    /// Contact details (phone number, chat id, ...) for ticket notifications.
+    #[serde(default)]
+    pub contact: Option<String>,
+}
+
+/// Path parameters of routes addressing a single ticket.
+#[derive(Deserialize, Debug)]
+pub struct TicketPath {
+    number: String,
+}
+
+/// Response listing the configured services and the waiting tickets.
+#[derive(Serialize, Debug)]
+pub struct TicketListResponse {
+    pub services: Vec<TicketServiceDefinition>,
+    pub waiting: Vec<TicketView>,
+}
+
+/// Axum route issuing (and printing) a ticket.
+pub async fn issue_ticket(
+    Room(state): Room,
+    payload: Option<Json<IssueTicketRequest>>,
+) -> Result<(StatusCode, Json<IssuedTicket>), (StatusCode, String)> {
+    let Json(payload) = payload.unwrap_or_default();
+    info!(
+        "POST /api/tickets: Issuing ticket for service {:?}.",
+        payload.service
+    );
+    match state
+        .tickets
+        .issue(payload.service.as_deref(), payload.contact)
+        .await
+    {
+        Ok(issued) => Ok((StatusCode::CREATED, Json(issued))),
+        Err(err) => {
+            warn!("Ticket issuance rejected: {}", err);
+            let status = match err {
+                TicketError::UnknownService(_) => StatusCode::BAD_REQUEST,
+                TicketError::NoServices => StatusCode::SERVICE_UNAVAILABLE,
+            };
+            Err((status, err.to_string()))
+        }
+    }
+}
+
+/// Axum route listing services and waiting tickets.
+pub async fn list_tickets(Room(state): Room) -> Json<TicketListResponse> {
+    debug!("GET /api/tickets: Listing waiting tickets.");
+    Json(TicketListResponse {
+        services: state.tickets.services(),
+        waiting: state.tickets.waiting(),
+    })
+}
+
+/// Axum route returning one ticket's status, e.g. for the QR code on the ticket.
+pub async fn get_ticket(
+    Room(state): Room,
+    Path(TicketPath { number }): Path<TicketPath>,
+) -> Result<Json<TicketView>, (StatusCode, String)> {
+    debug!("GET /api/tickets/{}: Fetching ticket status.", number);
+    state.tickets.ticket(&number).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("Ticket '{}' not found", number),
+        )
+    })
+}
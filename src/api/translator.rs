@@ -1,13 +1,10 @@
-use std::sync::Arc;
-
-use axum::extract::State;
 use axum::http::StatusCode;
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use crate::api::rooms::Room;
 use crate::application::translator::{TranslatorCallError, TranslatorStatus};
-use crate::AppState;
 
 #[derive(Debug, Deserialize)]
 pub struct CallTranslatorRequest {
@@ -27,7 +24,7 @@ pub struct TranslatorErrorResponse {
     pub remaining_seconds: Option<u64>,
 }
 
-pub async fn get_translator_status(State(state): State<Arc<AppState>>) -> Json<TranslatorStatus> {
+pub async fn get_translator_status(Room(state): Room) -> Json<TranslatorStatus> {
     info!("GET /api/translator/status: Fetching translator status");
     let status = state.translator.current_status().await;
     debug!("Translator status response: {:?}", status);
@@ -35,7 +32,7 @@ pub async fn get_translator_status(State(state): State<Arc<AppState>>) -> Json<T
 }
 
 pub async fn call_translator(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
     Json(payload): Json<CallTranslatorRequest>,
 ) -> Result<
     (StatusCode, Json<TranslatorSuccessResponse>),
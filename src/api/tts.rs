@@ -1,13 +1,12 @@
 use std::collections::HashMap;
-use std::sync::Arc;
 
-use axum::extract::State;
 use axum::http::StatusCode;
 use axum::Json;
 use serde::Deserialize;
 use tracing::{debug, error, info};
 
-use crate::AppState;
+use crate::api::rooms::Room;
+use crate::tts::stems::{self, StemInventory};
 
 /// Request data structure for manually triggering Text-to-Speech generation.
 #[derive(Deserialize, Debug)]
@@ -18,7 +17,7 @@ pub struct TriggerTTSRequest {
 }
 
 pub async fn trigger_tts(
-    State(state): State<Arc<AppState>>,
+    Room(state): Room,
     Json(request): Json<TriggerTTSRequest>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     info!(
@@ -46,20 +45,35 @@ pub async fn trigger_tts(
     }
 }
 
-pub async fn get_supported_languages(
-    State(state): State<Arc<AppState>>,
-) -> Json<HashMap<String, String>> {
+pub async fn get_supported_languages(Room(state): Room) -> Json<HashMap<String, String>> {
     info!("Received request for supported languages map.");
     let languages = state.tts.supported_languages_map();
     debug!("Returning supported languages: {:?}", languages);
     Json(languages)
 }
 
-pub async fn get_ordered_supported_languages(
-    State(state): State<Arc<AppState>>,
-) -> Json<Vec<String>> {
+pub async fn get_ordered_supported_languages(Room(state): Room) -> Json<Vec<String>> {
     info!("Received request for ordered supported languages.");
-    let ordered_langs = state.config.ordered_supported_language_codes();
+    let ordered_langs = state.config.get().ordered_supported_language_codes();
     debug!("Returning ordered supported languages: {:?}", ordered_langs);
     Json(ordered_langs)
 }
+
+/// Stem audio of every configured language: the files present and the
+/// required stems the fallback has to skip.
+pub async fn get_stem_inventory(
+    Room(state): Room,
+) -> Result<Json<Vec<StemInventory>>, (StatusCode, String)> {
+    debug!("GET /api/tts/stems: Scanning stem audio.");
+    let config = state.config.get();
+    tokio::task::spawn_blocking(move || stems::inventory(&config))
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to scan stem audio: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to scan stem audio".to_string(),
+            )
+        })
+}
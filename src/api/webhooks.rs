@@ -0,0 +1,17 @@
+use axum::Json;
+use tracing::{debug, info};
+
+use crate::api::rooms::Room;
+use crate::application::webhooks::WebhookDeliveryReport;
+
+/// Axum route listing webhook subscriptions, recent deliveries and dead letters.
+pub async fn list_webhook_deliveries(Room(state): Room) -> Json<WebhookDeliveryReport> {
+    info!("GET /api/webhooks/deliveries: Fetching webhook delivery status");
+    let report = state.webhooks.deliveries();
+    debug!(
+        "Webhook delivery report: {} recent, {} dead-lettered",
+        report.deliveries.len(),
+        report.dead_letters.len()
+    );
+    Json(report)
+}
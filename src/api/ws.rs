@@ -0,0 +1,292 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Query};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use crate::api::displays::{client_ip, user_agent};
+use crate::api::rooms::Room;
+use crate::application::displays::DisplayConnection;
+use crate::application::QueueCommand;
+use crate::sse::{EventFilter, EventSubscriptionQuery};
+use crate::state::RoomState;
+use crate::{AppEvent, Call};
+
+/// A command sent by a WebSocket client.
+///
+/// Example: `{"id":"req-1","command":"add","original_id":"A12","location":"3"}`.
+/// The optional `id` is echoed back in the acknowledgement or error.
+#[derive(Deserialize, Debug)]
+pub struct WsRequest {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub command: WsCommand,
+}
+
+/// Operations available over the WebSocket, mirroring the HTTP API.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum WsCommand {
+    Add {
+        original_id: String,
+        location: String,
+    },
+    Skip,
+    Complete,
+    Recall,
+    ForceSkip {
+        original_id: String,
+        location: String,
+    },
+    TranslatorCall {
+        location: String,
+    },
+    AnnouncementNext,
+    AnnouncementTrigger {
+        slot_id: String,
+    },
+}
+
+impl WsCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            WsCommand::Add { .. } => "add",
+            WsCommand::Skip => "skip",
+            WsCommand::Complete => "complete",
+            WsCommand::Recall => "recall",
+            WsCommand::ForceSkip { .. } => "force_skip",
+            WsCommand::TranslatorCall { .. } => "translator_call",
+            WsCommand::AnnouncementNext => "announcement_next",
+            WsCommand::AnnouncementTrigger { .. } => "announcement_trigger",
+        }
+    }
+}
+
+/// Replies sent in response to client commands, using the same `type`/`data`
+/// envelope as [`crate::AppEvent`].
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", content = "data")]
+pub enum WsReply {
+    Ack {
+        id: Option<String>,
+        command: &'static str,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        call: Option<Call>,
+    },
+    Error {
+        id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        command: Option<&'static str>,
+        message: String,
+    },
+}
+
+/// Axum route upgrading to a WebSocket that streams events and accepts commands.
+///
+/// Accepts the same query parameters as `/api/events` for filtering and display
+/// registration. Every event is sent as a text frame containing the `AppEvent`
+/// JSON envelope; every command receives exactly one `Ack` or `Error` reply.
+pub async fn ws_events(
+    Room(state): Room,
+    Query(query): Query<EventSubscriptionQuery>,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, (StatusCode, String)> {
+    let filter = EventFilter::from_query(&query)
+        .and_then(|filter| {
+            filter
+                .check_zone(&state.config.display_zones())
+                .map(|_| filter)
+        })
+        .map_err(|message| {
+            warn!(
+                "WS: Rejecting subscription with invalid filter: {}",
+                message
+            );
+            (StatusCode::BAD_REQUEST, message)
+        })?;
+
+    let display_connection = filter.display_id().map(|display_id| {
+        state.displays.connect(
+            display_id,
+            filter.zone().map(str::to_string),
+            user_agent(&headers),
+            client_ip(
+                &headers,
+                connect_info.as_ref(),
+                &state.config.get().trusted_proxies(),
+            ),
+        )
+    });
+
+    info!("New WebSocket client connected to /api/ws.");
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, filter, display_connection)))
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: Arc<RoomState>,
+    filter: EventFilter,
+    display_connection: Option<DisplayConnection>,
+) {
+    let mut events = state.event_bus_sender.subscribe();
+    let _client = state.events.stats().connect();
+    let mut interval = time::interval(state.config.get().sse_keep_alive_interval());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    interval.tick().await;
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => {
+                        debug!("WS: Receive error, closing connection: {}", err);
+                        break;
+                    }
+                };
+                let reply = handle_command_message(&state, &text).await;
+                if !send_json(&mut socket, &reply).await {
+                    break;
+                }
+            }
+            event = events.recv() => {
+                match event {
+                    Ok(event) => {
+                        if !filter.matches(&event) {
+                            continue;
+                        }
+                        if !send_json(&mut socket, &event).await {
+                            break;
+                        }
+                        if let AppEvent::ServerShutdown = event {
+                            let _ = socket.send(Message::Close(None)).await;
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "WS: Client lagged, skipped {} events. Consider increasing SSE_EVENT_BUFFER_SIZE.",
+                            skipped
+                        );
+                        state.events.stats().record_lag("ws", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            _ = interval.tick() => {
+                if let Some(connection) = &display_connection {
+                    connection.heartbeat();
+                }
+                if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    info!("WebSocket client disconnected from /api/ws.");
+}
+
+/// Serialises `value` and sends it as a text frame. Returns `false` when the
+/// connection is gone.
+async fn send_json<T: Serialize>(socket: &mut WebSocket, value: &T) -> bool {
+    match serde_json::to_string(value) {
+        Ok(payload) => socket.send(Message::Text(payload)).await.is_ok(),
+        Err(err) => {
+            error!("WS: Failed to serialise outgoing message: {}", err);
+            true
+        }
+    }
+}
+
+async fn handle_command_message(state: &RoomState, text: &str) -> WsReply {
+    let request: WsRequest = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(err) => {
+            warn!("WS: Rejecting malformed command: {}", err);
+            return WsReply::Error {
+                id: None,
+                command: None,
+                message: format!("Malformed command: {}", err),
+            };
+        }
+    };
+
+    let command = request.command.name();
+    debug!(
+        "WS: Executing '{}' command (id: {:?}).",
+        command, request.id
+    );
+    match execute_command(state, request.command).await {
+        Ok((message, call)) => WsReply::Ack {
+            id: request.id,
+            command,
+            message,
+            call,
+        },
+        Err(message) => {
+            warn!("WS: '{}' command failed: {}", command, message);
+            WsReply::Error {
+                id: request.id,
+                command: Some(command),
+                message,
+            }
+        }
+    }
+}
+
+async fn execute_command(
+    state: &RoomState,
+    command: WsCommand,
+) -> Result<(String, Option<Call>), String> {
+    let command = match command {
+        WsCommand::Add {
+            original_id,
+            location,
+        } => QueueCommand::Add {
+            original_id,
+            location,
+        },
+        WsCommand::Skip => QueueCommand::Skip,
+        WsCommand::Complete => QueueCommand::Complete,
+        WsCommand::Recall => QueueCommand::Recall,
+        WsCommand::ForceSkip {
+            original_id,
+            location,
+        } => QueueCommand::ForceSkip {
+            original_id,
+            location,
+        },
+        WsCommand::TranslatorCall { location } => QueueCommand::TranslatorCall { location },
+        WsCommand::AnnouncementNext => {
+            return state
+                .announcements
+                .manual_advance()
+                .await
+                .map(|_| ("Announcement advancement triggered".to_string(), None))
+                .map_err(|err| err.to_string())
+        }
+        WsCommand::AnnouncementTrigger { slot_id } => {
+            return state
+                .announcements
+                .manual_trigger(&slot_id)
+                .await
+                .map(|_| (format!("Announcement '{}' triggered", slot_id), None))
+                .map_err(|err| err.to_string())
+        }
+    };
+    let outcome = state.commands.execute(command).await?;
+    Ok((outcome.message, outcome.call))
+}
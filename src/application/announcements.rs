@@ -7,7 +7,7 @@ use tokio::sync::Mutex;
 use crate::{
     announcements::manager::{AnnouncementManager, AnnouncementStatus},
     announcements::ManualTriggerError,
-    config::AppConfig,
+    config::SharedConfig,
 };
 
 /// Service wrapper around [`AnnouncementManager`] to provide a simpler API surface.
@@ -18,7 +18,7 @@ pub struct AnnouncementService {
 
 impl AnnouncementService {
     /// Initialise the announcement subsystem and auto-cycle task where applicable.
-    pub async fn new(config: Arc<AppConfig>, event_bus: BroadcastSender) -> Self {
+    pub async fn new(config: SharedConfig, event_bus: BroadcastSender) -> Self {
         let manager = AnnouncementManager::new(config, event_bus).await;
         Self { manager }
     }
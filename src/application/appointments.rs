@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    application::tickets::{IssuedTicket, TicketService},
+    appointments::{Appointment, AppointmentBook, AppointmentError, AppointmentInput},
+};
+
+/// Service holding a room's appointments and converting them into tickets at
+/// check-in.
+#[derive(Clone)]
+pub struct AppointmentService {
+    book: Arc<Mutex<AppointmentBook>>,
+    tickets: TicketService,
+}
+
+impl AppointmentService {
+    pub fn new(tickets: TicketService) -> Self {
+        Self {
+            book: Arc::new(Mutex::new(AppointmentBook::new())),
+            tickets,
+        }
+    }
+
+    /// Adds or replaces appointments. Services must be configured ticket
+    /// services; the whole batch is rejected otherwise.
+    pub fn import(&self, inputs: Vec<AppointmentInput>) -> Result<usize, AppointmentError> {
+        let services = self.tickets.services();
+        if let Some(input) = inputs.iter().find(|input| {
+            input
+                .service
+                .as_deref()
+                .map(str::trim)
+                .filter(|service| !service.is_empty())
+                .is_some_and(|service| {
+                    !services
+                        .iter()
+                        .any(|definition| definition.prefix.eq_ignore_ascii_case(service))
+                })
+        }) {
+            return Err(AppointmentError::InvalidRecord(format!(
+                "'{}' uses unknown service '{}'",
+                input.id,
+                input.service.as_deref().unwrap_or_default()
+            )));
+        }
+
+        let count = self.book().import(inputs)?;
+        info!("Imported {} appointments.", count);
+        Ok(count)
+    }
+
+    /// All appointments ordered by time.
+    pub fn list(&self) -> Vec<Appointment> {
+        self.book().list().to_vec()
+    }
+
+    /// Checks in an appointment and issues (and prints) its ticket, due at the
+    /// appointment time.
+    pub async fn check_in(&self, id: &str) -> Result<IssuedTicket, AppointmentError> {
+        let appointment = self.book().check_in(id, Utc::now())?;
+        let issued = self
+            .tickets
+            .issue_for_appointment(
+                appointment.service.as_deref(),
+                appointment.contact.clone(),
+                &appointment.id,
+                appointment.scheduled_at,
+            )
+            .await;
+        match issued {
+            Ok(issued) => {
+                self.book().set_ticket(id, &issued.ticket.ticket.number);
+                info!(
+                    "Appointment '{}' checked in with ticket {}.",
+                    id, issued.ticket.ticket.number
+                );
+                Ok(issued)
+            }
+            Err(err) => {
+                warn!("Check-in of appointment '{}' failed: {}", id, err);
+                self.book().undo_check_in(id);
+                Err(err.into())
+            }
+        }
+    }
+
+    fn book(&self) -> MutexGuard<'_, AppointmentBook> {
+        self.book.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
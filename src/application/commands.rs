@@ -0,0 +1,148 @@
+//! Queue commands shared by the remote controls (WebSocket, MQTT and keypad).
+//!
+//! Each control only parses its own wire format into a [`QueueCommand`]; the
+//! validation, the service calls and the reply messages live here so the
+//! controls cannot drift apart.
+
+use crate::api::queue::{validate_identifier, validate_location};
+use crate::application::{QueueService, TranslatorService};
+use crate::config::SharedConfig;
+use crate::Call;
+
+/// A queue operation requested by a remote control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueCommand {
+    /// Make `original_id` the current call at `location`.
+    Add {
+        original_id: String,
+        location: String,
+    },
+    Skip,
+    Complete,
+    Recall,
+    /// Record `original_id` as skipped without calling it.
+    ForceSkip {
+        original_id: String,
+        location: String,
+    },
+    /// Request a translator at `location`.
+    TranslatorCall {
+        location: String,
+    },
+}
+
+/// Result of a successful [`QueueCommand`].
+#[derive(Debug, Clone)]
+pub struct CommandOutcome {
+    /// Human-readable confirmation for the control's reply.
+    pub message: String,
+    /// The call the command acted on, if it acted on one.
+    pub call: Option<Call>,
+}
+
+impl CommandOutcome {
+    fn with_call(message: String, call: Call) -> Self {
+        Self {
+            message,
+            call: Some(call),
+        }
+    }
+}
+
+/// Executes [`QueueCommand`]s against one room.
+#[derive(Clone)]
+pub struct CommandService {
+    config: SharedConfig,
+    queue: QueueService,
+    translator: TranslatorService,
+}
+
+impl CommandService {
+    pub fn new(config: SharedConfig, queue: QueueService, translator: TranslatorService) -> Self {
+        Self {
+            config,
+            queue,
+            translator,
+        }
+    }
+
+    /// Validates and runs `command`. The error is a message for the control's reply.
+    pub async fn execute(&self, command: QueueCommand) -> Result<CommandOutcome, String> {
+        match command {
+            QueueCommand::Add {
+                original_id,
+                location,
+            } => {
+                self.validate(&original_id, &location)?;
+                let call = self
+                    .queue
+                    .add_call(&original_id, &location)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                Ok(CommandOutcome::with_call(
+                    format!(
+                        "Call {} with location {} is now current. TTS initiated.",
+                        call.original_id, call.location
+                    ),
+                    call,
+                ))
+            }
+            QueueCommand::Skip => self
+                .queue
+                .skip_current_call()
+                .await
+                .map(|call| {
+                    CommandOutcome::with_call(format!("Call {} skipped.", call.original_id), call)
+                })
+                .ok_or_else(|| "No current call to skip.".to_string()),
+            QueueCommand::Complete => self
+                .queue
+                .complete_current_call()
+                .await
+                .map(|call| {
+                    CommandOutcome::with_call(format!("Call {} completed.", call.original_id), call)
+                })
+                .ok_or_else(|| "No current call to complete.".to_string()),
+            QueueCommand::Recall => self
+                .queue
+                .recall_current_call()
+                .await
+                .map(|call| {
+                    CommandOutcome::with_call(format!("Call {} recalled.", call.original_id), call)
+                })
+                .ok_or_else(|| "No current call to recall.".to_string()),
+            QueueCommand::ForceSkip {
+                original_id,
+                location,
+            } => {
+                self.validate(&original_id, &location)?;
+                let call = self
+                    .queue
+                    .force_skip_call(&original_id, &location)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                Ok(CommandOutcome::with_call(
+                    format!("Call {} added directly to skipped list.", call.original_id),
+                    call,
+                ))
+            }
+            QueueCommand::TranslatorCall { location } => self
+                .translator
+                .trigger_call(&location)
+                .await
+                .map(|outcome| CommandOutcome {
+                    message: format!("Translator requested at counter {}.", outcome.location),
+                    call: None,
+                })
+                .map_err(|err| err.to_string()),
+        }
+    }
+
+    fn validate(&self, original_id: &str, location: &str) -> Result<(), String> {
+        validate_identifier(
+            original_id,
+            self.config.get().queue_identifier_prefix_required,
+        )?;
+        validate_location(location)
+    }
+}
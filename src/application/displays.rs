@@ -0,0 +1,214 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+type BroadcastSender = tokio::sync::broadcast::Sender<crate::AppEvent>;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::AppConfig,
+    displays::{
+        DisplayCommand, DisplayCommandError, DisplayCommandRecord, DisplayCommandStatus,
+        DisplayInfo, DisplayRegistry, DisplayTarget,
+    },
+    AppEvent,
+};
+
+/// How often the background monitor looks for displays that have been offline too long.
+const DISPLAY_ALERT_CHECK_INTERVAL_SECONDS: u64 = 5;
+
+/// Service that tracks connected signage displays, raises disconnect alerts and
+/// dispatches remote control commands.
+///
+/// The registry is guarded by a synchronous mutex because connections are
+/// released from `Drop`, where awaiting is not possible.
+#[derive(Clone)]
+pub struct DisplayService {
+    registry: Arc<Mutex<DisplayRegistry>>,
+    event_bus: BroadcastSender,
+}
+
+impl DisplayService {
+    /// Create the service and start the disconnect monitor when alerts are enabled.
+    pub fn new(config: Arc<AppConfig>, event_bus: BroadcastSender) -> Self {
+        let service = Self {
+            registry: Arc::new(Mutex::new(DisplayRegistry::new())),
+            event_bus,
+        };
+
+        match config.display_disconnect_alert_threshold() {
+            Some(threshold) => {
+                info!(
+                    "Display disconnect alerts enabled (threshold {:?}).",
+                    threshold
+                );
+                let monitor = service.clone();
+                tokio::spawn(async move {
+                    monitor.run_disconnect_monitor(threshold).await;
+                });
+            }
+            None => info!("Display disconnect alerts are disabled (threshold is 0)."),
+        }
+
+        service
+    }
+
+    /// Register a new event-stream connection for a display.
+    ///
+    /// The returned guard marks the connection as closed when dropped, so it should
+    /// live exactly as long as the stream it belongs to.
+    pub fn connect(
+        &self,
+        display_id: &str,
+        zone: Option<String>,
+        user_agent: Option<String>,
+        ip: Option<String>,
+    ) -> DisplayConnection {
+        let recovered = self
+            .registry()
+            .connect(display_id, zone, user_agent, ip, Utc::now());
+        if recovered {
+            info!(
+                "Display '{}' reconnected after a disconnect alert.",
+                display_id
+            );
+        }
+        DisplayConnection {
+            service: self.clone(),
+            display_id: display_id.to_string(),
+        }
+    }
+
+    /// Record a heartbeat for a display. Returns `false` for unknown displays.
+    pub fn heartbeat(&self, display_id: &str) -> bool {
+        self.registry().heartbeat(display_id, Utc::now())
+    }
+
+    /// Return every known display sorted by id.
+    pub fn list(&self) -> Vec<DisplayInfo> {
+        self.registry().list()
+    }
+
+    /// Issue a command to the targeted displays and broadcast it over the event bus.
+    ///
+    /// # Returns
+    /// The tracking record listing the displays expected to acknowledge.
+    pub fn issue_command(
+        &self,
+        target: DisplayTarget,
+        command: DisplayCommand,
+    ) -> Result<DisplayCommandRecord, DisplayCommandError> {
+        let record = self.registry().issue_command(target, command, Utc::now())?;
+
+        let event = AppEvent::DisplayCommand {
+            command_id: record.command_id.clone(),
+            target: record.target.clone(),
+            command: record.command.clone(),
+            issued_at: record.issued_at,
+        };
+        if let Err(err) = self.event_bus.send(event) {
+            debug!(
+                "DisplayService: failed to broadcast display command ({}). no active listeners?",
+                err
+            );
+        }
+        Ok(record)
+    }
+
+    /// Record a display's acknowledgement of a previously issued command.
+    pub fn acknowledge_command(
+        &self,
+        command_id: &str,
+        display_id: &str,
+        status: DisplayCommandStatus,
+        message: Option<String>,
+    ) -> Result<DisplayCommandRecord, DisplayCommandError> {
+        let record = self.registry().acknowledge_command(
+            command_id,
+            display_id,
+            status,
+            message,
+            Utc::now(),
+        )?;
+        if status == DisplayCommandStatus::Failed {
+            warn!(
+                "Display '{}' reported that command '{}' failed.",
+                display_id, command_id
+            );
+        }
+        Ok(record)
+    }
+
+    /// Return recently issued commands, newest first.
+    pub fn commands(&self) -> Vec<DisplayCommandRecord> {
+        self.registry().commands()
+    }
+
+    /// Return a single tracked command.
+    pub fn command(&self, command_id: &str) -> Option<DisplayCommandRecord> {
+        self.registry().command(command_id)
+    }
+
+    fn registry(&self) -> MutexGuard<'_, DisplayRegistry> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn run_disconnect_monitor(&self, threshold: Duration) {
+        let check_interval =
+            Duration::from_secs(DISPLAY_ALERT_CHECK_INTERVAL_SECONDS).min(threshold);
+        let mut interval = tokio::time::interval(check_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+            let overdue = self.registry().take_overdue(threshold, now);
+            for offline_display in overdue {
+                let disconnected_at = offline_display.disconnected_at.unwrap_or(now);
+                let offline_seconds = (now - disconnected_at).num_seconds().max(0) as u64;
+                warn!(
+                    "Display '{}' (ip: {:?}) has been disconnected for {}s.",
+                    offline_display.display_id, offline_display.ip, offline_seconds
+                );
+                let event = AppEvent::DisplayAlert {
+                    display_id: offline_display.display_id,
+                    disconnected_at,
+                    offline_seconds,
+                };
+                if let Err(err) = self.event_bus.send(event) {
+                    debug!(
+                        "DisplayService: failed to broadcast display alert ({}). no active listeners?",
+                        err
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Guard representing one open event stream for a display.
+pub struct DisplayConnection {
+    service: DisplayService,
+    display_id: String,
+}
+
+impl DisplayConnection {
+    /// The display id this connection belongs to.
+    pub fn display_id(&self) -> &str {
+        &self.display_id
+    }
+
+    /// Refresh the display's last heartbeat.
+    pub fn heartbeat(&self) {
+        self.service.heartbeat(&self.display_id);
+    }
+}
+
+impl Drop for DisplayConnection {
+    fn drop(&mut self) {
+        self.service
+            .registry()
+            .disconnect(&self.display_id, Utc::now());
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+use crate::{
+    config::AppConfig,
+    event_log::EventLog,
+    shutdown::{InFlight, InFlightGuard},
+    storage::{self, HistoryPage, HistoryStatus, Storage, StorageError, StoredEvent},
+    Call,
+};
+
+/// A pending write to the storage.
+enum HistoryWrite {
+    Call(HistoryStatus, Call),
+    Event(StoredEvent),
+}
+
+/// Service that persists a room's call and event history and serves it back
+/// page by page.
+///
+/// Writes go through a single background writer so they reach the storage in
+/// order without blocking queue operations.
+#[derive(Clone)]
+pub struct HistoryService {
+    storage: Arc<dyn Storage>,
+    writes: mpsc::UnboundedSender<(HistoryWrite, InFlightGuard)>,
+    /// Tracks queued writes so a graceful shutdown waits until they are stored.
+    in_flight: InFlight,
+}
+
+impl HistoryService {
+    /// Open the configured storage and start recording the room's events.
+    pub fn new(config: &AppConfig, room_id: &str, events: &EventLog, in_flight: InFlight) -> Self {
+        let service = Self::with_storage(storage::open(config, room_id), in_flight);
+
+        let recorder = service.clone();
+        let mut receiver = events.subscribe();
+        let stats = events.stats().clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(logged) => recorder.write(HistoryWrite::Event(StoredEvent::from_logged(
+                        &logged,
+                        Utc::now(),
+                    ))),
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "HistoryService: lagged and did not store {} events.",
+                            skipped
+                        );
+                        stats.record_lag("history", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        service
+    }
+
+    /// Use an already opened storage without recording events.
+    pub fn with_storage(storage: Arc<dyn Storage>, in_flight: InFlight) -> Self {
+        let (writes, mut receiver) = mpsc::unbounded_channel::<(HistoryWrite, InFlightGuard)>();
+        let writer_storage = Arc::clone(&storage);
+        tokio::spawn(async move {
+            while let Some((write, _work)) = receiver.recv().await {
+                let storage = Arc::clone(&writer_storage);
+                let result = tokio::task::spawn_blocking(move || match write {
+                    HistoryWrite::Call(status, call) => storage.record_call(status, &call),
+                    HistoryWrite::Event(event) => storage.record_event(&event),
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => error!("HistoryService: {}", err),
+                    Err(err) => error!("HistoryService: writer task failed: {}", err),
+                }
+            }
+        });
+        Self {
+            storage,
+            writes,
+            in_flight,
+        }
+    }
+
+    /// Queue calls that moved into the completed or skipped history for storage.
+    pub fn archive(&self, calls: Vec<(HistoryStatus, Call)>) {
+        for (status, call) in calls {
+            self.write(HistoryWrite::Call(status, call));
+        }
+    }
+
+    /// One page (1-based) of call history, newest first.
+    pub async fn calls(
+        &self,
+        status: Option<HistoryStatus>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<HistoryPage, StorageError> {
+        let storage = Arc::clone(&self.storage);
+        tokio::task::spawn_blocking(move || storage.call_history(status, page, page_size))
+            .await
+            .map_err(|err| StorageError::Query(err.to_string()))?
+    }
+
+    /// Up to `limit` of the most recently stored events, newest first.
+    pub async fn recent_events(&self, limit: usize) -> Result<Vec<StoredEvent>, StorageError> {
+        let storage = Arc::clone(&self.storage);
+        tokio::task::spawn_blocking(move || storage.recent_events(limit))
+            .await
+            .map_err(|err| StorageError::Query(err.to_string()))?
+    }
+
+    /// Description of the storage backend, e.g. `memory`.
+    pub fn describe(&self) -> String {
+        self.storage.describe()
+    }
+
+    fn write(&self, write: HistoryWrite) {
+        if self.writes.send((write, self.in_flight.start())).is_err() {
+            error!("HistoryService: writer stopped; history entry dropped.");
+        }
+    }
+}
@@ -1,11 +1,25 @@
 //! High-level application services orchestrating domain managers and side effects.
 
 pub mod announcements;
+pub mod appointments;
+pub mod commands;
+pub mod displays;
+pub mod history;
 pub mod queue;
+pub mod settings;
+pub mod tickets;
 pub mod translator;
 pub mod tts;
+pub mod webhooks;
 
 pub use announcements::AnnouncementService;
+pub use appointments::AppointmentService;
+pub use commands::{CommandOutcome, CommandService, QueueCommand};
+pub use displays::DisplayService;
+pub use history::HistoryService;
 pub use queue::{QueueError, QueueService};
+pub use settings::SettingsService;
+pub use tickets::TicketService;
 pub use translator::TranslatorService;
 pub use tts::TtsService;
+pub use webhooks::WebhookService;
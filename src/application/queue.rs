@@ -1,14 +1,16 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 type BroadcastSender = tokio::sync::broadcast::Sender<crate::AppEvent>;
 
+use chrono::Utc;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    application::tts::TtsService,
-    config::AppConfig,
-    queue::{manager::QueueManager, QueueState},
+    application::{history::HistoryService, tts::TtsService},
+    config::SharedConfig,
+    queue::{manager::QueueManager, NoShowAction, NoShowPolicy, QueueState, NO_SHOW_SKIP_REASON},
     Call,
 };
 
@@ -36,30 +38,54 @@ impl std::fmt::Display for QueueError {
 
 impl std::error::Error for QueueError {}
 
+/// How often the no-show watcher checks how long the current call has been waiting.
+const NO_SHOW_CHECK_INTERVAL_SECONDS: u64 = 1;
+
 /// High-level service that wraps [`QueueManager`] and coordinates side effects
-/// such as event broadcasting and TTS fan-out.
+/// such as event broadcasting, TTS fan-out and the no-show timer.
 #[derive(Clone)]
 pub struct QueueService {
-    config: Arc<AppConfig>,
+    config: SharedConfig,
     manager: Arc<Mutex<QueueManager>>,
     event_bus: BroadcastSender,
     tts: TtsService,
+    history: HistoryService,
 }
 
 impl QueueService {
     /// Construct a new queue service using configuration limits and shared dependencies.
-    pub fn new(config: Arc<AppConfig>, event_bus: BroadcastSender, tts: TtsService) -> Self {
+    ///
+    /// Calls leaving the current slot are also archived to `history`, which is
+    /// not limited by the in-memory history sizes.
+    pub fn new(
+        config: SharedConfig,
+        event_bus: BroadcastSender,
+        tts: TtsService,
+        history: HistoryService,
+    ) -> Self {
+        let startup_config = config.get();
         let queue_manager = QueueManager::new(
-            config.max_history_size,
-            config.max_skipped_history_size,
-            config.queue_identifier_prefix_required,
+            startup_config.max_history_size,
+            startup_config.max_skipped_history_size,
+            startup_config.queue_identifier_prefix_required,
         );
-        Self {
+        let service = Self {
             config,
             manager: Arc::new(Mutex::new(queue_manager)),
             event_bus,
             tts,
+            history,
+        };
+
+        if let Some(policy) = startup_config.no_show_policy() {
+            info!(
+                "QueueService: no-show timer enabled (timeout {:?}, {} automatic recalls).",
+                policy.timeout, policy.recalls
+            );
+            service.spawn_no_show_watcher(policy);
         }
+
+        service
     }
 
     /// Add or recall a call and mark it as the current call.
@@ -74,6 +100,7 @@ impl QueueService {
             .add_call(original_id.to_string(), location.to_string())
             .ok_or(QueueError::InconsistentState("add_call"))?;
         let current_call = call_ref.clone();
+        self.history.archive(manager.take_archived());
         let queue_state = manager.snapshot();
         drop(manager);
 
@@ -89,6 +116,7 @@ impl QueueService {
 
         let mut manager = self.manager.lock().await;
         let skipped_call = manager.skip_current_call();
+        self.history.archive(manager.take_archived());
         let queue_state = manager.snapshot();
         drop(manager);
 
@@ -102,6 +130,7 @@ impl QueueService {
 
         let mut manager = self.manager.lock().await;
         let completed_call = manager.complete_current_call();
+        self.history.archive(manager.take_archived());
         let queue_state = manager.snapshot();
         drop(manager);
 
@@ -124,6 +153,7 @@ impl QueueService {
         let skipped_call = manager
             .add_to_skipped_directly(original_id.to_string(), location.to_string())
             .ok_or(QueueError::InconsistentState("force_skip_call"))?;
+        self.history.archive(manager.take_archived());
         let queue_state = manager.snapshot();
         drop(manager);
 
@@ -131,15 +161,137 @@ impl QueueService {
         Ok(skipped_call)
     }
 
+    /// Announce the current call again without changing the queue.
+    ///
+    /// # Returns
+    /// The recalled call, or `None` when there is no current call.
+    pub async fn recall_current_call(&self) -> Option<Call> {
+        info!("QueueService::recall_current_call");
+
+        let manager = self.manager.lock().await;
+        let queue_state = manager.snapshot();
+        drop(manager);
+
+        let current_call = queue_state.current_call.clone()?;
+        self.broadcast_queue_update(queue_state);
+        self.trigger_tts_for_call(&current_call);
+        Some(current_call)
+    }
+
+    /// Apply the history limits of the current configuration, broadcasting the
+    /// queue when calls were trimmed.
+    pub async fn apply_history_limits(&self) {
+        let config = self.config.get();
+        let mut manager = self.manager.lock().await;
+        let trimmed =
+            manager.set_history_limits(config.max_history_size, config.max_skipped_history_size);
+        let queue_state = manager.snapshot();
+        drop(manager);
+
+        if trimmed {
+            self.broadcast_queue_update(queue_state);
+        }
+    }
+
     /// Return a snapshot of the queue for read-only consumers.
     pub async fn snapshot(&self) -> QueueState {
         let manager = self.manager.lock().await;
         let snapshot = manager.snapshot();
         drop(manager);
-        snapshot
+        self.with_zone(snapshot)
+    }
+
+    /// Watch the current call and recall or skip it according to `policy` when
+    /// nobody acts on it.
+    fn spawn_no_show_watcher(&self, policy: NoShowPolicy) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(NO_SHOW_CHECK_INTERVAL_SECONDS));
+            // Current call being watched and how often the watcher recalled it.
+            let mut watched: Option<(Call, u32)> = None;
+            loop {
+                interval.tick().await;
+                let current = service.manager.lock().await.get_current_call().cloned();
+                let Some(call) = current else {
+                    watched = None;
+                    continue;
+                };
+                let mut recalls_done = match &watched {
+                    Some((watched_call, recalls)) if same_call(watched_call, &call) => *recalls,
+                    _ => 0,
+                };
+                let elapsed = (Utc::now() - call.timestamp).to_std().unwrap_or_default();
+                match policy.action(elapsed, recalls_done) {
+                    NoShowAction::Wait => {}
+                    NoShowAction::Recall => {
+                        if service.recall_unattended_call(&call).await {
+                            recalls_done += 1;
+                        }
+                    }
+                    NoShowAction::Skip => {
+                        service.skip_no_show(&call).await;
+                    }
+                }
+                watched = Some((call, recalls_done));
+            }
+        });
+    }
+
+    /// Announce `call` again if it is still the current call.
+    async fn recall_unattended_call(&self, call: &Call) -> bool {
+        let manager = self.manager.lock().await;
+        if !manager
+            .get_current_call()
+            .is_some_and(|current| same_call(current, call))
+        {
+            return false;
+        }
+        let queue_state = manager.snapshot();
+        drop(manager);
+
+        info!(
+            "QueueService: call '{}' not attended; announcing it again.",
+            call.id
+        );
+        self.broadcast_queue_update(queue_state);
+        self.trigger_tts_for_call(call);
+        true
+    }
+
+    /// Skip `call` as a no-show if it is still the current call.
+    async fn skip_no_show(&self, call: &Call) -> Option<Call> {
+        let mut manager = self.manager.lock().await;
+        if !manager
+            .get_current_call()
+            .is_some_and(|current| same_call(current, call))
+        {
+            return None;
+        }
+        let skipped_call = manager.skip_current_call_with_reason(Some(NO_SHOW_SKIP_REASON));
+        self.history.archive(manager.take_archived());
+        let queue_state = manager.snapshot();
+        drop(manager);
+
+        info!(
+            "QueueService: call '{}' at location '{}' skipped as a no-show.",
+            call.id, call.location
+        );
+        self.broadcast_queue_update(queue_state);
+        skipped_call
+    }
+
+    /// Fill in the display zone of the current call's counter.
+    fn with_zone(&self, mut queue_state: QueueState) -> QueueState {
+        queue_state.current_zone = queue_state
+            .current_call
+            .as_ref()
+            .and_then(|call| self.config.zone_for_location(&call.location));
+        queue_state
     }
 
     fn broadcast_queue_update(&self, queue_state: QueueState) {
+        let queue_state = self.with_zone(queue_state);
         debug!("QueueService::broadcast_queue_update -> {:?}", queue_state);
         if let Err(err) = self
             .event_bus
@@ -153,7 +305,7 @@ impl QueueService {
     }
 
     fn trigger_tts_for_call(&self, call: &Call) {
-        let ordered_languages = self.config.ordered_supported_language_codes();
+        let ordered_languages = self.config.get().ordered_supported_language_codes();
         let Some(primary_lang) = ordered_languages.first() else {
             warn!(
                 "QueueService: no supported languages configured; skipping TTS trigger for call '{}'.",
@@ -179,14 +331,20 @@ impl QueueService {
     }
 }
 
+/// Whether two snapshots refer to the same call, made current at the same time.
+fn same_call(left: &Call, right: &Call) -> bool {
+    left.id == right.id && left.timestamp == right.timestamp
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shutdown::InFlight;
     use crate::AppEvent;
     use tokio::sync::broadcast;
 
     fn test_services() -> (QueueService, broadcast::Receiver<AppEvent>) {
-        let config = Arc::new(AppConfig {
+        let config = SharedConfig::new(crate::config::AppConfig {
             server_address: "127.0.0.1".parse().unwrap(),
             server_port: 3000,
             max_history_size: 5,
@@ -209,11 +367,42 @@ mod tests {
             sse_keep_alive_interval_seconds: 15,
             sse_event_buffer_size: 10,
             tts_cache_web_path: "/tts".to_string(),
+            display_disconnect_alert_seconds: 120,
+            display_zones: String::new(),
+            rooms: String::new(),
+            event_replay_buffer_size: 500,
+            webhooks_file: String::new(),
+            mqtt_broker_url: String::new(),
+            mqtt_topic_prefix: "queue".to_string(),
+            mqtt_commands_enabled: false,
+            keypad_tcp_port: 0,
+            ticket_services: "A:General".to_string(),
+            ticket_printer: String::new(),
+            ticket_header: String::new(),
+            ticket_status_url_template: String::new(),
+            ticket_notifier: String::new(),
+            ticket_notify_people_ahead: 3,
+            no_show_timeout_seconds: 0,
+            no_show_auto_recalls: 0,
+            history_database_path: String::new(),
+            settings_overrides_path: String::new(),
+            shutdown_timeout_seconds: 8,
+            announcement_schedules: String::new(),
+            trusted_proxies: String::new(),
         });
 
         let (sender, receiver) = broadcast::channel(8);
-        let tts = TtsService::new(Arc::clone(&config), sender.clone());
-        let queue_service = QueueService::new(config, sender, tts);
+        let tts = TtsService::new(config.clone(), sender.clone(), InFlight::new());
+        let limits = config.get();
+        let history = HistoryService::with_storage(
+            Arc::new(crate::storage::MemoryStorage::new(
+                limits.max_history_size,
+                limits.max_skipped_history_size,
+                limits.event_replay_buffer_size,
+            )),
+            InFlight::new(),
+        );
+        let queue_service = QueueService::new(config, sender, tts, history);
         (queue_service, receiver)
     }
 
@@ -230,4 +419,72 @@ mod tests {
             other => panic!("unexpected event: {:?}", other),
         }
     }
+
+    #[tokio::test]
+    async fn recall_requires_current_call() {
+        let (service, mut receiver) = test_services();
+        assert!(service.recall_current_call().await.is_none());
+
+        service.add_call("A7", "3").await.unwrap();
+        let _ = receiver.recv().await.unwrap();
+
+        let recalled = service.recall_current_call().await.unwrap();
+        assert_eq!(recalled.original_id, "A7");
+        match receiver.recv().await.unwrap() {
+            AppEvent::QueueUpdate(state) => {
+                assert_eq!(state.current_call, Some(recalled));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn no_show_skip_records_reason_and_ignores_stale_calls() {
+        let (service, mut receiver) = test_services();
+        let first = service.add_call("A7", "3").await.unwrap();
+        let second = service.add_call("A8", "4").await.unwrap();
+        let _ = receiver.recv().await.unwrap();
+        let _ = receiver.recv().await.unwrap();
+
+        assert!(service.skip_no_show(&first).await.is_none());
+        let skipped = service.skip_no_show(&second).await.unwrap();
+        assert_eq!(skipped.skip_reason.as_deref(), Some(NO_SHOW_SKIP_REASON));
+        match receiver.recv().await.unwrap() {
+            AppEvent::QueueUpdate(state) => {
+                assert!(state.current_call.is_none());
+                assert_eq!(state.skipped_history.back(), Some(&skipped));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn archives_calls_leaving_the_current_slot() {
+        let (service, _receiver) = test_services();
+        service.add_call("A1", "1").await.unwrap();
+        service.add_call("A2", "1").await.unwrap();
+        service.skip_current_call().await.unwrap();
+
+        // Writes are applied by a background task.
+        let mut page = service.history.calls(None, 1, 10).await.unwrap();
+        for _ in 0..50 {
+            if page.total == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            page = service.history.calls(None, 1, 10).await.unwrap();
+        }
+        let entries: Vec<_> = page
+            .items
+            .iter()
+            .map(|record| (record.status, record.call.original_id.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (crate::queue::HistoryStatus::Skipped, "A2"),
+                (crate::queue::HistoryStatus::Completed, "A1"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::{
+    config::{AppConfig, SharedConfig},
+    settings::{
+        load_overrides, save_overrides, RoomOverrides, Settings, SettingsError, SettingsOverrides,
+    },
+};
+
+/// Service keeping the settings supervisors changed at runtime, for every room.
+///
+/// Overrides are persisted to `SETTINGS_OVERRIDES_PATH` when it is set, and
+/// otherwise last until the next restart.
+#[derive(Clone)]
+pub struct SettingsService {
+    path: Option<PathBuf>,
+    overrides: Arc<Mutex<RoomOverrides>>,
+}
+
+impl SettingsService {
+    /// Open the overrides file named by the server-wide configuration.
+    ///
+    /// # Errors
+    /// The file exists but cannot be read or is not valid JSON.
+    pub fn open(config: &AppConfig) -> Result<Self, SettingsError> {
+        let path = Some(config.settings_overrides_path.trim())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        let overrides = match &path {
+            Some(path) => {
+                let overrides = load_overrides(path)?;
+                info!(
+                    "Loaded settings overrides for {} room(s) from {:?}.",
+                    overrides.len(),
+                    path
+                );
+                overrides
+            }
+            None => {
+                info!("SETTINGS_OVERRIDES_PATH is not set; settings changes are not persisted.");
+                RoomOverrides::new()
+            }
+        };
+        Ok(Self {
+            path,
+            overrides: Arc::new(Mutex::new(overrides)),
+        })
+    }
+
+    /// Returns true when changes are written to disk.
+    pub fn is_persisted(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// The overrides currently applied to a room.
+    pub async fn overrides(&self, room_id: &str) -> SettingsOverrides {
+        self.overrides
+            .lock()
+            .await
+            .get(room_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Apply a room's overrides to a configuration read from the environment
+    /// and configuration file.
+    pub async fn apply_to(&self, room_id: &str, config: &mut AppConfig) {
+        if let Some(overrides) = self.overrides.lock().await.get(room_id) {
+            debug!(
+                "Applying settings overrides to room '{}': {:?}",
+                room_id, overrides
+            );
+            overrides.apply(config);
+        }
+    }
+
+    /// Merge `patch` into a room's overrides, validate the resulting
+    /// configuration, persist the overrides and switch `config` to it.
+    ///
+    /// Nothing changes when validation or saving fails.
+    ///
+    /// # Returns
+    /// The settings now in effect.
+    pub async fn update(
+        &self,
+        room_id: &str,
+        config: &SharedConfig,
+        patch: SettingsOverrides,
+    ) -> Result<Settings, SettingsError> {
+        let mut overrides = self.overrides.lock().await;
+        let mut merged = overrides.get(room_id).cloned().unwrap_or_default();
+        merged.merge(patch);
+
+        let mut next = AppConfig::clone(&config.get());
+        merged.apply(&mut next);
+        let mut errors = merged.validate();
+        errors.extend(next.validate());
+        if !errors.is_empty() {
+            return Err(SettingsError::Invalid(errors));
+        }
+
+        let mut updated = overrides.clone();
+        if merged.is_empty() {
+            updated.remove(room_id);
+        } else {
+            updated.insert(room_id.to_string(), merged);
+        }
+        if let Some(path) = &self.path {
+            save_overrides(path, &updated)?;
+            debug!("Saved settings overrides to {:?}.", path);
+        }
+        *overrides = updated;
+
+        let settings = Settings::from_config(&next);
+        config.replace(next);
+        info!("Settings of room '{}' updated: {:?}", room_id, settings);
+        Ok(settings)
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+type BroadcastSender = tokio::sync::broadcast::Sender<crate::AppEvent>;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    config::{AppConfig, TicketServiceDefinition},
+    event_log::EventBusStats,
+    notifications::{self, Notification, NotificationKind, Notifier},
+    tickets::{escpos, TicketBook, TicketError, TicketPrinter, TicketView},
+    AppEvent,
+};
+
+/// Outcome of sending a ticket to the printer.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PrintOutcome {
+    /// `TICKET_PRINTER` is not configured.
+    Disabled,
+    Printed,
+    Failed {
+        error: String,
+    },
+}
+
+/// A freshly issued ticket and what happened when printing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedTicket {
+    #[serde(flatten)]
+    pub ticket: TicketView,
+    pub print: PrintOutcome,
+}
+
+/// Service that issues tickets, prints them, marks them called when their
+/// number is called at a counter and notifies patients who left contact details.
+#[derive(Clone)]
+pub struct TicketService {
+    config: Arc<AppConfig>,
+    room_id: String,
+    book: Arc<Mutex<TicketBook>>,
+    printer: Option<TicketPrinter>,
+    notifier: Option<Arc<dyn Notifier>>,
+}
+
+impl TicketService {
+    /// Create the service and start watching the event bus for calls.
+    pub fn new(
+        config: Arc<AppConfig>,
+        room_id: &str,
+        event_bus: BroadcastSender,
+        stats: EventBusStats,
+    ) -> Self {
+        let services = config.ticket_services();
+        if services.is_empty() {
+            warn!(
+                "No valid TICKET_SERVICES for room '{}'; ticket issuance is unavailable.",
+                room_id
+            );
+        }
+        let printer = match TicketPrinter::parse(&config.ticket_printer) {
+            Ok(Some(printer)) => {
+                info!("Tickets for room '{}' are printed to {}.", room_id, printer);
+                Some(printer)
+            }
+            Ok(None) => {
+                info!("Ticket printing is disabled for room '{}'.", room_id);
+                None
+            }
+            Err(err) => {
+                error!(
+                    "{}. Ticket printing is disabled for room '{}'.",
+                    err, room_id
+                );
+                None
+            }
+        };
+
+        let notifier = match notifications::from_target(&config.ticket_notifier) {
+            Ok(Some(notifier)) => {
+                info!(
+                    "Ticket notifications for room '{}' are sent to {}.",
+                    room_id,
+                    notifier.describe()
+                );
+                Some(notifier)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                error!(
+                    "{}. Ticket notifications are disabled for room '{}'.",
+                    err, room_id
+                );
+                None
+            }
+        };
+
+        let service = Self {
+            book: Arc::new(Mutex::new(TicketBook::new(
+                services,
+                config.queue_identifier_prefix_required,
+            ))),
+            config,
+            room_id: room_id.to_string(),
+            printer,
+            notifier,
+        };
+
+        let watcher = service.clone();
+        let mut events = event_bus.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(AppEvent::QueueUpdate(state)) => {
+                        if let Some(call) = state.current_call {
+                            watcher.mark_called(&call.original_id, &call.location);
+                        }
+                        watcher.notify_approaching();
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "TicketService: lagged and lost {} events; called tickets may stay waiting.",
+                            skipped
+                        );
+                        stats.record_lag("tickets", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        service
+    }
+
+    /// Issue a ticket for a service (the first configured one when `None`) and
+    /// print it when a printer is configured.
+    ///
+    /// `contact` is passed to the notifier when the ticket comes close to being
+    /// called. A printing failure does not undo the ticket; it is reported in the
+    /// result.
+    pub async fn issue(
+        &self,
+        service: Option<&str>,
+        contact: Option<String>,
+    ) -> Result<IssuedTicket, TicketError> {
+        let ticket = self.book().issue(service, contact, Utc::now())?;
+        info!(
+            "Issued ticket {} for service '{}' ({} ahead).",
+            ticket.ticket.number, ticket.ticket.service, ticket.people_ahead
+        );
+        Ok(self.print(ticket).await)
+    }
+
+    /// Issue and print the ticket of a checked-in appointment due at `due_at`.
+    pub async fn issue_for_appointment(
+        &self,
+        service: Option<&str>,
+        contact: Option<String>,
+        appointment_id: &str,
+        due_at: DateTime<Utc>,
+    ) -> Result<IssuedTicket, TicketError> {
+        let ticket = self.book().issue_for_appointment(
+            service,
+            contact,
+            appointment_id,
+            due_at,
+            Utc::now(),
+        )?;
+        info!(
+            "Issued ticket {} for appointment '{}' due at {}.",
+            ticket.ticket.number, appointment_id, due_at
+        );
+        Ok(self.print(ticket).await)
+    }
+
+    /// Take the next waiting ticket (see [`TicketBook::next_waiting`]) and mark
+    /// it called to `location`. Returns its number, to be called on the queue;
+    /// the patient is notified once that call is current.
+    pub fn call_next(&self, service: Option<&str>, location: &str) -> Option<String> {
+        let ticket = self.book().take_next(service, location, Utc::now())?;
+        info!("Ticket {} called to counter {}.", ticket.number, location);
+        Some(ticket.number)
+    }
+
+    /// Put a ticket taken by [`TicketService::call_next`] back in the waiting
+    /// list when its call failed.
+    pub fn return_to_waiting(&self, number: &str) {
+        if self.book().return_to_waiting(number) {
+            info!("Ticket {} returned to the waiting list.", number);
+        }
+    }
+
+    async fn print(&self, ticket: TicketView) -> IssuedTicket {
+        let print = match &self.printer {
+            None => PrintOutcome::Disabled,
+            Some(printer) => {
+                let status_url = self
+                    .config
+                    .ticket_status_url(&self.room_id, &ticket.ticket.number);
+                let bytes = escpos::render_ticket(
+                    &ticket,
+                    &self.config.ticket_header,
+                    status_url.as_deref(),
+                );
+                match printer.print(&bytes).await {
+                    Ok(()) => {
+                        debug!(
+                            "Printed ticket {} ({} bytes).",
+                            ticket.ticket.number,
+                            bytes.len()
+                        );
+                        PrintOutcome::Printed
+                    }
+                    Err(err) => {
+                        error!("Failed to print ticket {}: {}", ticket.ticket.number, err);
+                        PrintOutcome::Failed {
+                            error: err.to_string(),
+                        }
+                    }
+                }
+            }
+        };
+
+        IssuedTicket { ticket, print }
+    }
+
+    /// Look up a waiting or recently called ticket.
+    pub fn ticket(&self, number: &str) -> Option<TicketView> {
+        self.book().get(number)
+    }
+
+    /// Waiting tickets in issue order.
+    pub fn waiting(&self) -> Vec<TicketView> {
+        self.book().waiting()
+    }
+
+    /// Services tickets can be issued for.
+    pub fn services(&self) -> Vec<TicketServiceDefinition> {
+        self.book().services().to_vec()
+    }
+
+    fn mark_called(&self, original_id: &str, location: &str) {
+        let called = self.book().mark_called(original_id, location, Utc::now());
+        if let Some(ticket) = called {
+            info!("Ticket {} called to counter {}.", ticket.number, location);
+        }
+        // Also covers tickets taken by `call_next`; a ticket is notified once.
+        self.notify(original_id, NotificationKind::Called, Some(location));
+    }
+
+    fn notify_approaching(&self) {
+        if self.notifier.is_none() {
+            return;
+        }
+        let numbers = self
+            .book()
+            .approaching(self.config.ticket_notify_people_ahead);
+        for number in numbers {
+            self.notify(&number, NotificationKind::Approaching, None);
+        }
+    }
+
+    /// Records a pending notification and delivers it in the background.
+    fn notify(&self, number: &str, kind: NotificationKind, location: Option<&str>) {
+        let Some(notifier) = self.notifier.clone() else {
+            return;
+        };
+        let Some(view) = self.book().begin_notification(number, kind, Utc::now()) else {
+            return;
+        };
+        let Some(contact) = view.ticket.contact else {
+            return;
+        };
+        let notification = Notification {
+            kind,
+            room: self.room_id.clone(),
+            ticket: view.ticket.number,
+            service_name: view.ticket.service_name,
+            contact,
+            people_ahead: view.people_ahead,
+            location: location.map(str::to_string),
+            created_at: Utc::now(),
+        };
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let error = match notifier.notify(&notification).await {
+                Ok(()) => {
+                    debug!(
+                        "Sent {} notification for ticket {} via {}.",
+                        kind,
+                        notification.ticket,
+                        notifier.describe()
+                    );
+                    None
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to send {} notification for ticket {}: {}",
+                        kind, notification.ticket, err
+                    );
+                    Some(err.to_string())
+                }
+            };
+            service
+                .book()
+                .finish_notification(&notification.ticket, kind, error, Utc::now());
+        });
+    }
+
+    fn book(&self) -> MutexGuard<'_, TicketBook> {
+        self.book.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -7,7 +7,7 @@ use serde::Serialize;
 use tokio::sync::Mutex;
 use tracing::{debug, info};
 
-use crate::config::AppConfig;
+use crate::config::SharedConfig;
 use crate::AppEvent;
 
 /// Snapshot of the current translator call cooldown status.
@@ -66,13 +66,13 @@ impl std::error::Error for TranslatorCallError {}
 /// Coordinates translator call triggers, enforcing cooldowns and broadcasting SSE events.
 #[derive(Clone)]
 pub struct TranslatorService {
-    config: Arc<AppConfig>,
+    config: SharedConfig,
     event_bus: BroadcastSender,
     last_trigger: Arc<Mutex<Option<Instant>>>,
 }
 
 impl TranslatorService {
-    pub fn new(config: Arc<AppConfig>, event_bus: BroadcastSender) -> Self {
+    pub fn new(config: SharedConfig, event_bus: BroadcastSender) -> Self {
         Self {
             config,
             event_bus,
@@ -82,7 +82,10 @@ impl TranslatorService {
 
     /// Returns the current translator cooldown status.
     pub async fn current_status(&self) -> TranslatorStatus {
-        let cooldown_seconds = self.config.announcement_manual_trigger_cooldown_seconds;
+        let cooldown_seconds = self
+            .config
+            .get()
+            .announcement_manual_trigger_cooldown_seconds;
         let remaining = self.cooldown_remaining_seconds().await;
         TranslatorStatus {
             cooldown_seconds,
@@ -98,7 +101,10 @@ impl TranslatorService {
     ) -> Result<TranslatorCallOutcome, TranslatorCallError> {
         let sanitized_location = Self::sanitize_location(location)?;
 
-        let cooldown_seconds = self.config.announcement_manual_trigger_cooldown_seconds;
+        let cooldown_seconds = self
+            .config
+            .get()
+            .announcement_manual_trigger_cooldown_seconds;
         let cooldown_duration = Duration::from_secs(cooldown_seconds);
 
         {
@@ -130,6 +136,7 @@ impl TranslatorService {
             audio_urls: playlist,
             cooldown_seconds,
             cooldown_remaining_seconds: cooldown_seconds,
+            zone: self.config.zone_for_location(&sanitized_location),
         };
 
         if let Err(err) = self.event_bus.send(event) {
@@ -152,7 +159,10 @@ impl TranslatorService {
     }
 
     async fn cooldown_remaining_seconds(&self) -> u64 {
-        let cooldown_seconds = self.config.announcement_manual_trigger_cooldown_seconds;
+        let cooldown_seconds = self
+            .config
+            .get()
+            .announcement_manual_trigger_cooldown_seconds;
         if cooldown_seconds == 0 {
             return 0;
         }
@@ -2,7 +2,11 @@ use std::{collections::HashMap, sync::Arc};
 
 type BroadcastSender = tokio::sync::broadcast::Sender<crate::AppEvent>;
 
-use crate::{config::AppConfig, tts::manager::TTSManager};
+use crate::{
+    config::SharedConfig,
+    shutdown::InFlight,
+    tts::manager::{OnlineTtsStatus, TTSManager},
+};
 
 /// Service wrapper around [`TTSManager`] that exposes a narrow interface required by the API layer.
 #[derive(Clone)]
@@ -11,9 +15,9 @@ pub struct TtsService {
 }
 
 impl TtsService {
-    /// Initialise a new TTS service instance.
-    pub fn new(config: Arc<AppConfig>, event_bus: BroadcastSender) -> Self {
-        let manager = TTSManager::new(config, event_bus);
+    /// Initialise a new TTS service instance; generation tasks are tracked by `in_flight`.
+    pub fn new(config: SharedConfig, event_bus: BroadcastSender, in_flight: InFlight) -> Self {
+        let manager = TTSManager::new(config, event_bus, in_flight);
         Self {
             manager: Arc::new(manager),
         }
@@ -25,8 +29,13 @@ impl TtsService {
             .trigger_tts_generation(id.to_string(), location.to_string(), lang.to_string())
     }
 
+    /// When online generation last succeeded and failed.
+    pub fn online_status(&self) -> OnlineTtsStatus {
+        self.manager.online_status()
+    }
+
     /// Return the configured set of supported language codes and their display names.
     pub fn supported_languages_map(&self) -> HashMap<String, String> {
-        self.manager.get_supported_languages().clone()
+        self.manager.get_supported_languages()
     }
 }
@@ -0,0 +1,281 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::Client as ReqwestClient;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    config::AppConfig,
+    event_log::{EventBusStats, EventLog, LoggedEvent},
+    webhooks::{
+        self, DeliveryLog, DeliveryRecord, DeliveryStatus, WebhookSubscription,
+        WebhookSubscriptionSummary,
+    },
+};
+
+/// Events queued per subscriber before new ones are dead-lettered straight away.
+const WEBHOOK_QUEUE_CAPACITY: usize = 256;
+
+/// An event queued for one subscriber, with the record created when it was
+/// queued so the delivery keeps its `created_at`.
+type QueuedDelivery = (Arc<LoggedEvent>, DeliveryRecord);
+
+/// JSON body POSTed to webhook subscribers.
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event_id: u64,
+    room: &'a str,
+    event: &'a crate::AppEvent,
+    sent_at: chrono::DateTime<Utc>,
+}
+
+/// Snapshot returned by `GET /api/webhooks/deliveries`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDeliveryReport {
+    pub enabled: bool,
+    pub subscriptions: Vec<WebhookSubscriptionSummary>,
+    /// Recent deliveries, newest first.
+    pub deliveries: Vec<DeliveryRecord>,
+    /// Deliveries that exhausted their attempts, newest first.
+    pub dead_letters: Vec<DeliveryRecord>,
+}
+
+/// Service that forwards room events to the subscribers listed in `WEBHOOKS_FILE`.
+///
+/// A dispatcher task reads the room's [`EventLog`] and hands each matching event
+/// to one worker per subscriber, so a slow or failing endpoint never delays the
+/// others. Workers deliver in event order and retry with exponential backoff.
+#[derive(Clone)]
+pub struct WebhookService {
+    subscriptions: Arc<Vec<WebhookSubscription>>,
+    log: Arc<Mutex<DeliveryLog>>,
+}
+
+impl WebhookService {
+    /// Load the subscriptions of a room and start delivering its events.
+    ///
+    /// An unreadable or invalid file is logged and leaves webhooks disabled.
+    pub fn new(config: Arc<AppConfig>, events: &EventLog, room_id: &str) -> Self {
+        let subscriptions = if config.webhooks_file.trim().is_empty() {
+            info!(
+                "Webhooks are disabled for room '{}' (WEBHOOKS_FILE is empty).",
+                room_id
+            );
+            Vec::new()
+        } else {
+            match webhooks::load_subscriptions(Path::new(config.webhooks_file.trim())) {
+                Ok(subscriptions) => subscriptions,
+                Err(err) => {
+                    error!("{}. Webhooks are disabled for room '{}'.", err, room_id);
+                    Vec::new()
+                }
+            }
+        };
+
+        let service = Self {
+            subscriptions: Arc::new(subscriptions),
+            log: Arc::new(Mutex::new(DeliveryLog::new())),
+        };
+        if service.subscriptions.is_empty() {
+            return service;
+        }
+
+        info!(
+            "Webhooks enabled for room '{}' with {} subscription(s).",
+            room_id,
+            service.subscriptions.len()
+        );
+        let client = ReqwestClient::new();
+        let mut queues = Vec::with_capacity(service.subscriptions.len());
+        for subscription in service.subscriptions.iter() {
+            let (sender, receiver) = mpsc::channel(WEBHOOK_QUEUE_CAPACITY);
+            queues.push((subscription.clone(), sender));
+            let worker = service.clone();
+            let subscription = subscription.clone();
+            let client = client.clone();
+            let room_id = room_id.to_string();
+            tokio::spawn(async move {
+                worker
+                    .run_worker(subscription, client, room_id, receiver)
+                    .await;
+            });
+        }
+
+        let dispatcher = service.clone();
+        let receiver = events.subscribe();
+        let stats = events.stats().clone();
+        tokio::spawn(async move {
+            dispatcher.run_dispatcher(receiver, queues, stats).await;
+        });
+
+        service
+    }
+
+    /// Configured subscriptions (without secrets) and recent delivery progress.
+    pub fn deliveries(&self) -> WebhookDeliveryReport {
+        let log = self.log();
+        WebhookDeliveryReport {
+            enabled: !self.subscriptions.is_empty(),
+            subscriptions: self.subscriptions.iter().map(Into::into).collect(),
+            deliveries: log.recent(),
+            dead_letters: log.dead_letters(),
+        }
+    }
+
+    fn log(&self) -> MutexGuard<'_, DeliveryLog> {
+        self.log.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn run_dispatcher(
+        &self,
+        mut receiver: tokio::sync::broadcast::Receiver<Arc<LoggedEvent>>,
+        queues: Vec<(WebhookSubscription, mpsc::Sender<QueuedDelivery>)>,
+        stats: EventBusStats,
+    ) {
+        loop {
+            let logged = match receiver.recv().await {
+                Ok(logged) => logged,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Webhooks: dispatcher lagged and lost {} events. Consider increasing SSE_EVENT_BUFFER_SIZE.",
+                        skipped
+                    );
+                    stats.record_lag("webhooks", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            for (subscription, queue) in &queues {
+                if !subscription.wants(&logged.event) {
+                    continue;
+                }
+                let mut record = DeliveryRecord::new(
+                    &subscription.id,
+                    logged.id,
+                    logged.event.event_name(),
+                    Utc::now(),
+                );
+                if queue
+                    .try_send((Arc::clone(&logged), record.clone()))
+                    .is_ok()
+                {
+                    self.log().upsert(record);
+                } else {
+                    warn!(
+                        "Webhooks: queue for '{}' is full; dead-lettering event {}.",
+                        subscription.id, logged.id
+                    );
+                    record.status = DeliveryStatus::DeadLettered;
+                    record.last_error = Some("delivery queue full".to_string());
+                    self.log().upsert(record);
+                }
+            }
+        }
+        debug!("Webhooks: event log closed, dispatcher stopped.");
+    }
+
+    async fn run_worker(
+        &self,
+        subscription: WebhookSubscription,
+        client: ReqwestClient,
+        room_id: String,
+        mut receiver: mpsc::Receiver<QueuedDelivery>,
+    ) {
+        while let Some((logged, record)) = receiver.recv().await {
+            self.deliver(&subscription, &client, &room_id, &logged, record)
+                .await;
+        }
+        debug!("Webhooks: worker for '{}' stopped.", subscription.id);
+    }
+
+    async fn deliver(
+        &self,
+        subscription: &WebhookSubscription,
+        client: &ReqwestClient,
+        room_id: &str,
+        logged: &LoggedEvent,
+        mut record: DeliveryRecord,
+    ) {
+        let event_type = logged.event.event_name();
+        let body = match serde_json::to_vec(&WebhookPayload {
+            event_id: logged.id,
+            room: room_id,
+            event: &logged.event,
+            sent_at: Utc::now(),
+        }) {
+            Ok(body) => body,
+            Err(err) => {
+                error!("Webhooks: failed to serialise event {}: {}", logged.id, err);
+                return;
+            }
+        };
+        let signature = webhooks::sign_payload(&subscription.secret, &body);
+
+        loop {
+            record.attempts += 1;
+            let result = client
+                .post(&subscription.url)
+                .timeout(Duration::from_secs(subscription.timeout_seconds))
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(webhooks::SIGNATURE_HEADER, &signature)
+                .header(webhooks::EVENT_HEADER, event_type)
+                .header(webhooks::DELIVERY_HEADER, &record.delivery_id)
+                .body(body.clone())
+                .send()
+                .await;
+
+            let failure = match result {
+                Ok(response) if response.status().is_success() => None,
+                Ok(response) => Some(format!("HTTP {}", response.status())),
+                Err(err) => Some(err.to_string()),
+            };
+
+            let now = Utc::now();
+            record.updated_at = now;
+            match failure {
+                None => {
+                    debug!(
+                        "Webhooks: delivered {} to '{}' (attempt {}).",
+                        record.delivery_id, subscription.id, record.attempts
+                    );
+                    record.status = DeliveryStatus::Delivered;
+                    record.next_attempt_at = None;
+                    record.last_error = None;
+                    self.log().upsert(record);
+                    return;
+                }
+                Some(reason) if record.attempts >= subscription.max_attempts => {
+                    error!(
+                        "Webhooks: giving up on {} for '{}' after {} attempts: {}",
+                        record.delivery_id, subscription.id, record.attempts, reason
+                    );
+                    record.status = DeliveryStatus::DeadLettered;
+                    record.next_attempt_at = None;
+                    record.last_error = Some(reason);
+                    self.log().upsert(record);
+                    return;
+                }
+                Some(reason) => {
+                    let delay = subscription.retry_delay(record.attempts);
+                    warn!(
+                        "Webhooks: attempt {} of {} for '{}' failed ({}); retrying in {:?}.",
+                        record.attempts, subscription.max_attempts, subscription.id, reason, delay
+                    );
+                    record.status = DeliveryStatus::Retrying;
+                    record.next_attempt_at = chrono::Duration::from_std(delay)
+                        .ok()
+                        .map(|delay| now + delay);
+                    record.last_error = Some(reason);
+                    self.log().upsert(record.clone());
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// src/appointments/csv.rs
+
+//! Minimal CSV reader for appointment exports.
+//!
+//! The first line is a header naming the columns. `id` and `scheduled_at` are
+//! required; `service`, `name` and `contact` are optional and other columns are
+//! ignored. Fields may be wrapped in double quotes (with `""` for a literal quote).
+
+use super::{AppointmentError, AppointmentInput};
+
+/// Parses a CSV document into appointment inputs.
+pub fn parse_appointments_csv(text: &str) -> Result<Vec<AppointmentInput>, AppointmentError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = split_line(header.trim_start_matches('\u{feff}'))
+        .into_iter()
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let (Some(id_column), Some(time_column)) = (column("id"), column("scheduled_at")) else {
+        return Err(AppointmentError::InvalidRecord(
+            "CSV header must contain 'id' and 'scheduled_at' columns".to_string(),
+        ));
+    };
+    let service_column = column("service");
+    let name_column = column("name");
+    let contact_column = column("contact");
+
+    lines
+        .map(|(index, line)| {
+            let fields = split_line(line);
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|column| fields.get(column))
+                    .map(|value| value.trim().to_string())
+            };
+            let id = field(Some(id_column)).filter(|id| !id.is_empty());
+            let scheduled_at = field(Some(time_column)).filter(|time| !time.is_empty());
+            match (id, scheduled_at) {
+                (Some(id), Some(scheduled_at)) => Ok(AppointmentInput {
+                    id,
+                    scheduled_at,
+                    service: field(service_column),
+                    name: field(name_column),
+                    contact: field(contact_column),
+                }),
+                _ => Err(AppointmentError::InvalidRecord(format!(
+                    "line {} is missing id or scheduled_at",
+                    index + 1
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_header_quotes_and_optional_columns() {
+        let text = "ID,Name,scheduled_at,service,extra\r\n\
+                    1001,\"Doe, Jane\",2026-03-01 09:00,B,x\r\n\
+                    \r\n\
+                    1002,\"Said \"\"Hi\"\"\",2026-03-01 09:15,,\r\n";
+        let inputs = parse_appointments_csv(text).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0].id, "1001");
+        assert_eq!(inputs[0].name.as_deref(), Some("Doe, Jane"));
+        assert_eq!(inputs[0].service.as_deref(), Some("B"));
+        assert_eq!(inputs[1].name.as_deref(), Some("Said \"Hi\""));
+        assert_eq!(inputs[1].service.as_deref(), Some(""));
+        assert_eq!(inputs[1].contact, None);
+
+        assert!(parse_appointments_csv("id,name\n1,x").is_err());
+        assert!(matches!(
+            parse_appointments_csv("id,scheduled_at\n,2026-03-01 09:00"),
+            Err(AppointmentError::InvalidRecord(message)) if message.contains("line 2")
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+// src/appointments/mod.rs
+
+//! Scheduled appointments that are turned into tickets at check-in.
+//!
+//! Appointments are imported ahead of time (as JSON or CSV) and wait until the
+//! patient checks in. Check-in issues a ticket carrying the appointment time as
+//! its due time, so call-next interleaves the patient with walk-ins once the
+//! slot has arrived.
+
+pub mod csv;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::tickets::TicketError;
+
+/// Where an appointment is in its lifecycle.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AppointmentStatus {
+    Scheduled,
+    CheckedIn,
+}
+
+/// A scheduled appointment.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Appointment {
+    /// Identifier from the booking system, used for check-in.
+    pub id: String,
+    /// Patient name or reference shown to staff.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Ticket service prefix; the first configured service when not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    pub scheduled_at: DateTime<Utc>,
+    pub status: AppointmentStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_in_at: Option<DateTime<Utc>>,
+    /// Number of the ticket issued at check-in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<String>,
+    /// Contact details passed on to the ticket. Never exposed through the API.
+    #[serde(skip)]
+    pub contact: Option<String>,
+}
+
+/// An appointment as submitted for import.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct AppointmentInput {
+    pub id: String,
+    /// RFC 3339 timestamp, or `YYYY-MM-DD HH:MM` in the server's local time.
+    pub scheduled_at: String,
+    #[serde(default)]
+    pub service: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub contact: Option<String>,
+}
+
+/// Errors raised by the appointment store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppointmentError {
+    /// An imported record is malformed.
+    InvalidRecord(String),
+    NotFound(String),
+    /// The appointment has already been checked in and cannot change.
+    AlreadyCheckedIn(String),
+    /// Issuing the check-in ticket failed.
+    Ticket(TicketError),
+}
+
+impl fmt::Display for AppointmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppointmentError::InvalidRecord(reason) => {
+                write!(f, "Invalid appointment: {}", reason)
+            }
+            AppointmentError::NotFound(id) => write!(f, "Appointment '{}' not found", id),
+            AppointmentError::AlreadyCheckedIn(id) => {
+                write!(f, "Appointment '{}' is already checked in", id)
+            }
+            AppointmentError::Ticket(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for AppointmentError {}
+
+impl From<TicketError> for AppointmentError {
+    fn from(err: TicketError) -> Self {
+        AppointmentError::Ticket(err)
+    }
+}
+
+/// Parses an appointment time: RFC 3339, or `YYYY-MM-DD HH:MM[:SS]` (a `T`
+/// separator is also accepted) in the server's local time zone.
+pub fn parse_scheduled_at(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+    .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+    .map(|local| local.with_timezone(&Utc))
+}
+
+/// Appointments of one room, ordered by time.
+#[derive(Debug, Default)]
+pub struct AppointmentBook {
+    appointments: Vec<Appointment>,
+}
+
+impl AppointmentBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces appointments by id. The whole batch is rejected when any
+    /// record is invalid or would replace a checked-in appointment. Returns the
+    /// number of imported appointments.
+    pub fn import(&mut self, inputs: Vec<AppointmentInput>) -> Result<usize, AppointmentError> {
+        let mut parsed = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let id = input.id.trim().to_string();
+            if id.is_empty() {
+                return Err(AppointmentError::InvalidRecord("missing id".to_string()));
+            }
+            let scheduled_at = parse_scheduled_at(&input.scheduled_at).ok_or_else(|| {
+                AppointmentError::InvalidRecord(format!(
+                    "'{}' has an unreadable scheduled_at '{}'",
+                    id, input.scheduled_at
+                ))
+            })?;
+            if self
+                .get(&id)
+                .is_some_and(|existing| existing.status == AppointmentStatus::CheckedIn)
+            {
+                return Err(AppointmentError::AlreadyCheckedIn(id));
+            }
+            parsed.push(Appointment {
+                id,
+                name: non_empty(input.name),
+                service: non_empty(input.service),
+                scheduled_at,
+                status: AppointmentStatus::Scheduled,
+                checked_in_at: None,
+                ticket: None,
+                contact: non_empty(input.contact),
+            });
+        }
+
+        let count = parsed.len();
+        for appointment in parsed {
+            self.appointments
+                .retain(|existing| existing.id != appointment.id);
+            self.appointments.push(appointment);
+        }
+        self.appointments
+            .sort_by_key(|appointment| appointment.scheduled_at);
+        Ok(count)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Appointment> {
+        self.appointments
+            .iter()
+            .find(|appointment| appointment.id == id)
+    }
+
+    /// All appointments ordered by time.
+    pub fn list(&self) -> &[Appointment] {
+        &self.appointments
+    }
+
+    /// Marks a scheduled appointment as checked in and returns it.
+    pub fn check_in(
+        &mut self,
+        id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Appointment, AppointmentError> {
+        let appointment = self
+            .appointments
+            .iter_mut()
+            .find(|appointment| appointment.id == id)
+            .ok_or_else(|| AppointmentError::NotFound(id.to_string()))?;
+        if appointment.status == AppointmentStatus::CheckedIn {
+            return Err(AppointmentError::AlreadyCheckedIn(id.to_string()));
+        }
+        appointment.status = AppointmentStatus::CheckedIn;
+        appointment.checked_in_at = Some(now);
+        Ok(appointment.clone())
+    }
+
+    /// Records the ticket issued at check-in.
+    pub fn set_ticket(&mut self, id: &str, number: &str) {
+        if let Some(appointment) = self
+            .appointments
+            .iter_mut()
+            .find(|appointment| appointment.id == id)
+        {
+            appointment.ticket = Some(number.to_string());
+        }
+    }
+
+    /// Reverts a check-in whose ticket could not be issued.
+    pub fn undo_check_in(&mut self, id: &str) {
+        if let Some(appointment) = self
+            .appointments
+            .iter_mut()
+            .find(|appointment| appointment.id == id)
+        {
+            appointment.status = AppointmentStatus::Scheduled;
+            appointment.checked_in_at = None;
+            appointment.ticket = None;
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: &str, scheduled_at: &str) -> AppointmentInput {
+        AppointmentInput {
+            id: id.to_string(),
+            scheduled_at: scheduled_at.to_string(),
+            service: None,
+            name: None,
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn imports_sorts_and_checks_in() {
+        let mut book = AppointmentBook::new();
+        let imported = book
+            .import(vec![
+                input("b", "2026-03-01T10:30:00+07:00"),
+                input("a", "2026-03-01T09:00:00+07:00"),
+            ])
+            .unwrap();
+        assert_eq!(imported, 2);
+        let ids: Vec<_> = book.list().iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        assert!(matches!(
+            book.import(vec![input("c", "tomorrow")]),
+            Err(AppointmentError::InvalidRecord(_))
+        ));
+        assert_eq!(book.list().len(), 2, "a rejected batch changes nothing");
+
+        let now = Utc::now();
+        assert_eq!(
+            book.check_in("a", now).unwrap().status,
+            AppointmentStatus::CheckedIn
+        );
+        assert_eq!(
+            book.check_in("a", now),
+            Err(AppointmentError::AlreadyCheckedIn("a".to_string()))
+        );
+        assert_eq!(
+            book.import(vec![input("a", "2026-03-01 11:00")]),
+            Err(AppointmentError::AlreadyCheckedIn("a".to_string()))
+        );
+        assert_eq!(
+            book.check_in("zzz", now),
+            Err(AppointmentError::NotFound("zzz".to_string()))
+        );
+        book.undo_check_in("a");
+        assert_eq!(book.get("a").unwrap().status, AppointmentStatus::Scheduled);
+    }
+
+    #[test]
+    fn parses_local_and_rfc3339_times() {
+        assert_eq!(
+            parse_scheduled_at("2026-03-01T02:00:00Z"),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap())
+        );
+        let local = Local.with_ymd_and_hms(2026, 3, 1, 9, 15, 0).unwrap();
+        assert_eq!(
+            parse_scheduled_at("2026-03-01 09:15"),
+            Some(local.with_timezone(&Utc))
+        );
+        assert!(parse_scheduled_at("09:15").is_none());
+    }
+}
@@ -0,0 +1,18 @@
+// src/bin/queuectl.rs
+
+//! `queuectl`: operates and inspects a running server from the command line.
+//! See [`queue_calling_system::ctl`] for the commands.
+
+use queue_calling_system::ctl::{Ctl, SERVER_ENV};
+
+#[tokio::main]
+async fn main() {
+    let result = match Ctl::parse(std::env::args().skip(1), std::env::var(SERVER_ENV).ok()) {
+        Ok(ctl) => ctl.run().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
+}
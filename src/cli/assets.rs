@@ -0,0 +1,183 @@
+// src/cli/assets.rs
+
+//! `verify-assets`: checks the media files the configuration refers to.
+
+use std::path::{Path, PathBuf};
+
+use crate::announcements::manifest;
+use crate::cli::report::{Report, Section};
+use crate::config::{AppConfig, ConfigSource, DEFAULT_ROOM_ID};
+use crate::settings::load_overrides;
+use crate::tts::stems;
+
+/// Extensions of audio files played from announcement slots.
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg"];
+
+/// Extensions of banner media shown on displays.
+const BANNER_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "svg", "mp4", "webm", "ogg", "mov",
+];
+
+/// Checks the announcement slots, banners and audio stems of every room.
+pub fn verify_assets(source: &ConfigSource) -> Report {
+    let mut report = Report::default();
+    let configs = match load_configs(source) {
+        Ok(configs) => configs,
+        Err(e) => {
+            report.section("configuration").error(e);
+            return report;
+        }
+    };
+    for (room_id, config) in configs {
+        let section = report.section(format!("room '{}'", room_id));
+        check_announcements(section, &config.announcement_audio_base_path());
+        check_banners(section, &config.banner_base_path());
+        check_stems(section, &config);
+    }
+    report
+}
+
+/// Every room's configuration with the runtime settings overrides applied.
+fn load_configs(source: &ConfigSource) -> Result<Vec<(String, AppConfig)>, String> {
+    let config = AppConfig::load(source).map_err(|e| e.to_string())?;
+    let rooms = config.load_rooms(source).map_err(|e| e.to_string())?;
+    let overrides_path = config.settings_overrides_path.trim().to_string();
+    let overrides = if overrides_path.is_empty() {
+        Default::default()
+    } else {
+        load_overrides(Path::new(&overrides_path)).map_err(|e| e.to_string())?
+    };
+    Ok(std::iter::once((DEFAULT_ROOM_ID.to_string(), config))
+        .chain(rooms)
+        .map(|(room_id, mut config)| {
+            if let Some(room_overrides) = overrides.get(&room_id) {
+                room_overrides.apply(&mut config);
+            }
+            (room_id, config)
+        })
+        .collect())
+}
+
+/// Every enabled announcement slot must have a valid manifest, if any, and
+/// at least one audio file to play.
+fn check_announcements(section: &mut Section, base: &Path) {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        section.error(format!(
+            "announcements: {:?} is not a readable directory",
+            base
+        ));
+        return;
+    };
+    let mut slots = 0;
+    for slot in entries.flatten().map(|entry| entry.path()) {
+        if !slot.is_dir() {
+            continue;
+        }
+        slots += 1;
+        let manifest = match manifest::load(&slot) {
+            Ok(manifest) => manifest.unwrap_or_default(),
+            Err(e) => {
+                section.error(format!("announcements: {}", e));
+                continue;
+            }
+        };
+        if !manifest.enabled {
+            continue;
+        }
+        let (audio, problems) = manifest.arrange_audio(media_files(&slot, AUDIO_EXTENSIONS));
+        for problem in problems {
+            section.warning(format!("announcements: slot {:?}: {}", slot, problem));
+        }
+        if audio.is_empty() {
+            section.error(format!(
+                "announcements: slot {:?} has no {} files",
+                slot,
+                AUDIO_EXTENSIONS.join("/")
+            ));
+        }
+    }
+    if slots == 0 {
+        section.warning(format!("announcements: {:?} has no slots", base));
+    }
+}
+
+fn check_banners(section: &mut Section, base: &Path) {
+    if !base.is_dir() {
+        section.warning(format!("banners: {:?} is not a directory", base));
+    } else if media_files(base, BANNER_EXTENSIONS).is_empty() {
+        section.warning(format!("banners: {:?} has no images or videos", base));
+    }
+}
+
+/// Stem audio is the fallback when TTS is unavailable, so every announced
+/// language needs a complete stem directory.
+fn check_stems(section: &mut Section, config: &AppConfig) {
+    for inventory in stems::inventory(config) {
+        if inventory.available.is_empty() {
+            section.error(format!(
+                "audio stems: {:?} (for '{}') is missing or empty",
+                inventory.directory, inventory.language
+            ));
+        } else if !inventory.missing.is_empty() {
+            section.error(format!(
+                "audio stems: {:?} (for '{}') lacks {}",
+                inventory.directory,
+                inventory.language,
+                inventory.missing.join(", ")
+            ));
+        }
+    }
+}
+
+/// Files in `dir` (not recursive) with one of `extensions`, sorted by name.
+fn media_files(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    extensions
+                        .iter()
+                        .any(|known| ext.eq_ignore_ascii_case(known))
+                })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_announcements_flags_empty_slots() {
+        let base = std::env::temp_dir().join(format!("queue-assets-{}", std::process::id()));
+        std::fs::create_dir_all(base.join("slot_a")).unwrap();
+        std::fs::create_dir_all(base.join("slot_b")).unwrap();
+        std::fs::write(base.join("slot_a/chime.MP3"), b"").unwrap();
+        std::fs::write(base.join("slot_b/notes.txt"), b"").unwrap();
+        std::fs::create_dir_all(base.join("slot_c")).unwrap();
+        std::fs::write(base.join("slot_c/slot.toml"), "enabled = false\n").unwrap();
+        std::fs::write(base.join("slot_a/slot.json"), r#"{"audio": ["intro.mp3"]}"#).unwrap();
+
+        let mut report = Report::default();
+        let section = report.section("room 'default'");
+        check_announcements(section, &base);
+        assert_eq!(section.errors().len(), 2);
+        for slot in ["slot_a", "slot_b"] {
+            assert!(section.errors().iter().any(|error| error.contains(slot)));
+        }
+        assert_eq!(section.warnings().len(), 2);
+        std::fs::remove_dir_all(&base).unwrap();
+
+        check_announcements(section, &base);
+        assert_eq!(section.errors().len(), 3);
+    }
+}
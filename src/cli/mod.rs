@@ -0,0 +1,169 @@
+// src/cli/mod.rs
+
+//! Command-line interface of the server binary.
+//!
+//! Without a subcommand (or with `serve`) the binary runs the server. The other
+//! subcommands load the configuration exactly as the server would, print a
+//! report and exit, so deploy scripts can gate restarts on them:
+//!
+//! - `check-config` validates every setting, including templates, the language
+//!   list, list settings and directory paths.
+//! - `print-config` prints the effective configuration of every room.
+//! - `verify-assets` checks announcement slots, banners and audio stems.
+
+pub mod assets;
+pub mod check;
+pub mod report;
+
+pub use report::Report;
+
+use std::path::PathBuf;
+
+use crate::config::{ConfigError, ConfigSource};
+
+/// Command-line option naming the TOML configuration file.
+pub const CONFIG_FILE_ARG: &str = "--config";
+
+/// Usage line printed for `--help` and unknown arguments.
+pub const USAGE: &str =
+    "usage: queue-calling-system [serve|check-config|print-config|verify-assets] [--config <file.toml>]";
+
+/// Exit status of a command that found problems.
+pub const EXIT_PROBLEMS: i32 = 1;
+
+/// Exit status when the command line or configuration cannot be read.
+pub const EXIT_USAGE: i32 = 2;
+
+/// What the binary was asked to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Run the server (the default).
+    Serve,
+    /// Validate the configuration and exit.
+    CheckConfig,
+    /// Print the effective configuration and exit.
+    PrintConfig,
+    /// Check the media files the configuration refers to and exit.
+    VerifyAssets,
+    /// Print the usage line and exit.
+    Help,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "serve" => Some(Command::Serve),
+            "check-config" => Some(Command::CheckConfig),
+            "print-config" => Some(Command::PrintConfig),
+            "verify-assets" => Some(Command::VerifyAssets),
+            "help" | "--help" | "-h" => Some(Command::Help),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed command line.
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    /// Configuration sources, from `--config <path>` (or `--config=<path>`)
+    /// falling back to `QUEUE_CONFIG_FILE`.
+    pub source: ConfigSource,
+}
+
+impl Cli {
+    /// Parses the command-line arguments, excluding the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ConfigError> {
+        let mut command = None;
+        let mut file = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == CONFIG_FILE_ARG {
+                let path = args.next().ok_or_else(|| {
+                    ConfigError::Usage(format!("{} requires a file path", CONFIG_FILE_ARG))
+                })?;
+                file = Some(PathBuf::from(path));
+            } else if let Some(path) = arg.strip_prefix("--config=") {
+                file = Some(PathBuf::from(path));
+            } else {
+                match Command::from_name(&arg) {
+                    Some(parsed) if command.is_none() => command = Some(parsed),
+                    _ => {
+                        return Err(ConfigError::Usage(format!(
+                            "unknown argument '{}'; {}",
+                            arg, USAGE
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            command: command.unwrap_or(Command::Serve),
+            source: ConfigSource::new(file),
+        })
+    }
+
+    /// Runs a command other than [`Command::Serve`], printing its output to
+    /// stdout, and returns the process exit status.
+    pub fn run(&self) -> i32 {
+        match self.command {
+            Command::Serve => 0,
+            Command::Help => {
+                println!("{}", USAGE);
+                0
+            }
+            Command::CheckConfig => report(check::check_config(&self.source)),
+            Command::PrintConfig => match check::print_config(&self.source) {
+                Ok(output) => {
+                    print!("{}", output);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    EXIT_USAGE
+                }
+            },
+            Command::VerifyAssets => report(assets::verify_assets(&self.source)),
+        }
+    }
+}
+
+fn report(report: Report) -> i32 {
+    print!("{}", report);
+    if report.is_ok() {
+        0
+    } else {
+        EXIT_PROBLEMS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_commands_and_config_file() {
+        let cli = Cli::parse(args(&[])).unwrap();
+        assert_eq!(cli.command, Command::Serve);
+
+        let cli = Cli::parse(args(&["check-config", "--config", "/etc/queue.toml"])).unwrap();
+        assert_eq!(cli.command, Command::CheckConfig);
+        assert_eq!(cli.source.file(), Some(Path::new("/etc/queue.toml")));
+
+        let cli = Cli::parse(args(&["--config=queue.toml", "verify-assets"])).unwrap();
+        assert_eq!(cli.command, Command::VerifyAssets);
+        assert_eq!(cli.source.file(), Some(Path::new("queue.toml")));
+
+        assert_eq!(
+            Cli::parse(args(&["--config"])).unwrap_err().to_string(),
+            "--config requires a file path"
+        );
+        assert!(Cli::parse(args(&["--verbose"])).is_err());
+        assert!(Cli::parse(args(&["serve", "print-config"])).is_err());
+    }
+}
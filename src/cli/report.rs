@@ -0,0 +1,92 @@
+// src/cli/report.rs
+
+//! Plain-text report printed by `check-config` and `verify-assets`.
+
+use std::fmt;
+
+/// Problems found by a command, grouped by what they concern (e.g. a room).
+#[derive(Debug, Default)]
+pub struct Report {
+    sections: Vec<Section>,
+}
+
+/// Problems concerning one part of the configuration.
+#[derive(Debug)]
+pub struct Section {
+    title: String,
+    /// Problems that make the command fail.
+    errors: Vec<String>,
+    /// Worth knowing, but the server works as configured.
+    warnings: Vec<String>,
+}
+
+impl Report {
+    /// Starts a new section; later problems are added to it.
+    pub fn section(&mut self, title: impl Into<String>) -> &mut Section {
+        self.sections.push(Section {
+            title: title.into(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        });
+        self.sections.last_mut().expect("section was just added")
+    }
+
+    /// Total number of errors.
+    pub fn error_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.errors.len())
+            .sum()
+    }
+
+    /// Returns true when no section has errors.
+    pub fn is_ok(&self) -> bool {
+        self.error_count() == 0
+    }
+}
+
+impl Section {
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.errors.push(message.into());
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in &self.sections {
+            if section.errors.is_empty() && section.warnings.is_empty() {
+                writeln!(f, "{}: ok", section.title)?;
+                continue;
+            }
+            writeln!(
+                f,
+                "{}: {} error(s), {} warning(s)",
+                section.title,
+                section.errors.len(),
+                section.warnings.len()
+            )?;
+            for error in &section.errors {
+                writeln!(f, "  error: {}", error)?;
+            }
+            for warning in &section.warnings {
+                writeln!(f, "  warning: {}", warning)?;
+            }
+        }
+        match self.error_count() {
+            0 => writeln!(f, "OK"),
+            count => writeln!(f, "FAILED: {} error(s)", count),
+        }
+    }
+}
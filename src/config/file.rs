@@ -0,0 +1,565 @@
+// src/config/file.rs
+
+//! TOML configuration file.
+//!
+//! The file groups the settings into sections and allows structured values that
+//! are awkward in flat environment variables: TTS languages with their
+//! announcement templates, display zones with their counters, announcement
+//! slot schedules, ticket services and per-room overrides. It is flattened into
+//! the environment variable names documented in the README, so environment
+//! variables override any key.
+//!
+//! ```toml
+//! [server]
+//! port = 3000
+//!
+//! [[tts.languages]]
+//! code = "th"
+//! name = "Thai"
+//! template = "หมายเลข {Q_NUM}, เชิญช่อง {DEST_NUM}"
+//!
+//! [[displays.zones]]
+//! name = "lobby"
+//! counters = ["1-4", 10]
+//!
+//! [rooms.eye.queue]
+//! max_history_size = 10
+//! ```
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use serde::Deserialize;
+
+use crate::announcements::SlotSchedule;
+
+use super::{parse_counter_range, room_env_prefix, DEFAULT_ROOM_ID};
+
+/// Parses `contents` into `(ENVIRONMENT_VARIABLE, value)` pairs.
+///
+/// Errors name the offending key, with its line and column when the problem is
+/// a syntax error, an unknown key or a value of the wrong type.
+pub(super) fn parse(contents: &str) -> Result<Vec<(String, String)>, String> {
+    let file: ConfigFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    file.into_vars()
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    server: ServerSection,
+    queue: QueueSection,
+    announcements: AnnouncementsSection,
+    tts: TtsSection,
+    events: EventsSection,
+    displays: DisplaysSection,
+    webhooks: WebhooksSection,
+    mqtt: MqttSection,
+    tickets: TicketsSection,
+    /// Additional rooms by id, each overriding the sections above.
+    rooms: BTreeMap<String, RoomSections>,
+}
+
+/// The sections a room may override.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RoomSections {
+    queue: QueueSection,
+    announcements: AnnouncementsSection,
+    tts: TtsSection,
+    events: EventsSection,
+    displays: DisplaysSection,
+    webhooks: WebhooksSection,
+    mqtt: MqttSection,
+    tickets: TicketsSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ServerSection {
+    address: Option<IpAddr>,
+    port: Option<u16>,
+    serve_dir: Option<String>,
+    keypad_tcp_port: Option<u16>,
+    settings_overrides_path: Option<String>,
+    shutdown_timeout_seconds: Option<u64>,
+    trusted_proxies: Option<Vec<IpAddr>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct QueueSection {
+    max_history_size: Option<usize>,
+    max_skipped_history_size: Option<usize>,
+    identifier_prefix_required: Option<bool>,
+    no_show_timeout_seconds: Option<u64>,
+    no_show_auto_recalls: Option<u32>,
+    history_database_path: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AnnouncementsSection {
+    audio_sub_path: Option<String>,
+    banners_sub_path: Option<String>,
+    auto_cycle_interval_seconds: Option<u64>,
+    manual_trigger_cooldown_seconds: Option<u64>,
+    banner_rotation_interval_seconds: Option<u64>,
+    schedules: Option<Vec<SlotScheduleEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SlotScheduleEntry {
+    slot: String,
+    schedule: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TtsSection {
+    cache_base_path: Option<String>,
+    cache_web_path: Option<String>,
+    cache_maximum_files: Option<usize>,
+    external_service_timeout_seconds: Option<u64>,
+    /// Announcement languages in playback order.
+    languages: Option<Vec<TtsLanguage>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TtsLanguage {
+    code: String,
+    name: Option<String>,
+    /// Live announcement template; only Thai and English have one.
+    template: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct EventsSection {
+    sse_keep_alive_interval_seconds: Option<u64>,
+    sse_buffer_size: Option<usize>,
+    replay_buffer_size: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DisplaysSection {
+    disconnect_alert_seconds: Option<u64>,
+    zones: Option<Vec<DisplayZoneEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DisplayZoneEntry {
+    name: String,
+    counters: Vec<CounterEntry>,
+}
+
+/// A single counter (`10`) or a range (`"1-4"`).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CounterEntry {
+    Number(u64),
+    Text(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WebhooksSection {
+    file: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MqttSection {
+    broker_url: Option<String>,
+    topic_prefix: Option<String>,
+    commands_enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TicketsSection {
+    printer: Option<String>,
+    header: Option<String>,
+    status_url_template: Option<String>,
+    notifier: Option<String>,
+    notify_people_ahead: Option<usize>,
+    services: Option<Vec<TicketServiceEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TicketServiceEntry {
+    prefix: String,
+    name: String,
+}
+
+/// Collects variables, prefixing names for room overrides.
+struct Vars {
+    prefix: String,
+    entries: Vec<(String, String)>,
+}
+
+impl Vars {
+    fn set<T: ToString>(&mut self, name: &str, value: &Option<T>) {
+        if let Some(value) = value {
+            self.entries
+                .push((format!("{}{}", self.prefix, name), value.to_string()));
+        }
+    }
+}
+
+impl ConfigFile {
+    fn into_vars(self) -> Result<Vec<(String, String)>, String> {
+        let mut vars = Vars {
+            prefix: String::new(),
+            entries: Vec::new(),
+        };
+        vars.set("SERVER_ADDRESS", &self.server.address);
+        vars.set("SERVER_PORT", &self.server.port);
+        vars.set("SERVE_DIR_PATH", &self.server.serve_dir);
+        vars.set("KEYPAD_TCP_PORT", &self.server.keypad_tcp_port);
+        vars.set(
+            "SETTINGS_OVERRIDES_PATH",
+            &self.server.settings_overrides_path,
+        );
+        vars.set(
+            "SHUTDOWN_TIMEOUT_SECONDS",
+            &self.server.shutdown_timeout_seconds,
+        );
+        if let Some(proxies) = &self.server.trusted_proxies {
+            let proxies: Vec<String> = proxies.iter().map(IpAddr::to_string).collect();
+            vars.set("TRUSTED_PROXIES", &Some(proxies.join(",")));
+        }
+
+        RoomSections {
+            queue: self.queue,
+            announcements: self.announcements,
+            tts: self.tts,
+            events: self.events,
+            displays: self.displays,
+            webhooks: self.webhooks,
+            mqtt: self.mqtt,
+            tickets: self.tickets,
+        }
+        .write(&mut vars, "")?;
+
+        if !self.rooms.is_empty() {
+            let ids: Vec<&str> = self.rooms.keys().map(String::as_str).collect();
+            vars.set("ROOMS", &Some(ids.join(",")));
+        }
+        for (room_id, sections) in &self.rooms {
+            let key = format!("rooms.{}", room_id);
+            if room_id == DEFAULT_ROOM_ID
+                || room_id.is_empty()
+                || !room_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            {
+                return Err(format!(
+                    "{}: room ids may only contain letters, digits, '-' and '_', and '{}' is reserved",
+                    key, DEFAULT_ROOM_ID
+                ));
+            }
+            vars.prefix = room_env_prefix(room_id);
+            sections.write(&mut vars, &format!("{}.", key))?;
+        }
+        Ok(vars.entries)
+    }
+}
+
+impl RoomSections {
+    /// Adds this room's variables; `key_prefix` locates the sections in error messages.
+    fn write(&self, vars: &mut Vars, key_prefix: &str) -> Result<(), String> {
+        vars.set("MAX_HISTORY_SIZE", &self.queue.max_history_size);
+        vars.set(
+            "MAX_SKIPPED_HISTORY_SIZE",
+            &self.queue.max_skipped_history_size,
+        );
+        vars.set(
+            "QUEUE_IDENTIFIER_PREFIX_REQUIRED",
+            &self.queue.identifier_prefix_required,
+        );
+        vars.set(
+            "NO_SHOW_TIMEOUT_SECONDS",
+            &self.queue.no_show_timeout_seconds,
+        );
+        vars.set("NO_SHOW_AUTO_RECALLS", &self.queue.no_show_auto_recalls);
+        vars.set("HISTORY_DATABASE_PATH", &self.queue.history_database_path);
+
+        vars.set(
+            "ANNOUNCEMENTS_AUDIO_SUB_PATH",
+            &self.announcements.audio_sub_path,
+        );
+        vars.set("BANNERS_SUB_PATH", &self.announcements.banners_sub_path);
+        vars.set(
+            "ANNOUNCEMENT_AUTO_CYCLE_INTERVAL_SECONDS",
+            &self.announcements.auto_cycle_interval_seconds,
+        );
+        vars.set(
+            "ANNOUNCEMENT_MANUAL_TRIGGER_COOLDOWN_SECONDS",
+            &self.announcements.manual_trigger_cooldown_seconds,
+        );
+        vars.set(
+            "BANNER_ROTATION_INTERVAL_SECONDS",
+            &self.announcements.banner_rotation_interval_seconds,
+        );
+        if let Some(schedules) = &self.announcements.schedules {
+            let mut entries = Vec::new();
+            for (index, entry) in schedules.iter().enumerate() {
+                let key = format!("{}announcements.schedules[{}]", key_prefix, index);
+                let slot = entry.slot.trim();
+                if slot.is_empty() || slot.contains(['=', ';']) {
+                    return Err(format!(
+                        "{}.slot: '{}' is not a slot directory name",
+                        key, slot
+                    ));
+                }
+                if entry.schedule.contains(';') {
+                    return Err(format!("{}.schedule: schedules may not contain ';'", key));
+                }
+                if let Err(e) = SlotSchedule::parse(&entry.schedule) {
+                    return Err(format!("{}.schedule: {}", key, e));
+                }
+                entries.push(format!("{}={}", slot, entry.schedule.trim()));
+            }
+            vars.set("ANNOUNCEMENT_SCHEDULES", &Some(entries.join(";")));
+        }
+
+        vars.set("GTTS_CACHE_BASE_PATH", &self.tts.cache_base_path);
+        vars.set("TTS_CACHE_WEB_PATH", &self.tts.cache_web_path);
+        vars.set("TTS_CACHE_MAXIMUM_FILES", &self.tts.cache_maximum_files);
+        vars.set(
+            "TTS_EXTERNAL_SERVICE_TIMEOUT_SECONDS",
+            &self.tts.external_service_timeout_seconds,
+        );
+        if let Some(languages) = &self.tts.languages {
+            let mut entries = Vec::new();
+            for (index, language) in languages.iter().enumerate() {
+                let key = format!("{}tts.languages[{}]", key_prefix, index);
+                let code = language.code.trim();
+                if code.is_empty() || code.contains([',', ':']) {
+                    return Err(format!("{}.code: '{}' is not a language code", key, code));
+                }
+                match &language.name {
+                    Some(name) if name.contains(',') => {
+                        return Err(format!("{}.name: names may not contain ','", key));
+                    }
+                    Some(name) => entries.push(format!("{}:{}", code, name.trim())),
+                    None => entries.push(code.to_string()),
+                }
+                if let Some(template) = &language.template {
+                    let variable = match code.to_ascii_lowercase().split('-').next() {
+                        Some("th") => "TTS_ANNOUNCEMENT_TEMPLATE_TH",
+                        Some("en") => "TTS_ANNOUNCEMENT_TEMPLATE_EN",
+                        _ => {
+                            return Err(format!(
+                                "{}.template: live announcement templates are only supported for Thai (th) and English (en)",
+                                key
+                            ));
+                        }
+                    };
+                    vars.set(variable, &Some(template));
+                }
+            }
+            vars.set("TTS_SUPPORTED_LANGUAGES", &Some(entries.join(",")));
+        }
+
+        vars.set(
+            "SSE_KEEP_ALIVE_INTERVAL_SECONDS",
+            &self.events.sse_keep_alive_interval_seconds,
+        );
+        vars.set("SSE_EVENT_BUFFER_SIZE", &self.events.sse_buffer_size);
+        vars.set("EVENT_REPLAY_BUFFER_SIZE", &self.events.replay_buffer_size);
+
+        vars.set(
+            "DISPLAY_DISCONNECT_ALERT_SECONDS",
+            &self.displays.disconnect_alert_seconds,
+        );
+        if let Some(zones) = &self.displays.zones {
+            let mut entries = Vec::new();
+            for (index, zone) in zones.iter().enumerate() {
+                let key = format!("{}displays.zones[{}]", key_prefix, index);
+                let name = zone.name.trim();
+                if name.is_empty() || name.contains([':', ';', ',']) {
+                    return Err(format!("{}.name: '{}' is not a zone name", key, name));
+                }
+                let mut counters = Vec::new();
+                for (counter_index, counter) in zone.counters.iter().enumerate() {
+                    let counter = match counter {
+                        CounterEntry::Number(number) => number.to_string(),
+                        CounterEntry::Text(text) => text.trim().to_string(),
+                    };
+                    if parse_counter_range(&counter).is_none() {
+                        return Err(format!(
+                            "{}.counters[{}]: '{}' is not a counter number or a range like \"1-4\"",
+                            key, counter_index, counter
+                        ));
+                    }
+                    counters.push(counter);
+                }
+                if counters.is_empty() {
+                    return Err(format!(
+                        "{}.counters: a zone needs at least one counter",
+                        key
+                    ));
+                }
+                entries.push(format!("{}:{}", name, counters.join(",")));
+            }
+            vars.set("DISPLAY_ZONES", &Some(entries.join(";")));
+        }
+
+        vars.set("WEBHOOKS_FILE", &self.webhooks.file);
+
+        vars.set("MQTT_BROKER_URL", &self.mqtt.broker_url);
+        vars.set("MQTT_TOPIC_PREFIX", &self.mqtt.topic_prefix);
+        vars.set("MQTT_COMMANDS_ENABLED", &self.mqtt.commands_enabled);
+
+        vars.set("TICKET_PRINTER", &self.tickets.printer);
+        vars.set("TICKET_HEADER", &self.tickets.header);
+        vars.set(
+            "TICKET_STATUS_URL_TEMPLATE",
+            &self.tickets.status_url_template,
+        );
+        vars.set("TICKET_NOTIFIER", &self.tickets.notifier);
+        vars.set(
+            "TICKET_NOTIFY_PEOPLE_AHEAD",
+            &self.tickets.notify_people_ahead,
+        );
+        if let Some(services) = &self.tickets.services {
+            let mut entries = Vec::new();
+            for (index, service) in services.iter().enumerate() {
+                let key = format!("{}tickets.services[{}]", key_prefix, index);
+                let prefix = service.prefix.trim();
+                if prefix.len() != 1 || !prefix.chars().all(|c| c.is_ascii_uppercase()) {
+                    return Err(format!(
+                        "{}.prefix: '{}' must be a single upper-case letter",
+                        key, prefix
+                    ));
+                }
+                if service.name.contains(',') {
+                    return Err(format!("{}.name: names may not contain ','", key));
+                }
+                entries.push(format!("{}:{}", prefix, service.name.trim()));
+            }
+            vars.set("TICKET_SERVICES", &Some(entries.join(",")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup<'a>(vars: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        vars.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn flattens_sections_into_variables() {
+        let vars = parse(
+            r#"
+[server]
+port = 8080
+trusted_proxies = ["127.0.0.1", "::1"]
+
+[queue]
+identifier_prefix_required = false
+
+[[tts.languages]]
+code = "th"
+name = "Thai"
+template = "เชิญหมายเลข {Q_NUM} ช่อง {DEST_NUM}"
+
+[[tts.languages]]
+code = "en-GB"
+name = "British English"
+
+[[displays.zones]]
+name = "lobby"
+counters = ["1-4", 10]
+
+[[announcements.schedules]]
+slot = "01-bring-id"
+schedule = "mon-fri 07:00-10:00"
+
+[[announcements.schedules]]
+slot = "02-lunch"
+schedule = "at 12:00"
+
+[[tickets.services]]
+prefix = "A"
+name = "General"
+
+[[tickets.services]]
+prefix = "B"
+name = "Pharmacy"
+
+[rooms.eye.queue]
+max_history_size = 10
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(lookup(&vars, "SERVER_PORT"), Some("8080"));
+        assert_eq!(lookup(&vars, "TRUSTED_PROXIES"), Some("127.0.0.1,::1"));
+        assert_eq!(
+            lookup(&vars, "QUEUE_IDENTIFIER_PREFIX_REQUIRED"),
+            Some("false")
+        );
+        assert_eq!(
+            lookup(&vars, "TTS_SUPPORTED_LANGUAGES"),
+            Some("th:Thai,en-GB:British English")
+        );
+        assert_eq!(
+            lookup(&vars, "TTS_ANNOUNCEMENT_TEMPLATE_TH"),
+            Some("เชิญหมายเลข {Q_NUM} ช่อง {DEST_NUM}")
+        );
+        assert_eq!(lookup(&vars, "TTS_ANNOUNCEMENT_TEMPLATE_EN"), None);
+        assert_eq!(lookup(&vars, "DISPLAY_ZONES"), Some("lobby:1-4,10"));
+        assert_eq!(
+            lookup(&vars, "ANNOUNCEMENT_SCHEDULES"),
+            Some("01-bring-id=mon-fri 07:00-10:00;02-lunch=at 12:00")
+        );
+        assert_eq!(
+            lookup(&vars, "TICKET_SERVICES"),
+            Some("A:General,B:Pharmacy")
+        );
+        assert_eq!(lookup(&vars, "ROOMS"), Some("eye"));
+        assert_eq!(lookup(&vars, "ROOM_EYE_MAX_HISTORY_SIZE"), Some("10"));
+        assert_eq!(lookup(&vars, "MAX_HISTORY_SIZE"), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_bad_key() {
+        let error = parse("[queue]\nmax_history = 5\n").unwrap_err();
+        assert!(error.contains("line 2, column 1"), "{}", error);
+        assert!(error.contains("unknown field `max_history`"), "{}", error);
+
+        let error = parse("[server]\nport = \"http\"\n").unwrap_err();
+        assert!(error.contains("line 2, column 8"), "{}", error);
+
+        let error =
+            parse("[[displays.zones]]\nname = \"lobby\"\ncounters = [\"4-1\"]\n").unwrap_err();
+        assert_eq!(
+            error,
+            "displays.zones[0].counters[0]: '4-1' is not a counter number or a range like \"1-4\""
+        );
+
+        let error = parse(
+            "[[rooms.eye.tts.languages]]\ncode = \"ja\"\ntemplate = \"{Q_NUM} {DEST_NUM}\"\n",
+        )
+        .unwrap_err();
+        assert!(
+            error.starts_with("rooms.eye.tts.languages[0].template:"),
+            "{}",
+            error
+        );
+    }
+}
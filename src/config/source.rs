@@ -0,0 +1,260 @@
+// src/config/source.rs
+
+//! Where configuration values come from and how they are layered.
+//!
+//! From lowest to highest precedence: the built-in defaults, the TOML file given
+//! by `--config` or `QUEUE_CONFIG_FILE`, the process environment, and the
+//! `KEY=VALUE` file named by `CONFIG_ENV_FILE`. Every layer is flattened into
+//! environment variable names, so `envconfig` parses all of them the same way.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use super::file;
+
+/// Environment variable naming the TOML configuration file when `--config` is not given.
+pub const CONFIG_FILE_VAR: &str = "QUEUE_CONFIG_FILE";
+
+/// Environment variable naming an optional `KEY=VALUE` file whose entries
+/// override the process environment. Unlike the environment, the file is read
+/// again whenever the configuration is reloaded.
+pub const CONFIG_ENV_FILE_VAR: &str = "CONFIG_ENV_FILE";
+
+/// Errors raised while reading or parsing the configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The command line could not be understood.
+    Usage(String),
+    /// A configuration file could not be read.
+    Read {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// A configuration file is malformed; `message` points at the offending key or line.
+    File { path: PathBuf, message: String },
+    /// A variable holds a value that cannot be parsed.
+    Variable {
+        /// Room whose `ROOM_<ID>_` overrides were being applied, if any.
+        room: Option<String>,
+        name: String,
+        /// The offending value, or `None` when a required variable is missing.
+        value: Option<String>,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Usage(message) => write!(f, "{}", message),
+            ConfigError::Read { path, error } => {
+                write!(f, "cannot read '{}': {}", path.display(), error)
+            }
+            ConfigError::File { path, message } => {
+                write!(
+                    f,
+                    "invalid configuration in '{}': {}",
+                    path.display(),
+                    message
+                )
+            }
+            ConfigError::Variable { room, name, value } => {
+                if let Some(room) = room {
+                    write!(f, "room '{}': ", room)?;
+                }
+                match value {
+                    Some(value) => write!(f, "{}: invalid value '{}'", name, value),
+                    None => write!(f, "{}: missing", name),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ConfigError {
+    /// Describes an `envconfig` failure with the variable's value from `vars`.
+    pub(super) fn from_envconfig(
+        error: envconfig::Error,
+        vars: &HashMap<String, String>,
+        room: Option<&str>,
+    ) -> Self {
+        let name = match error {
+            envconfig::Error::EnvVarMissing { name } | envconfig::Error::ParseError { name } => {
+                name
+            }
+        };
+        let value = room
+            .and_then(|room| vars.get(&format!("{}{}", super::room_env_prefix(room), name)))
+            .or_else(|| vars.get(name))
+            .cloned();
+        ConfigError::Variable {
+            room: room.map(str::to_string),
+            name: name.to_string(),
+            value,
+        }
+    }
+}
+
+/// The configuration sources of this process.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    file: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    /// A source reading `file` (given with `--config`) below the environment,
+    /// falling back to `QUEUE_CONFIG_FILE` when it is `None`.
+    pub fn new(file: Option<PathBuf>) -> Self {
+        let file = file.or_else(|| {
+            env::var(CONFIG_FILE_VAR)
+                .ok()
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+        });
+        Self { file }
+    }
+
+    /// A source reading `path` below the environment.
+    pub fn with_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: Some(path.into()),
+        }
+    }
+
+    /// The TOML configuration file, if one is used.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Returns every configuration variable, layered as described in the module docs.
+    pub fn vars(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let mut vars = HashMap::new();
+        if let Some(path) = &self.file {
+            let contents = fs::read_to_string(path).map_err(|error| ConfigError::Read {
+                path: path.clone(),
+                error,
+            })?;
+            let entries = file::parse(&contents).map_err(|message| ConfigError::File {
+                path: path.clone(),
+                message,
+            })?;
+            debug!(
+                "Read {} configuration values from '{}'.",
+                entries.len(),
+                path.display()
+            );
+            vars.extend(entries);
+        }
+
+        vars.extend(env::vars());
+
+        if let Some(path) = vars
+            .get(CONFIG_ENV_FILE_VAR)
+            .map(|path| PathBuf::from(path.trim()))
+            .filter(|path| !path.as_os_str().is_empty())
+        {
+            let contents = fs::read_to_string(&path).map_err(|error| ConfigError::Read {
+                path: path.clone(),
+                error,
+            })?;
+            let entries = parse_env_file(&contents).map_err(|message| ConfigError::File {
+                path: path.clone(),
+                message,
+            })?;
+            debug!(
+                "Read {} configuration entries from '{}'.",
+                entries.len(),
+                path.display()
+            );
+            vars.extend(entries);
+        }
+        Ok(vars)
+    }
+}
+
+/// Parses a `KEY=VALUE` environment file the way `podman --env-file` does:
+/// values are taken literally, blank lines and `#` comments are skipped, and a
+/// bare `KEY` keeps the value from the process environment.
+fn parse_env_file(contents: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (line.trim_end(), None),
+        };
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!(
+                "line {}: invalid variable name '{}'",
+                index + 1,
+                key
+            ));
+        }
+        if let Some(value) = value {
+            entries.push((key.to_string(), value.to_string()));
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let entries = parse_env_file(
+            "# comment\n\nTTS_SUPPORTED_LANGUAGES=th:Thai,en-GB:British English\nRUST_LOG\nTICKET_HEADER=\n",
+        )
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "TTS_SUPPORTED_LANGUAGES".to_string(),
+                    "th:Thai,en-GB:British English".to_string()
+                ),
+                ("TICKET_HEADER".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(
+            parse_env_file("SERVER_PORT=1\nbad key=2\n").unwrap_err(),
+            "line 2: invalid variable name 'bad key'"
+        );
+    }
+
+    #[test]
+    fn test_envconfig_errors_name_the_value() {
+        let vars: HashMap<String, String> = [
+            ("MAX_HISTORY_SIZE", "five"),
+            ("ROOM_EYE_MAX_HISTORY_SIZE", "-1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let error = envconfig::Error::ParseError {
+            name: "MAX_HISTORY_SIZE",
+        };
+        assert_eq!(
+            ConfigError::from_envconfig(error, &vars, None).to_string(),
+            "MAX_HISTORY_SIZE: invalid value 'five'"
+        );
+        let error = envconfig::Error::ParseError {
+            name: "MAX_HISTORY_SIZE",
+        };
+        assert_eq!(
+            ConfigError::from_envconfig(error, &vars, Some("eye")).to_string(),
+            "room 'eye': MAX_HISTORY_SIZE: invalid value '-1'"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+// src/ctl/format.rs
+
+//! Human-readable output of `queuectl`.
+//!
+//! Responses and events are read as plain JSON values rather than the server's
+//! types, so a `queuectl` from another release still prints what it can.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, TimeZone};
+use serde_json::Value;
+
+/// One event received from `/api/events`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event:` field, e.g. `queue_update`.
+    pub event: String,
+    /// The `data:` lines joined with newlines.
+    pub data: String,
+    /// The `id:` field, if any.
+    pub id: Option<String>,
+}
+
+/// Splits a Server-Sent Events stream into events as chunks arrive.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    /// Bytes after the last complete line, which may end inside a UTF-8 character.
+    pending: Vec<u8>,
+    /// Complete lines not yet closed by a blank line.
+    buffer: String,
+}
+
+impl SseParser {
+    /// Adds a chunk of the stream and returns the events it completed.
+    /// Comments such as keep-alives are dropped.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.pending.extend_from_slice(chunk);
+        // A newline byte never occurs inside a multi-byte character, so complete
+        // lines decode on their own.
+        if let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            self.buffer
+                .push_str(&String::from_utf8_lossy(&lines).replace("\r\n", "\n"));
+        }
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let block: String = self.buffer.drain(..end + 2).collect();
+            let mut event = SseEvent::default();
+            let mut data = Vec::new();
+            for line in block.lines() {
+                let (field, value) = line.split_once(':').unwrap_or((line, ""));
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                match field {
+                    "event" => event.event = value.to_string(),
+                    "data" => data.push(value),
+                    "id" => event.id = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            if data.is_empty() {
+                continue;
+            }
+            event.data = data.join("\n");
+            if event.event.is_empty() {
+                event.event = "message".to_string();
+            }
+            events.push(event);
+        }
+        events
+    }
+}
+
+/// The explanation in a response body: the `message` or `error` field of a
+/// JSON body, otherwise the body itself.
+pub fn message(body: &str) -> String {
+    let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(body) else {
+        return body.trim().to_string();
+    };
+    fields
+        .get("message")
+        .or_else(|| fields.get("error"))
+        .and_then(Value::as_str)
+        .map_or_else(|| body.trim().to_string(), str::to_string)
+}
+
+/// The output of `queuectl state`.
+pub fn queue_state(state: &Value) -> String {
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "Current:   {}",
+        match state.get("current_call").filter(|call| !call.is_null()) {
+            Some(call) => with_zone(call_label(call), state.get("current_zone")),
+            None => "none".to_string(),
+        }
+    );
+    for (title, key) in [
+        ("Completed", "completed_history"),
+        ("Skipped", "skipped_history"),
+    ] {
+        let calls = calls(state, key);
+        let _ = writeln!(
+            output,
+            "{:<10} {}",
+            format!("{}:", title),
+            if calls.is_empty() {
+                "none".to_string()
+            } else {
+                calls.join(", ")
+            }
+        );
+    }
+    output
+}
+
+/// One line of `queuectl watch` for an event received at `now`.
+pub fn event_line<Tz: TimeZone>(event: &SseEvent, now: DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let payload: Value = serde_json::from_str(&event.data).unwrap_or(Value::Null);
+    let data = payload.get("data").unwrap_or(&Value::Null);
+    format!(
+        "{} {:<20} {}",
+        now.format("%H:%M:%S"),
+        event.event,
+        describe_event(&event.event, data).unwrap_or_else(|| event.data.clone())
+    )
+}
+
+/// Summary of an event's data, or `None` for events this client does not know.
+fn describe_event(name: &str, data: &Value) -> Option<String> {
+    let text = |key: &str| data.get(key).and_then(Value::as_str).unwrap_or("?");
+    let number = |key: &str| data.get(key).and_then(Value::as_u64).unwrap_or(0);
+    let summary = match name {
+        "queue_update" => {
+            let current = match data.get("current_call").filter(|call| !call.is_null()) {
+                Some(call) => with_zone(call_label(call), data.get("current_zone")),
+                None => "none".to_string(),
+            };
+            format!(
+                "current {}; {} completed, {} skipped",
+                current,
+                calls(data, "completed_history").len(),
+                calls(data, "skipped_history").len()
+            )
+        }
+        "announcement_status" => {
+            let slot = data
+                .get("current_slot_id")
+                .and_then(Value::as_str)
+                .unwrap_or("none");
+            if data.get("cooldown_active").and_then(Value::as_bool) == Some(true) {
+                format!(
+                    "slot {}; cooldown {}s left",
+                    slot,
+                    number("cooldown_remaining_seconds")
+                )
+            } else {
+                format!("slot {}", slot)
+            }
+        }
+        "translator_call" => with_zone(
+            format!("translator requested at counter {}", text("location")),
+            data.get("zone"),
+        ),
+        "tts_complete" => format!(
+            "{} at counter {} [{}], {} audio file(s)",
+            text("id"),
+            text("location"),
+            text("lang"),
+            data.get("audio_urls")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len)
+        ),
+        "display_alert" => format!(
+            "display {} offline for {}s",
+            text("display_id"),
+            number("offline_seconds")
+        ),
+        "display_command" => format!(
+            "{} for {} ({})",
+            compact(data.get("command")),
+            compact(data.get("target")),
+            text("command_id")
+        ),
+        "settings_changed" => {
+            let languages: Vec<&str> = data
+                .get("languages")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|language| language.get("code").and_then(Value::as_str))
+                .collect();
+            format!(
+                "languages {}; history {}/{}; manual cooldown {}s",
+                languages.join(", "),
+                number("max_history_size"),
+                number("max_skipped_history_size"),
+                number("announcement_manual_trigger_cooldown_seconds")
+            )
+        }
+        "server_shutdown" => "server is shutting down".to_string(),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+/// `A12 at counter 3`, noting calls skipped by the no-show timer.
+fn call_label(call: &Value) -> String {
+    let field = |key: &str| call.get(key).and_then(Value::as_str);
+    let mut label = format!(
+        "{} at counter {}",
+        field("original_id").unwrap_or("?"),
+        field("location").unwrap_or("?")
+    );
+    if let Some(reason) = field("skip_reason") {
+        let _ = write!(label, " ({})", reason);
+    }
+    label
+}
+
+fn calls(state: &Value, key: &str) -> Vec<String> {
+    state
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(call_label)
+        .collect()
+}
+
+fn with_zone(label: String, zone: Option<&Value>) -> String {
+    match zone.and_then(Value::as_str) {
+        Some(zone) => format!("{} (zone {})", label, zone),
+        None => label,
+    }
+}
+
+fn compact(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => "?".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser
+            .push(b": keep-alive\n\nevent: translator_call\r")
+            .is_empty());
+        let events = parser.push(
+            b"\nid: 7\r\ndata: {\"type\":\"TranslatorCall\",\"data\":{\"location\":\"4\"}}\r\n\r\n",
+        );
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event: "translator_call".to_string(),
+                data: r#"{"type":"TranslatorCall","data":{"location":"4"}}"#.to_string(),
+                id: Some("7".to_string()),
+            }]
+        );
+
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+        assert_eq!(
+            event_line(&events[0], now),
+            "09:30:00 translator_call      translator requested at counter 4"
+        );
+    }
+
+    #[test]
+    fn test_sse_parser_keeps_characters_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let stream = "event: announcement_status\ndata: กรุณา\n\n".as_bytes();
+        // Cut inside the first Thai character (three bytes in UTF-8).
+        let cut = stream.len() - "รุณา\n\n".len() - 1;
+        assert!(parser.push(&stream[..cut]).is_empty());
+        let events = parser.push(&stream[cut..]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "กรุณา");
+    }
+
+    #[test]
+    fn test_queue_state_and_messages() {
+        let state = serde_json::json!({
+            "identifier_prefix_required": true,
+            "current_call": {"id": "A12", "original_id": "A12", "location": "3"},
+            "completed_history": [{"id": "A11", "original_id": "A11", "location": "2"}],
+            "skipped_history": [],
+            "current_zone": "lobby"
+        });
+        assert_eq!(
+            queue_state(&state),
+            "Current:   A12 at counter 3 (zone lobby)\n\
+             Completed: A11 at counter 2\n\
+             Skipped:   none\n"
+        );
+
+        assert_eq!(
+            message("No current call to skip."),
+            "No current call to skip."
+        );
+        assert_eq!(
+            message(r#"{"error":"Slot 'x' was not found"}"#),
+            "Slot 'x' was not found"
+        );
+    }
+}
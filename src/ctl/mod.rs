@@ -0,0 +1,348 @@
+// src/ctl/mod.rs
+
+//! `queuectl`, a command-line client operating a running server over its HTTP
+//! API, e.g. over SSH:
+//!
+//! ```text
+//! queuectl call A12 3        queuectl announce next
+//! queuectl skip              queuectl announce trigger 01-number-not-queue
+//! queuectl complete          queuectl translator 4
+//! queuectl state             queuectl watch
+//! ```
+//!
+//! The server is `http://127.0.0.1:3000` unless `--server <url>` or
+//! `QUEUECTL_SERVER` says otherwise; `--room <id>` targets a room other than
+//! the default one.
+
+pub mod format;
+
+use std::fmt;
+
+use reqwest::{Client, Method, Response};
+use serde_json::{json, Value};
+
+/// Server used when neither `--server` nor `QUEUECTL_SERVER` is given.
+pub const DEFAULT_SERVER: &str = "http://127.0.0.1:3000";
+
+/// Environment variable naming the server.
+pub const SERVER_ENV: &str = "QUEUECTL_SERVER";
+
+/// Usage text printed for `--help` and invalid command lines.
+pub const USAGE: &str = "usage: queuectl [--server <url>] [--room <id>] <command>
+
+commands:
+  call <id> <counter>         call a number to a counter
+  skip                        skip the current call
+  complete                    complete the current call
+  state                       print the current call and histories
+  announce next               play the next announcement slot
+  announce trigger <slot>     play a specific announcement slot
+  translator <counter>        request a translator at a counter
+  watch                       print events as they happen";
+
+/// Exit status when the server rejected the request or could not be reached.
+pub const EXIT_FAILED: i32 = 1;
+
+/// Exit status for an invalid command line.
+pub const EXIT_USAGE: i32 = 2;
+
+/// A `queuectl` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Call { id: String, location: String },
+    Skip,
+    Complete,
+    State,
+    AnnounceNext,
+    AnnounceTrigger { slot_id: String },
+    Translator { location: String },
+    Watch,
+    Help,
+}
+
+/// Parsed `queuectl` command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ctl {
+    pub command: Command,
+    /// Base URL of the server, without a trailing slash.
+    pub server: String,
+    /// Room to operate; the default room when `None`.
+    pub room: Option<String>,
+}
+
+/// Errors raised by `queuectl`.
+#[derive(Debug)]
+pub enum CtlError {
+    /// The command line is invalid.
+    Usage(String),
+    /// The server could not be reached or the response could not be read.
+    Http(reqwest::Error),
+    /// The server answered with an error status; carries its explanation.
+    Rejected(u16, String),
+}
+
+impl fmt::Display for CtlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CtlError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            CtlError::Http(err) => write!(f, "Request failed: {}", err),
+            CtlError::Rejected(status, message) => {
+                write!(f, "Server answered {}: {}", status, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CtlError {}
+
+impl From<reqwest::Error> for CtlError {
+    fn from(err: reqwest::Error) -> Self {
+        CtlError::Http(err)
+    }
+}
+
+impl CtlError {
+    /// Process exit status for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CtlError::Usage(_) => EXIT_USAGE,
+            CtlError::Http(_) | CtlError::Rejected(..) => EXIT_FAILED,
+        }
+    }
+}
+
+impl Ctl {
+    /// Parses the command-line arguments, excluding the program name.
+    /// `server_env` is the value of [`SERVER_ENV`], if set.
+    pub fn parse<I: IntoIterator<Item = String>>(
+        args: I,
+        server_env: Option<String>,
+    ) -> Result<Self, CtlError> {
+        let mut server = server_env.filter(|server| !server.trim().is_empty());
+        let mut room = None;
+        let mut words = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--server" | "--room" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| CtlError::Usage(format!("{} requires a value", arg)))?;
+                    if arg == "--server" {
+                        server = Some(value);
+                    } else {
+                        room = Some(value);
+                    }
+                }
+                "--help" | "-h" => words.push("help".to_string()),
+                option if option.starts_with("--") => {
+                    return Err(CtlError::Usage(format!("unknown option '{}'", option)))
+                }
+                _ => words.push(arg),
+            }
+        }
+
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let command = match words.as_slice() {
+            ["call", id, location] => Command::Call {
+                id: id.to_string(),
+                location: location.to_string(),
+            },
+            ["skip"] => Command::Skip,
+            ["complete"] => Command::Complete,
+            ["state"] => Command::State,
+            ["announce", "next"] => Command::AnnounceNext,
+            ["announce", "trigger", slot_id] => Command::AnnounceTrigger {
+                slot_id: slot_id.to_string(),
+            },
+            ["translator", location] => Command::Translator {
+                location: location.to_string(),
+            },
+            ["watch"] => Command::Watch,
+            ["help"] => Command::Help,
+            [] => return Err(CtlError::Usage("missing command".to_string())),
+            _ => {
+                return Err(CtlError::Usage(format!(
+                    "invalid command '{}'",
+                    words.join(" ")
+                )))
+            }
+        };
+
+        Ok(Self {
+            command,
+            server: server
+                .unwrap_or_else(|| DEFAULT_SERVER.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            room,
+        })
+    }
+
+    /// URL of an API path such as `/queue/add` for the selected room.
+    pub fn url(&self, path: &str) -> String {
+        match &self.room {
+            Some(room) => format!(
+                "{}/api/rooms/{}{}",
+                self.server,
+                urlencoding::encode(room),
+                path
+            ),
+            None => format!("{}/api{}", self.server, path),
+        }
+    }
+
+    /// Runs the command, printing its output to stdout.
+    pub async fn run(&self) -> Result<(), CtlError> {
+        let client = Client::new();
+        let output = match &self.command {
+            Command::Help => USAGE.to_string(),
+            Command::Call { id, location } => {
+                let body = json!({ "original_id": id, "location": location });
+                self.send(&client, Method::POST, "/queue/add", Some(body))
+                    .await?
+            }
+            Command::Skip => {
+                self.send(&client, Method::POST, "/queue/skip", None)
+                    .await?
+            }
+            Command::Complete => {
+                self.send(&client, Method::POST, "/queue/complete", None)
+                    .await?
+            }
+            Command::State => {
+                let state = self
+                    .send(&client, Method::GET, "/queue/state", None)
+                    .await?;
+                match serde_json::from_str::<Value>(&state) {
+                    Ok(state) => format::queue_state(&state),
+                    Err(_) => state,
+                }
+            }
+            Command::AnnounceNext => {
+                self.send(&client, Method::POST, "/announcements/next", None)
+                    .await?
+            }
+            Command::AnnounceTrigger { slot_id } => {
+                let path = format!("/announcements/trigger/{}", urlencoding::encode(slot_id));
+                self.send(&client, Method::POST, &path, None).await?
+            }
+            Command::Translator { location } => {
+                let body = json!({ "location": location });
+                self.send(&client, Method::POST, "/translator/call", Some(body))
+                    .await?
+            }
+            Command::Watch => return self.watch(&client).await,
+        };
+        println!("{}", output.trim_end());
+        Ok(())
+    }
+
+    /// Sends a request and returns the response body, or the server's
+    /// explanation as [`CtlError::Rejected`].
+    async fn send(
+        &self,
+        client: &Client,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<String, CtlError> {
+        let mut request = client.request(method.clone(), self.url(path));
+        if let Some(body) = body {
+            request = request.json(&body);
+        } else if method == Method::POST {
+            request = request.json(&json!({}));
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if status.is_success() {
+            Ok(format::message(&text))
+        } else {
+            Err(CtlError::Rejected(status.as_u16(), format::message(&text)))
+        }
+    }
+
+    /// Tails the event stream until the server closes it.
+    async fn watch(&self, client: &Client) -> Result<(), CtlError> {
+        let mut response: Response = client
+            .get(self.url("/events"))
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await?;
+            return Err(CtlError::Rejected(status.as_u16(), format::message(&text)));
+        }
+        eprintln!("Watching {} (Ctrl-C to stop)...", self.url("/events"));
+
+        let mut parser = format::SseParser::default();
+        while let Some(chunk) = response.chunk().await? {
+            for event in parser.push(&chunk) {
+                println!("{}", format::event_line(&event, chrono::Local::now()));
+            }
+        }
+        eprintln!("The server closed the event stream.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &[&str]) -> Result<Ctl, CtlError> {
+        Ctl::parse(raw.iter().map(|arg| arg.to_string()), None)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let ctl = parse(&["call", "A12", "3"]).unwrap();
+        assert_eq!(
+            ctl.command,
+            Command::Call {
+                id: "A12".to_string(),
+                location: "3".to_string()
+            }
+        );
+        assert_eq!(ctl.url("/queue/add"), "http://127.0.0.1:3000/api/queue/add");
+
+        let ctl = parse(&[
+            "--room",
+            "clinic b",
+            "announce",
+            "trigger",
+            "01-number-not-queue",
+            "--server",
+            "http://queue:8080/",
+        ])
+        .unwrap();
+        assert_eq!(
+            ctl.command,
+            Command::AnnounceTrigger {
+                slot_id: "01-number-not-queue".to_string()
+            }
+        );
+        assert_eq!(
+            ctl.url("/queue/state"),
+            "http://queue:8080/api/rooms/clinic%20b/queue/state"
+        );
+
+        let ctl = Ctl::parse(["watch".to_string()], Some("http://env:1".to_string())).unwrap();
+        assert_eq!(ctl.command, Command::Watch);
+        assert_eq!(ctl.server, "http://env:1");
+
+        assert!(matches!(parse(&[]), Err(CtlError::Usage(_))));
+        assert!(matches!(parse(&["call", "A12"]), Err(CtlError::Usage(_))));
+        assert!(matches!(parse(&["announce"]), Err(CtlError::Usage(_))));
+        assert!(matches!(
+            parse(&["--verbose", "state"]),
+            Err(CtlError::Usage(_))
+        ));
+        assert!(matches!(
+            parse(&["state", "--room"]),
+            Err(CtlError::Usage(_))
+        ));
+    }
+}
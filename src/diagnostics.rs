@@ -0,0 +1,302 @@
+// src/diagnostics.rs
+
+//! Readiness probe and deep diagnostics.
+//!
+//! `/health` only says that the process answers. `/ready` additionally checks
+//! what announcements depend on — a writable TTS cache and complete audio
+//! stems for every configured language — and `/api/diagnostics` reports those
+//! checks together with runtime figures such as the TTS cache size, the last
+//! online TTS outcome, connected clients and event-bus lag.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::state::{AppState, RoomState};
+use crate::tts::manager::OnlineTtsStatus;
+use crate::tts::stems;
+
+/// Report served by `/api/diagnostics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub uptime_seconds: u64,
+    /// True when `problems` is empty; `/ready` answers 503 otherwise.
+    pub ready: bool,
+    pub problems: Vec<String>,
+    pub directories: Vec<DirectoryCheck>,
+    pub tts_cache: TtsCacheUsage,
+    /// TTS and history writes a shutdown would wait for.
+    pub pending_writes: usize,
+    pub rooms: Vec<RoomDiagnostics>,
+}
+
+/// A directory the server reads from or writes to.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryCheck {
+    /// Variable configuring the directory, e.g. `GTTS_CACHE_BASE_PATH`.
+    pub setting: &'static str,
+    pub path: PathBuf,
+    pub writable: bool,
+    /// Why the directory is unusable, if it is.
+    pub error: Option<String>,
+}
+
+/// Files currently in the TTS cache.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TtsCacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Stem audio of one configured language.
+#[derive(Debug, Clone, Serialize)]
+pub struct StemCheck {
+    pub language: String,
+    pub directory: PathBuf,
+    /// Required stems that are absent; empty when the set is complete.
+    pub missing: Vec<String>,
+}
+
+/// Per-room part of [`Diagnostics`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomDiagnostics {
+    pub id: String,
+    pub stems: Vec<StemCheck>,
+    /// Where call history is kept, e.g. `memory` or the database file.
+    pub history: String,
+    pub announcement_slots: usize,
+    pub banners: usize,
+    pub online_tts: OnlineTtsStatus,
+    /// SSE and WebSocket clients currently connected.
+    pub connected_clients: usize,
+    /// Events lost per subscriber that fell behind the event bus since startup.
+    pub lagged_events: BTreeMap<&'static str, u64>,
+}
+
+/// File system checks shared by `/ready` and `/api/diagnostics`.
+struct FileChecks {
+    directories: Vec<DirectoryCheck>,
+    stems: Vec<(String, Vec<StemCheck>)>,
+    problems: Vec<String>,
+}
+
+/// Problems that keep the server from announcing calls properly; empty when
+/// it is ready.
+pub async fn readiness(state: Arc<AppState>) -> Vec<String> {
+    match tokio::task::spawn_blocking(move || check_files(&state).problems).await {
+        Ok(problems) => problems,
+        Err(e) => vec![format!("readiness checks failed: {}", e)],
+    }
+}
+
+/// Collects the full diagnostics report.
+pub async fn collect(state: Arc<AppState>) -> Diagnostics {
+    let rooms: Vec<Arc<RoomState>> = state
+        .room_ids()
+        .iter()
+        .filter_map(|room_id| state.room(room_id))
+        .collect();
+    let mut announcements = Vec::with_capacity(rooms.len());
+    for room in &rooms {
+        announcements.push(room.announcements.current_status().await);
+    }
+
+    let blocking_state = Arc::clone(&state);
+    let (checks, tts_cache) = tokio::task::spawn_blocking(move || {
+        let config = blocking_state.config.get();
+        (
+            check_files(&blocking_state),
+            tts_cache_usage(&config.gtts_cache_base_path),
+        )
+    })
+    .await
+    .unwrap_or_else(|e| {
+        (
+            FileChecks {
+                directories: Vec::new(),
+                stems: Vec::new(),
+                problems: vec![format!("diagnostics checks failed: {}", e)],
+            },
+            TtsCacheUsage::default(),
+        )
+    });
+
+    let mut stems: BTreeMap<String, Vec<StemCheck>> = checks.stems.into_iter().collect();
+    let rooms = rooms
+        .iter()
+        .zip(announcements)
+        .map(|(room, status)| RoomDiagnostics {
+            id: room.id.clone(),
+            stems: stems.remove(&room.id).unwrap_or_default(),
+            history: room.history.describe(),
+            announcement_slots: status.available_slots.len(),
+            banners: status.current_banner_playlist.len(),
+            online_tts: room.tts.online_status(),
+            connected_clients: room.events.stats().connected_clients(),
+            lagged_events: room.events.stats().lagged(),
+        })
+        .collect();
+
+    Diagnostics {
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: state.uptime().as_secs(),
+        ready: checks.problems.is_empty(),
+        problems: checks.problems,
+        directories: checks.directories,
+        tts_cache,
+        pending_writes: state.in_flight.pending(),
+        rooms,
+    }
+}
+
+fn check_files(state: &AppState) -> FileChecks {
+    let config = state.config.get();
+    let mut checks = FileChecks {
+        directories: Vec::new(),
+        stems: Vec::new(),
+        problems: Vec::new(),
+    };
+
+    if !config.serve_dir_path.is_dir() {
+        checks.problems.push(format!(
+            "SERVE_DIR_PATH: {:?} is not a directory",
+            config.serve_dir_path
+        ));
+    }
+    checks.check_directory("GTTS_CACHE_BASE_PATH", &config.gtts_cache_base_path);
+    let overrides = config.settings_overrides_path.trim();
+    if !overrides.is_empty() {
+        let dir = Path::new(overrides).parent().unwrap_or(Path::new("."));
+        checks.check_directory("SETTINGS_OVERRIDES_PATH", dir);
+    }
+
+    for room_id in state.room_ids() {
+        let Some(room) = state.room(&room_id) else {
+            continue;
+        };
+        let room_config = room.config.get();
+        let room_stems: Vec<StemCheck> = stems::inventory(&room_config)
+            .into_iter()
+            .map(|inventory| StemCheck {
+                language: inventory.language,
+                directory: inventory.directory,
+                missing: inventory.missing,
+            })
+            .collect();
+        for stem in &room_stems {
+            if !stem.missing.is_empty() {
+                checks.problems.push(format!(
+                    "room '{}': audio stems for '{}' in {:?} lack {}",
+                    room_id,
+                    stem.language,
+                    stem.directory,
+                    stem.missing.join(", ")
+                ));
+            }
+        }
+        checks.stems.push((room_id, room_stems));
+    }
+    checks
+}
+
+impl FileChecks {
+    fn check_directory(&mut self, setting: &'static str, path: &Path) {
+        let result = check_writable(path);
+        if let Err(e) = &result {
+            self.problems.push(format!("{}: {}", setting, e));
+        }
+        self.directories.push(DirectoryCheck {
+            setting,
+            path: path.to_path_buf(),
+            writable: result.is_ok(),
+            error: result.err(),
+        });
+    }
+}
+
+/// Files and bytes below the TTS cache directory, one level of language
+/// directories deep as the cache is laid out.
+fn tts_cache_usage(base: &Path) -> TtsCacheUsage {
+    let mut usage = TtsCacheUsage::default();
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return usage;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let files: Vec<PathBuf> = if path.is_dir() {
+            match std::fs::read_dir(&path) {
+                Ok(inner) => inner.flatten().map(|entry| entry.path()).collect(),
+                Err(_) => continue,
+            }
+        } else {
+            vec![path]
+        };
+        for file in files {
+            if let Ok(metadata) = std::fs::metadata(&file) {
+                if metadata.is_file() {
+                    usage.files += 1;
+                    usage.bytes += metadata.len();
+                }
+            }
+        }
+    }
+    usage
+}
+
+/// Numbers the probe files of [`check_writable`], so concurrent checks of the
+/// same directory never share one.
+static PROBE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Checks that files can be created in `dir`, or in its nearest existing
+/// ancestor when the directory does not exist yet.
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    let existing = dir
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or(Path::new("."));
+    if !existing.is_dir() {
+        return Err(format!("{:?} is not a directory", existing));
+    }
+    let probe = existing.join(format!(
+        ".queue-check-{}-{}",
+        std::process::id(),
+        PROBE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&probe, b"")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| format!("{:?} is not writable: {}", existing, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tts_cache_usage_counts_language_dirs() {
+        let base = std::env::temp_dir().join(format!("queue-diagnostics-{}", std::process::id()));
+        std::fs::create_dir_all(base.join("multi")).unwrap();
+        std::fs::write(base.join("multi/a12_3.mp3"), b"abcd").unwrap();
+        std::fs::write(base.join("multi/a13_3.mp3"), b"ef").unwrap();
+        std::fs::write(base.join("stray.mp3"), b"g").unwrap();
+
+        let usage = tts_cache_usage(&base);
+        assert_eq!((usage.files, usage.bytes), (3, 7));
+        assert!(check_writable(&base.join("not/yet/created")).is_ok());
+        let checks: Vec<_> = (0..8)
+            .map(|_| {
+                let dir = base.clone();
+                std::thread::spawn(move || check_writable(&dir))
+            })
+            .collect();
+        for check in checks {
+            assert!(check.join().unwrap().is_ok());
+        }
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(tts_cache_usage(&base).files, 0);
+    }
+}
@@ -0,0 +1,228 @@
+// src/displays/commands.rs
+
+//! Remote control commands that can be pushed to signage displays.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Default duration of the identification overlay when none is supplied.
+pub const DEFAULT_IDENTIFY_DURATION_SECONDS: u64 = 10;
+/// Upper bound for the identification overlay duration.
+pub const MAX_IDENTIFY_DURATION_SECONDS: u64 = 300;
+/// Upper bound for the volume level (percent).
+pub const MAX_VOLUME_LEVEL: u8 = 100;
+
+/// An action a display should perform.
+///
+/// Serialized with an `action` tag, e.g. `{"action":"set_volume","level":40}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DisplayCommand {
+    /// Reload the signage page.
+    Reload,
+    /// Set the playback volume, in percent (0-100).
+    SetVolume { level: u8 },
+    /// Mute all audio playback.
+    Mute,
+    /// Restore audio playback after a mute.
+    Unmute,
+    /// Play the call chime so staff can verify audio output.
+    TestChime,
+    /// Show an overlay with the display id for the given duration.
+    Identify {
+        #[serde(default = "default_identify_duration")]
+        duration_seconds: u64,
+    },
+}
+
+fn default_identify_duration() -> u64 {
+    DEFAULT_IDENTIFY_DURATION_SECONDS
+}
+
+impl DisplayCommand {
+    /// Checks command parameters are within their supported ranges.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            DisplayCommand::SetVolume { level } if *level > MAX_VOLUME_LEVEL => Err(format!(
+                "Volume level must be between 0 and {}. Received: {}",
+                MAX_VOLUME_LEVEL, level
+            )),
+            DisplayCommand::Identify { duration_seconds }
+                if *duration_seconds == 0 || *duration_seconds > MAX_IDENTIFY_DURATION_SECONDS =>
+            {
+                Err(format!(
+                    "Identify duration must be between 1 and {} seconds. Received: {}",
+                    MAX_IDENTIFY_DURATION_SECONDS, duration_seconds
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Which displays a command is addressed to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "scope", content = "id", rename_all = "snake_case")]
+pub enum DisplayTarget {
+    /// Every identified display.
+    All,
+    /// A single display by id.
+    Display(String),
+    /// Every display that registered with the given zone.
+    Zone(String),
+}
+
+impl DisplayTarget {
+    /// Returns true when a display with the given id and zone is addressed.
+    pub fn includes(&self, display_id: &str, zone: Option<&str>) -> bool {
+        match self {
+            DisplayTarget::All => true,
+            DisplayTarget::Display(target_id) => target_id == display_id,
+            DisplayTarget::Zone(target_zone) => zone == Some(target_zone.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for DisplayTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayTarget::All => write!(f, "all displays"),
+            DisplayTarget::Display(id) => write!(f, "display '{}'", id),
+            DisplayTarget::Zone(zone) => write!(f, "zone '{}'", zone),
+        }
+    }
+}
+
+/// Outcome reported by a display when acknowledging a command.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayCommandStatus {
+    /// The command was carried out.
+    Ok,
+    /// The display received the command but could not carry it out.
+    Failed,
+}
+
+/// Acknowledgement received from one display.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DisplayCommandAck {
+    pub display_id: String,
+    pub status: DisplayCommandStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub acknowledged_at: DateTime<Utc>,
+}
+
+/// Bookkeeping for an issued command and the acknowledgements it received.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DisplayCommandRecord {
+    pub command_id: String,
+    pub target: DisplayTarget,
+    pub command: DisplayCommand,
+    pub issued_at: DateTime<Utc>,
+    /// Displays that were connected and addressed when the command was issued.
+    pub expected_displays: Vec<String>,
+    pub acknowledgements: Vec<DisplayCommandAck>,
+}
+
+impl DisplayCommandRecord {
+    /// Displays that were expected to acknowledge but have not done so yet.
+    pub fn pending_displays(&self) -> Vec<&str> {
+        self.expected_displays
+            .iter()
+            .filter(|id| {
+                !self
+                    .acknowledgements
+                    .iter()
+                    .any(|ack| &ack.display_id == *id)
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Errors that can occur while issuing or acknowledging display commands.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DisplayCommandError {
+    /// Command parameters are out of range.
+    InvalidCommand(String),
+    /// The targeted display has never connected.
+    UnknownDisplay(String),
+    /// No registered display belongs to the targeted zone.
+    EmptyZone(String),
+    /// No command with the given id is being tracked.
+    CommandNotFound(String),
+    /// The acknowledging display was not addressed by the command.
+    NotTargeted {
+        command_id: String,
+        display_id: String,
+    },
+}
+
+impl fmt::Display for DisplayCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayCommandError::InvalidCommand(message) => write!(f, "{}", message),
+            DisplayCommandError::UnknownDisplay(id) => {
+                write!(f, "Display '{}' is not registered.", id)
+            }
+            DisplayCommandError::EmptyZone(zone) => {
+                write!(f, "No registered displays belong to zone '{}'.", zone)
+            }
+            DisplayCommandError::CommandNotFound(id) => {
+                write!(f, "Display command '{}' was not found.", id)
+            }
+            DisplayCommandError::NotTargeted {
+                command_id,
+                display_id,
+            } => write!(
+                f,
+                "Display '{}' was not targeted by command '{}'.",
+                display_id, command_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DisplayCommandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_commands_with_action_tag() {
+        let command: DisplayCommand =
+            serde_json::from_str(r#"{"action":"set_volume","level":40}"#).unwrap();
+        assert_eq!(command, DisplayCommand::SetVolume { level: 40 });
+
+        let command: DisplayCommand = serde_json::from_str(r#"{"action":"identify"}"#).unwrap();
+        assert_eq!(
+            command,
+            DisplayCommand::Identify {
+                duration_seconds: DEFAULT_IDENTIFY_DURATION_SECONDS
+            }
+        );
+    }
+
+    #[test]
+    fn validates_parameter_ranges() {
+        assert!(DisplayCommand::SetVolume { level: 100 }.validate().is_ok());
+        assert!(DisplayCommand::SetVolume { level: 101 }.validate().is_err());
+        assert!(DisplayCommand::Identify {
+            duration_seconds: 0
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn target_matching() {
+        assert!(DisplayTarget::All.includes("tv-1", None));
+        assert!(DisplayTarget::Display("tv-1".to_string()).includes("tv-1", None));
+        assert!(!DisplayTarget::Display("tv-1".to_string()).includes("tv-2", None));
+        assert!(DisplayTarget::Zone("lobby".to_string()).includes("tv-2", Some("lobby")));
+        assert!(!DisplayTarget::Zone("lobby".to_string()).includes("tv-2", None));
+    }
+}
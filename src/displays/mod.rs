@@ -0,0 +1,57 @@
+// src/displays/mod.rs
+
+//! Tracks signage displays connected to the event stream.
+//!
+//! Displays identify themselves with a `display_id` query parameter when they
+//! open `/api/events`. The `DisplayRegistry` records when each display connected,
+//! where it connected from and when it was last seen, so operators can tell
+//! which screens are actually online. Operators can push remote control
+//! commands (see [`commands`]) to a single display, a zone or every display.
+
+pub mod commands;
+pub mod registry;
+
+pub use commands::{
+    DisplayCommand, DisplayCommandAck, DisplayCommandError, DisplayCommandRecord,
+    DisplayCommandStatus, DisplayTarget,
+};
+pub use registry::{DisplayInfo, DisplayRegistry};
+
+/// Maximum accepted length of a client-supplied display identifier.
+pub const MAX_DISPLAY_ID_LENGTH: usize = 64;
+
+/// Validates a client-supplied display identifier.
+///
+/// Identifiers are limited to ASCII letters, digits, `-`, `_` and `.` so they
+/// stay readable in logs and safe to embed in URLs.
+pub fn validate_display_id(raw: &str) -> Result<String, String> {
+    validate_label("Display id", raw)
+}
+
+/// Validates a display zone label. Zones follow the same rules as display ids.
+pub fn validate_zone(raw: &str) -> Result<String, String> {
+    validate_label("Zone", raw)
+}
+
+fn validate_label(kind: &str, raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(format!("{} must not be empty.", kind));
+    }
+    if trimmed.len() > MAX_DISPLAY_ID_LENGTH {
+        return Err(format!(
+            "{} must be at most {} characters long.",
+            kind, MAX_DISPLAY_ID_LENGTH
+        ));
+    }
+    if !trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "{} may only contain letters, digits, '-', '_' or '.'. Received: {}",
+            kind, trimmed
+        ));
+    }
+    Ok(trimmed.to_string())
+}
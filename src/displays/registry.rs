@@ -0,0 +1,450 @@
+// src/displays/registry.rs
+
+//! In-memory registry of signage displays and their connection state.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::commands::{
+    DisplayCommand, DisplayCommandAck, DisplayCommandError, DisplayCommandRecord,
+    DisplayCommandStatus, DisplayTarget,
+};
+
+/// Number of issued commands kept for acknowledgement tracking.
+pub const MAX_TRACKED_COMMANDS: usize = 100;
+
+/// Number of displays kept in the registry. When a new display id connects
+/// beyond this, the display that has been offline longest is forgotten.
+pub const MAX_TRACKED_DISPLAYS: usize = 500;
+
+/// Connection details recorded for a single display.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DisplayInfo {
+    /// Client-supplied identifier of the display.
+    pub display_id: String,
+    /// Zone label the display registered with, used to target commands.
+    pub zone: Option<String>,
+    /// `User-Agent` header sent by the most recent connection.
+    pub user_agent: Option<String>,
+    /// Remote address of the most recent connection.
+    pub ip: Option<String>,
+    /// When the most recent connection was opened.
+    pub connected_at: DateTime<Utc>,
+    /// When the display was last seen alive (connect, keep-alive or explicit heartbeat).
+    pub last_heartbeat: DateTime<Utc>,
+    /// When the last connection closed, if the display is currently offline.
+    pub disconnected_at: Option<DateTime<Utc>>,
+    /// Number of event streams currently open for this display id.
+    pub active_connections: usize,
+    /// Convenience flag mirroring `active_connections > 0`.
+    pub connected: bool,
+    /// Whether a disconnect alert has been raised for the current outage.
+    pub alert_raised: bool,
+}
+
+/// Keeps track of every display that has connected since start-up.
+///
+/// Offline displays stay registered so that an outage stays visible in
+/// `GET /api/displays` until the screen comes back, unless more than
+/// [`MAX_TRACKED_DISPLAYS`] ids have connected.
+#[derive(Debug, Default)]
+pub struct DisplayRegistry {
+    displays: HashMap<String, DisplayInfo>,
+    /// Recently issued commands, oldest first.
+    commands: VecDeque<DisplayCommandRecord>,
+    next_command_seq: u64,
+}
+
+impl DisplayRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a new event-stream connection for `display_id`.
+    ///
+    /// # Returns
+    /// `true` when a display that had an open disconnect alert came back online.
+    pub fn connect(
+        &mut self,
+        display_id: &str,
+        zone: Option<String>,
+        user_agent: Option<String>,
+        ip: Option<String>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if !self.displays.contains_key(display_id) && self.displays.len() >= MAX_TRACKED_DISPLAYS {
+            self.evict_longest_offline();
+        }
+        let entry = self
+            .displays
+            .entry(display_id.to_string())
+            .or_insert_with(|| DisplayInfo {
+                display_id: display_id.to_string(),
+                zone: None,
+                user_agent: None,
+                ip: None,
+                connected_at: now,
+                last_heartbeat: now,
+                disconnected_at: None,
+                active_connections: 0,
+                connected: false,
+                alert_raised: false,
+            });
+
+        let recovered = entry.alert_raised;
+        entry.zone = zone;
+        entry.user_agent = user_agent;
+        entry.ip = ip;
+        entry.connected_at = now;
+        entry.last_heartbeat = now;
+        entry.disconnected_at = None;
+        entry.active_connections += 1;
+        entry.connected = true;
+        entry.alert_raised = false;
+
+        info!(
+            "Display '{}' connected (zone: {:?}, ip: {:?}, active connections: {}).",
+            display_id, entry.zone, entry.ip, entry.active_connections
+        );
+        recovered
+    }
+
+    /// Forgets the display that has been offline longest. Connected displays
+    /// are kept; they are bounded by the open connections.
+    fn evict_longest_offline(&mut self) {
+        let oldest = self
+            .displays
+            .values()
+            .filter_map(|entry| Some((entry.disconnected_at?, entry.display_id.clone())))
+            .min();
+        if let Some((_, display_id)) = oldest {
+            debug!(
+                "Display registry is full; forgetting offline display '{}'.",
+                display_id
+            );
+            self.displays.remove(&display_id);
+        }
+    }
+
+    /// Records that one event-stream connection for `display_id` has closed.
+    pub fn disconnect(&mut self, display_id: &str, now: DateTime<Utc>) {
+        let Some(entry) = self.displays.get_mut(display_id) else {
+            debug!("Disconnect reported for unknown display '{}'.", display_id);
+            return;
+        };
+
+        entry.active_connections = entry.active_connections.saturating_sub(1);
+        if entry.active_connections == 0 {
+            entry.connected = false;
+            entry.disconnected_at = Some(now);
+            info!("Display '{}' disconnected.", display_id);
+        } else {
+            debug!(
+                "Display '{}' closed one connection; {} still open.",
+                display_id, entry.active_connections
+            );
+        }
+    }
+
+    /// Refreshes the last heartbeat of a known display.
+    ///
+    /// # Returns
+    /// `false` if the display has never connected.
+    pub fn heartbeat(&mut self, display_id: &str, now: DateTime<Utc>) -> bool {
+        match self.displays.get_mut(display_id) {
+            Some(entry) => {
+                entry.last_heartbeat = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a snapshot of every known display, sorted by id.
+    pub fn list(&self) -> Vec<DisplayInfo> {
+        let mut displays: Vec<DisplayInfo> = self.displays.values().cloned().collect();
+        displays.sort_by(|a, b| a.display_id.cmp(&b.display_id));
+        displays
+    }
+
+    /// Returns the number of displays with at least one open connection.
+    pub fn connected_count(&self) -> usize {
+        self.displays.values().filter(|d| d.connected).count()
+    }
+
+    /// Marks and returns displays that have been offline for longer than `threshold`
+    /// and have not been alerted on yet for the current outage.
+    pub fn take_overdue(&mut self, threshold: Duration, now: DateTime<Utc>) -> Vec<DisplayInfo> {
+        let threshold = chrono::Duration::from_std(threshold).unwrap_or(chrono::Duration::MAX);
+        let mut overdue = Vec::new();
+        for entry in self.displays.values_mut() {
+            if entry.connected || entry.alert_raised {
+                continue;
+            }
+            let Some(disconnected_at) = entry.disconnected_at else {
+                continue;
+            };
+            if now - disconnected_at >= threshold {
+                entry.alert_raised = true;
+                overdue.push(entry.clone());
+            }
+        }
+        overdue.sort_by(|a, b| a.display_id.cmp(&b.display_id));
+        overdue
+    }
+
+    /// Records a new command addressed to `target` and returns its tracking record.
+    ///
+    /// Targets naming a display or zone that was never registered are rejected so
+    /// typos do not silently go nowhere. Only displays connected right now are
+    /// expected to acknowledge.
+    pub fn issue_command(
+        &mut self,
+        target: DisplayTarget,
+        command: DisplayCommand,
+        now: DateTime<Utc>,
+    ) -> Result<DisplayCommandRecord, DisplayCommandError> {
+        command
+            .validate()
+            .map_err(DisplayCommandError::InvalidCommand)?;
+
+        match &target {
+            DisplayTarget::All => {}
+            DisplayTarget::Display(id) => {
+                if !self.displays.contains_key(id) {
+                    return Err(DisplayCommandError::UnknownDisplay(id.clone()));
+                }
+            }
+            DisplayTarget::Zone(zone) => {
+                if !self
+                    .displays
+                    .values()
+                    .any(|d| d.zone.as_deref() == Some(zone.as_str()))
+                {
+                    return Err(DisplayCommandError::EmptyZone(zone.clone()));
+                }
+            }
+        }
+
+        let mut expected_displays: Vec<String> = self
+            .displays
+            .values()
+            .filter(|d| d.connected && target.includes(&d.display_id, d.zone.as_deref()))
+            .map(|d| d.display_id.clone())
+            .collect();
+        expected_displays.sort();
+
+        self.next_command_seq += 1;
+        let record = DisplayCommandRecord {
+            command_id: format!("cmd-{}-{}", now.timestamp_millis(), self.next_command_seq),
+            target,
+            command,
+            issued_at: now,
+            expected_displays,
+            acknowledgements: Vec::new(),
+        };
+
+        if self.commands.len() >= MAX_TRACKED_COMMANDS {
+            self.commands.pop_front();
+        }
+        self.commands.push_back(record.clone());
+        info!(
+            "Display command '{}' ({:?}) issued to {}; expecting {} acknowledgement(s).",
+            record.command_id,
+            record.command,
+            record.target,
+            record.expected_displays.len()
+        );
+        Ok(record)
+    }
+
+    /// Stores an acknowledgement from `display_id` for a tracked command.
+    /// A repeated acknowledgement from the same display replaces the earlier one.
+    pub fn acknowledge_command(
+        &mut self,
+        command_id: &str,
+        display_id: &str,
+        status: DisplayCommandStatus,
+        message: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<DisplayCommandRecord, DisplayCommandError> {
+        let zone = self.displays.get(display_id).and_then(|d| d.zone.clone());
+        let record = self
+            .commands
+            .iter_mut()
+            .find(|c| c.command_id == command_id)
+            .ok_or_else(|| DisplayCommandError::CommandNotFound(command_id.to_string()))?;
+
+        if !record.target.includes(display_id, zone.as_deref())
+            && !record.expected_displays.iter().any(|id| id == display_id)
+        {
+            return Err(DisplayCommandError::NotTargeted {
+                command_id: command_id.to_string(),
+                display_id: display_id.to_string(),
+            });
+        }
+
+        record
+            .acknowledgements
+            .retain(|ack| ack.display_id != display_id);
+        record.acknowledgements.push(DisplayCommandAck {
+            display_id: display_id.to_string(),
+            status,
+            message,
+            acknowledged_at: now,
+        });
+        debug!(
+            "Display '{}' acknowledged command '{}' with {:?}.",
+            display_id, command_id, status
+        );
+        Ok(record.clone())
+    }
+
+    /// Returns tracked commands, newest first.
+    pub fn commands(&self) -> Vec<DisplayCommandRecord> {
+        self.commands.iter().rev().cloned().collect()
+    }
+
+    /// Returns a tracked command by id.
+    pub fn command(&self, command_id: &str) -> Option<DisplayCommandRecord> {
+        self.commands
+            .iter()
+            .find(|c| c.command_id == command_id)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgets_the_longest_offline_display_when_full() {
+        let mut registry = DisplayRegistry::new();
+        let start = Utc::now();
+        for index in 0..MAX_TRACKED_DISPLAYS {
+            let id = format!("tv-{}", index);
+            registry.connect(&id, None, None, None, start);
+            if index < 2 {
+                let offline_since = start + chrono::Duration::seconds(index as i64);
+                registry.disconnect(&id, offline_since);
+            }
+        }
+
+        registry.connect("tv-0", None, None, None, start);
+        assert_eq!(registry.list().len(), MAX_TRACKED_DISPLAYS, "known id");
+
+        registry.connect("rotating-1", None, None, None, start);
+        let ids: Vec<String> = registry.list().into_iter().map(|d| d.display_id).collect();
+        assert_eq!(ids.len(), MAX_TRACKED_DISPLAYS);
+        assert!(
+            !ids.contains(&"tv-1".to_string()),
+            "longest offline is evicted"
+        );
+        assert!(ids.contains(&"rotating-1".to_string()));
+
+        registry.connect("rotating-2", None, None, None, start);
+        assert_eq!(
+            registry.list().len(),
+            MAX_TRACKED_DISPLAYS + 1,
+            "connected displays are never evicted"
+        );
+    }
+
+    #[test]
+    fn tracks_connections_and_raises_single_alert() {
+        let mut registry = DisplayRegistry::new();
+        let start = Utc::now();
+
+        registry.connect("lobby-1", None, Some("TV".to_string()), None, start);
+        registry.connect("lobby-1", None, Some("TV".to_string()), None, start);
+        registry.disconnect("lobby-1", start);
+        assert!(registry.list()[0].connected, "one stream is still open");
+
+        registry.disconnect("lobby-1", start);
+        assert_eq!(registry.connected_count(), 0);
+
+        let threshold = Duration::from_secs(60);
+        assert!(registry
+            .take_overdue(threshold, start + chrono::Duration::seconds(30))
+            .is_empty());
+
+        let later = start + chrono::Duration::seconds(61);
+        let overdue = registry.take_overdue(threshold, later);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].display_id, "lobby-1");
+        assert!(
+            registry.take_overdue(threshold, later).is_empty(),
+            "an outage is only alerted once"
+        );
+
+        assert!(registry.connect("lobby-1", None, None, None, later));
+        assert!(!registry.list()[0].alert_raised);
+    }
+
+    #[test]
+    fn heartbeat_requires_known_display() {
+        let mut registry = DisplayRegistry::new();
+        let now = Utc::now();
+        assert!(!registry.heartbeat("unknown", now));
+
+        registry.connect("tv", None, None, None, now);
+        let later = now + chrono::Duration::seconds(5);
+        assert!(registry.heartbeat("tv", later));
+        assert_eq!(registry.list()[0].last_heartbeat, later);
+    }
+
+    #[test]
+    fn commands_track_expected_displays_and_acks() {
+        let mut registry = DisplayRegistry::new();
+        let now = Utc::now();
+        registry.connect("lobby-1", Some("lobby".to_string()), None, None, now);
+        registry.connect("lobby-2", Some("lobby".to_string()), None, None, now);
+        registry.connect("upstairs-1", Some("upstairs".to_string()), None, None, now);
+
+        assert_eq!(
+            registry.issue_command(
+                DisplayTarget::Zone("basement".to_string()),
+                DisplayCommand::Reload,
+                now
+            ),
+            Err(DisplayCommandError::EmptyZone("basement".to_string()))
+        );
+
+        let record = registry
+            .issue_command(
+                DisplayTarget::Zone("lobby".to_string()),
+                DisplayCommand::Mute,
+                now,
+            )
+            .unwrap();
+        assert_eq!(record.expected_displays, vec!["lobby-1", "lobby-2"]);
+
+        let err = registry
+            .acknowledge_command(
+                &record.command_id,
+                "upstairs-1",
+                DisplayCommandStatus::Ok,
+                None,
+                now,
+            )
+            .unwrap_err();
+        assert!(matches!(err, DisplayCommandError::NotTargeted { .. }));
+
+        let updated = registry
+            .acknowledge_command(
+                &record.command_id,
+                "lobby-1",
+                DisplayCommandStatus::Ok,
+                None,
+                now,
+            )
+            .unwrap();
+        assert_eq!(updated.pending_displays(), vec!["lobby-2"]);
+        assert_eq!(registry.commands()[0], updated);
+    }
+}
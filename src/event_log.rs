@@ -0,0 +1,300 @@
+// src/event_log.rs
+
+//! Numbered replay buffer in front of the event bus.
+//!
+//! Every [`AppEvent`] broadcast on a room's event bus is assigned a monotonically
+//! increasing id and kept in a bounded buffer. SSE clients use the ids for
+//! `Last-Event-ID` resumption and long-polling clients use them as a cursor, so
+//! both transports see exactly the same sequence of events.
+//! [`EventBusStats`] counts the clients streaming a room's events and the
+//! events its subscribers lost by falling behind.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+use crate::AppEvent;
+
+/// An event together with the id assigned by the [`EventLog`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LoggedEvent {
+    pub id: u64,
+    pub event: AppEvent,
+}
+
+/// Result of reading the log from a cursor.
+#[derive(Debug, Clone, Default)]
+pub struct EventReplay {
+    /// Buffered events newer than the cursor, oldest first.
+    pub events: Vec<Arc<LoggedEvent>>,
+    /// Id of the newest event recorded so far (0 when nothing was recorded yet).
+    pub last_event_id: u64,
+    /// True when events after the cursor have already been evicted from the buffer.
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+struct LogState {
+    events: VecDeque<Arc<LoggedEvent>>,
+    capacity: usize,
+    last_id: u64,
+}
+
+/// Bounded, numbered history of the events broadcast on one event bus.
+#[derive(Clone)]
+pub struct EventLog {
+    state: Arc<Mutex<LogState>>,
+    sender: broadcast::Sender<Arc<LoggedEvent>>,
+    stats: EventBusStats,
+}
+
+/// Connected streaming clients and lag counts of one event bus, shared by its
+/// clones.
+#[derive(Clone, Debug, Default)]
+pub struct EventBusStats {
+    clients: Arc<AtomicUsize>,
+    lagged: Arc<Mutex<BTreeMap<&'static str, u64>>>,
+}
+
+/// Counts one SSE or WebSocket client until dropped.
+#[derive(Debug)]
+pub struct ConnectedClient {
+    clients: Arc<AtomicUsize>,
+}
+
+impl EventBusStats {
+    /// Counts a streaming client for as long as the returned guard lives.
+    pub fn connect(&self) -> ConnectedClient {
+        self.clients.fetch_add(1, Ordering::Relaxed);
+        ConnectedClient {
+            clients: Arc::clone(&self.clients),
+        }
+    }
+
+    /// Number of SSE and WebSocket clients currently connected.
+    pub fn connected_clients(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+
+    /// Records that `subscriber` (e.g. `sse` or `webhooks`) fell behind and
+    /// lost `skipped` events.
+    pub fn record_lag(&self, subscriber: &'static str, skipped: u64) {
+        *self
+            .lagged
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(subscriber)
+            .or_default() += skipped;
+    }
+
+    /// Events lost per subscriber since startup; subscribers that never fell
+    /// behind are absent.
+    pub fn lagged(&self) -> BTreeMap<&'static str, u64> {
+        self.lagged
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Drop for ConnectedClient {
+    fn drop(&mut self) {
+        self.clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl EventLog {
+    /// Create a log keeping the last `capacity` events and start recording events
+    /// from `event_bus`. `channel_capacity` bounds how far a live subscriber may
+    /// fall behind before it starts losing events.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn new(
+        event_bus: &broadcast::Sender<AppEvent>,
+        capacity: usize,
+        channel_capacity: usize,
+    ) -> Self {
+        let log = Self::detached(capacity, channel_capacity);
+        let recorder = log.clone();
+        let mut receiver = event_bus.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        recorder.record(event);
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "EventLog: recorder lagged and lost {} events. Consider increasing SSE_EVENT_BUFFER_SIZE.",
+                            skipped
+                        );
+                        recorder.stats.record_lag("event_log", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            debug!("EventLog: event bus closed, recorder stopped.");
+        });
+        log
+    }
+
+    fn detached(capacity: usize, channel_capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(channel_capacity.max(1));
+        Self {
+            state: Arc::new(Mutex::new(LogState {
+                events: VecDeque::with_capacity(capacity),
+                capacity: capacity.max(1),
+                last_id: 0,
+            })),
+            sender,
+            stats: EventBusStats::default(),
+        }
+    }
+
+    /// Append an event, assigning it the next id, and notify live subscribers.
+    pub fn record(&self, event: AppEvent) -> Arc<LoggedEvent> {
+        let mut state = self.lock();
+        state.last_id += 1;
+        let logged = Arc::new(LoggedEvent {
+            id: state.last_id,
+            event,
+        });
+        if state.events.len() >= state.capacity {
+            state.events.pop_front();
+        }
+        state.events.push_back(Arc::clone(&logged));
+        // Sent while holding the lock so `replay_and_subscribe` never misses or
+        // duplicates an event.
+        let _ = self.sender.send(Arc::clone(&logged));
+        logged
+    }
+
+    /// Buffered events with an id greater than `since`.
+    pub fn since(&self, since: u64) -> EventReplay {
+        Self::replay_locked(&self.lock(), since)
+    }
+
+    /// Atomically read the events after `since` and subscribe to newer ones.
+    pub fn replay_and_subscribe(
+        &self,
+        since: u64,
+    ) -> (EventReplay, broadcast::Receiver<Arc<LoggedEvent>>) {
+        let state = self.lock();
+        let replay = Self::replay_locked(&state, since);
+        (replay, self.sender.subscribe())
+    }
+
+    /// Subscribe to events recorded from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<LoggedEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Client and lag counts of the event bus behind this log.
+    pub fn stats(&self) -> &EventBusStats {
+        &self.stats
+    }
+
+    /// Id of the newest recorded event.
+    pub fn last_event_id(&self) -> u64 {
+        self.lock().last_id
+    }
+
+    fn replay_locked(state: &LogState, since: u64) -> EventReplay {
+        if since > state.last_id {
+            // The cursor comes from before a restart; ids start over, so hand out
+            // everything that is still buffered.
+            return EventReplay {
+                events: state.events.iter().cloned().collect(),
+                last_event_id: state.last_id,
+                truncated: true,
+            };
+        }
+        let oldest_id = state.events.front().map(|e| e.id);
+        EventReplay {
+            events: state
+                .events
+                .iter()
+                .filter(|e| e.id > since)
+                .cloned()
+                .collect(),
+            last_event_id: state.last_id,
+            truncated: oldest_id.is_some_and(|oldest| oldest > since + 1),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LogState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(display_id: &str) -> AppEvent {
+        AppEvent::DisplayAlert {
+            display_id: display_id.to_string(),
+            disconnected_at: chrono::Utc::now(),
+            offline_seconds: 1,
+        }
+    }
+
+    #[test]
+    fn replays_from_cursor_and_reports_eviction() {
+        let log = EventLog::detached(3, 8);
+        for name in ["a", "b", "c", "d"] {
+            log.record(alert(name));
+        }
+
+        let replay = log.since(2);
+        assert_eq!(
+            replay.events.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(replay.last_event_id, 4);
+        assert!(!replay.truncated);
+
+        let replay = log.since(0);
+        assert_eq!(replay.events.len(), 3);
+        assert!(replay.truncated, "event 1 was evicted");
+        assert!(log.since(4).events.is_empty());
+
+        let stale = log.since(40);
+        assert_eq!(stale.events.len(), 3, "cursor from before a restart");
+        assert!(stale.truncated);
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_events_after_replay() {
+        let log = EventLog::detached(10, 8);
+        log.record(alert("a"));
+        let (replay, mut receiver) = log.replay_and_subscribe(0);
+        assert_eq!(replay.events.len(), 1);
+
+        log.record(alert("b"));
+        assert_eq!(receiver.recv().await.unwrap().id, 2);
+    }
+
+    #[test]
+    fn stats_count_clients_and_lag() {
+        let log = EventLog::detached(3, 8);
+        let stats = log.clone().stats().clone();
+        let first = stats.connect();
+        let second = log.stats().connect();
+        assert_eq!(log.stats().connected_clients(), 2);
+        drop(first);
+        drop(second);
+        assert_eq!(stats.connected_clients(), 0);
+
+        stats.record_lag("sse", 3);
+        log.stats().record_lag("sse", 2);
+        log.stats().record_lag("webhooks", 1);
+        assert_eq!(
+            log.stats().lagged().into_iter().collect::<Vec<_>>(),
+            vec![("sse", 5), ("webhooks", 1)]
+        );
+    }
+}
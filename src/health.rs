@@ -1,13 +1,38 @@
-use axum::{http::StatusCode, Json};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, Json};
 use serde::Serialize;
 
+use crate::{diagnostics, AppState};
+
 /// Simple health response returned by the `/health` endpoint.
 #[derive(Serialize)]
 pub struct HealthResponse {
     status: &'static str,
 }
 
+/// Response of the `/ready` endpoint.
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    status: &'static str,
+    /// What keeps the server from being ready; empty when it is.
+    problems: Vec<String>,
+}
+
 /// Liveness probe that always returns HTTP 200 when the service is up.
 pub async fn check() -> (StatusCode, Json<HealthResponse>) {
     (StatusCode::OK, Json(HealthResponse { status: "ok" }))
 }
+
+/// Readiness probe: HTTP 200 when the TTS cache is writable and every
+/// configured language has complete audio stems, HTTP 503 listing the
+/// problems otherwise. See `/api/diagnostics` for details.
+pub async fn ready(State(state): State<Arc<AppState>>) -> (StatusCode, Json<ReadinessResponse>) {
+    let problems = diagnostics::readiness(state).await;
+    let (code, status) = if problems.is_empty() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (code, Json(ReadinessResponse { status, problems }))
+}
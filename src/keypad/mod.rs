@@ -0,0 +1,208 @@
+// src/keypad/mod.rs
+
+//! Line-based TCP protocol for hardware caller keypads.
+//!
+//! Each request is one line of ASCII words, e.g. `CALL A12 3`; every request
+//! receives exactly one `OK <message>` or `ERR <message>` line. Whenever the
+//! current call of the session's room changes, the server also pushes an
+//! `UPDATE <number> <counter>` line (`UPDATE - -` when no call is active).
+//!
+//! | Request                  | Action                                             |
+//! |--------------------------|----------------------------------------------------|
+//! | `CALL <number> <counter>`| Call a number at a counter.                        |
+//! | `RECALL`                 | Repeat the current call.                           |
+//! | `SKIP`                   | Skip the current call.                             |
+//! | `COMPLETE`               | Complete the current call.                         |
+//! | `NEXT <counter>`         | Call the next waiting ticket, or the number after  |
+//! |                          | the most recent call when no ticket is waiting.    |
+//! | `TRANSLATOR <counter>`   | Request a translator at a counter.                 |
+//! | `ROOM <id>`              | Switch the session to another room.                |
+
+pub mod server;
+
+use std::fmt;
+
+use crate::{queue::QueueState, Call};
+
+/// Longest request line accepted, in bytes (excluding the line terminator).
+pub const MAX_LINE_LENGTH: usize = 128;
+
+/// A parsed keypad request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeypadCommand {
+    Call {
+        original_id: String,
+        location: String,
+    },
+    Recall,
+    Skip,
+    Complete,
+    Next {
+        location: String,
+    },
+    Translator {
+        location: String,
+    },
+    Room {
+        room_id: String,
+    },
+}
+
+/// Reasons a request line is rejected before it reaches the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeypadParseError {
+    Empty,
+    UnknownCommand(String),
+    Usage(&'static str),
+}
+
+impl fmt::Display for KeypadParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeypadParseError::Empty => write!(f, "Empty command"),
+            KeypadParseError::UnknownCommand(verb) => write!(
+                f,
+                "Unknown command '{}'. Use CALL, RECALL, SKIP, COMPLETE, NEXT, TRANSLATOR or ROOM",
+                verb
+            ),
+            KeypadParseError::Usage(usage) => write!(f, "Usage: {}", usage),
+        }
+    }
+}
+
+impl std::error::Error for KeypadParseError {}
+
+impl KeypadCommand {
+    /// Parses one request line. Verbs are case-insensitive and call numbers are
+    /// upper-cased, so `call a12 3` is accepted as `CALL A12 3`.
+    pub fn parse(line: &str) -> Result<Self, KeypadParseError> {
+        let mut words = line.split_whitespace();
+        let verb = words
+            .next()
+            .ok_or(KeypadParseError::Empty)?
+            .to_ascii_uppercase();
+        let args: Vec<&str> = words.collect();
+
+        let command = match (verb.as_str(), args.as_slice()) {
+            ("CALL", [original_id, location]) => KeypadCommand::Call {
+                original_id: original_id.to_ascii_uppercase(),
+                location: location.to_string(),
+            },
+            ("CALL", _) => return Err(KeypadParseError::Usage("CALL <number> <counter>")),
+            ("RECALL", []) => KeypadCommand::Recall,
+            ("SKIP", []) => KeypadCommand::Skip,
+            ("COMPLETE", []) => KeypadCommand::Complete,
+            ("RECALL" | "SKIP" | "COMPLETE", _) => {
+                return Err(KeypadParseError::Usage("RECALL | SKIP | COMPLETE"))
+            }
+            ("NEXT", [location]) => KeypadCommand::Next {
+                location: location.to_string(),
+            },
+            ("NEXT", _) => return Err(KeypadParseError::Usage("NEXT <counter>")),
+            ("TRANSLATOR", [location]) => KeypadCommand::Translator {
+                location: location.to_string(),
+            },
+            ("TRANSLATOR", _) => return Err(KeypadParseError::Usage("TRANSLATOR <counter>")),
+            ("ROOM", [room_id]) => KeypadCommand::Room {
+                room_id: room_id.to_string(),
+            },
+            ("ROOM", _) => return Err(KeypadParseError::Usage("ROOM <id>")),
+            _ => return Err(KeypadParseError::UnknownCommand(verb)),
+        };
+        Ok(command)
+    }
+}
+
+/// Formats a successful reply line (without terminator).
+pub fn ok_line(message: &str) -> String {
+    format!("OK {}", single_line(message))
+}
+
+/// Formats an error reply line (without terminator).
+pub fn err_line(message: &str) -> String {
+    format!("ERR {}", single_line(message))
+}
+
+/// Formats the pushed state line for the current call (without terminator).
+pub fn update_line(current_call: Option<&Call>) -> String {
+    match current_call {
+        Some(call) => format!("UPDATE {} {}", call.original_id, call.location),
+        None => "UPDATE - -".to_string(),
+    }
+}
+
+fn single_line(message: &str) -> String {
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The most recently called number: the current call, otherwise the newest
+/// entry of the completed or skipped history.
+pub fn last_called(state: &QueueState) -> Option<&Call> {
+    state.current_call.as_ref().or_else(|| {
+        match (state.completed_history.back(), state.skipped_history.back()) {
+            (Some(completed), Some(skipped)) => Some(if skipped.timestamp > completed.timestamp {
+                skipped
+            } else {
+                completed
+            }),
+            (completed, skipped) => completed.or(skipped),
+        }
+    })
+}
+
+/// The number following `original_id`, keeping its letter prefix and zero
+/// padding (`A12` → `A13`, `B09` → `B10`, `7` → `8`).
+pub fn next_identifier(original_id: &str) -> Option<String> {
+    let digits_start = original_id
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(original_id.len());
+    let (prefix, digits) = original_id.split_at(digits_start);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let next = digits.parse::<u64>().ok()?.checked_add(1)?;
+    Some(format!("{}{:0width$}", prefix, next, width = digits.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_lines() {
+        assert_eq!(
+            KeypadCommand::parse("call a12 3"),
+            Ok(KeypadCommand::Call {
+                original_id: "A12".to_string(),
+                location: "3".to_string(),
+            })
+        );
+        assert_eq!(KeypadCommand::parse(" SKIP \r"), Ok(KeypadCommand::Skip));
+        assert_eq!(
+            KeypadCommand::parse("NEXT 4"),
+            Ok(KeypadCommand::Next {
+                location: "4".to_string()
+            })
+        );
+        assert_eq!(
+            KeypadCommand::parse("CALL A12"),
+            Err(KeypadParseError::Usage("CALL <number> <counter>"))
+        );
+        assert_eq!(
+            KeypadCommand::parse("DANCE"),
+            Err(KeypadParseError::UnknownCommand("DANCE".to_string()))
+        );
+        assert_eq!(KeypadCommand::parse("   "), Err(KeypadParseError::Empty));
+        assert_eq!(err_line("No current\ncall"), "ERR No current call");
+    }
+
+    #[test]
+    fn computes_next_identifier() {
+        assert_eq!(next_identifier("A12").as_deref(), Some("A13"));
+        assert_eq!(next_identifier("B09").as_deref(), Some("B10"));
+        assert_eq!(next_identifier("A99").as_deref(), Some("A100"));
+        assert_eq!(next_identifier("7").as_deref(), Some("8"));
+        assert_eq!(next_identifier("A"), None);
+        assert_eq!(next_identifier("A1B"), None);
+    }
+}
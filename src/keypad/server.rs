@@ -0,0 +1,265 @@
+// src/keypad/server.rs
+
+//! TCP listener and per-connection sessions for the keypad protocol.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::{
+    err_line, last_called, next_identifier, ok_line, update_line, KeypadCommand, MAX_LINE_LENGTH,
+};
+use crate::api::queue::validate_location;
+use crate::application::QueueCommand;
+use crate::state::{AppState, RoomState};
+use crate::AppEvent;
+
+/// Bind the keypad listener and serve connections in the background.
+///
+/// Sessions start in the default room and can switch with `ROOM <id>`.
+pub async fn start(state: Arc<AppState>, address: SocketAddr) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    info!("Keypad TCP listener bound to {}", address);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    info!("Keypad connected from {}.", peer);
+                    let state = Arc::clone(&state);
+                    tokio::spawn(async move {
+                        run_session(stream, state).await;
+                        info!("Keypad {} disconnected.", peer);
+                    });
+                }
+                Err(err) => warn!("Keypad: failed to accept connection: {}", err),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// A request line read from the socket.
+enum Incoming {
+    Line(String),
+    TooLong,
+}
+
+async fn run_session(stream: TcpStream, state: Arc<AppState>) {
+    let (read_half, mut writer) = stream.into_split();
+    // Lines are read in their own task because `read_until` is not cancel-safe
+    // inside `select!`.
+    let (line_sender, mut lines) = mpsc::channel(8);
+    tokio::spawn(read_lines(read_half, line_sender));
+
+    let mut room = state.default_room();
+    let mut events = room.event_bus_sender.subscribe();
+    let mut last_update = update_line(room.queue.snapshot().await.current_call.as_ref());
+    if !send_line(&mut writer, &last_update).await {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            incoming = lines.recv() => {
+                let line = match incoming {
+                    Some(Incoming::Line(line)) => line,
+                    Some(Incoming::TooLong) => {
+                        warn!("Keypad: rejecting request longer than {} bytes.", MAX_LINE_LENGTH);
+                        let reply = err_line(&format!("Line longer than {} bytes", MAX_LINE_LENGTH));
+                        if !send_line(&mut writer, &reply).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    None => break,
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let reply = match KeypadCommand::parse(&line) {
+                    Ok(KeypadCommand::Room { room_id }) => match state.room(&room_id) {
+                        Some(next_room) => {
+                            room = next_room;
+                            events = room.event_bus_sender.subscribe();
+                            last_update = String::new();
+                            ok_line(&format!("Room {} selected.", room.id))
+                        }
+                        None => err_line(&format!("Unknown room '{}'.", room_id)),
+                    },
+                    Ok(command) => {
+                        debug!("Keypad: executing '{}' in room '{}'.", line.trim(), room.id);
+                        match execute(&room, command).await {
+                            Ok(message) => ok_line(&message),
+                            Err(message) => {
+                                warn!("Keypad: '{}' failed: {}", line.trim(), message);
+                                err_line(&message)
+                            }
+                        }
+                    }
+                    Err(err) => err_line(&err.to_string()),
+                };
+                if !send_line(&mut writer, &reply).await {
+                    break;
+                }
+
+                // After a room switch, push that room's state right away.
+                if last_update.is_empty() {
+                    last_update = update_line(room.queue.snapshot().await.current_call.as_ref());
+                    if !send_line(&mut writer, &last_update).await {
+                        break;
+                    }
+                }
+            }
+            event = events.recv() => {
+                match event {
+                    Ok(AppEvent::QueueUpdate(queue_state)) => {
+                        let update = update_line(queue_state.current_call.as_ref());
+                        if update == last_update {
+                            continue;
+                        }
+                        last_update = update;
+                        if !send_line(&mut writer, &last_update).await {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => warn!(
+                        "Keypad: session lagged, skipped {} events. Consider increasing SSE_EVENT_BUFFER_SIZE.",
+                        skipped
+                    ),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+}
+
+async fn read_lines(read_half: OwnedReadHalf, lines: mpsc::Sender<Incoming>) {
+    let mut reader = BufReader::new(read_half);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        let limit = MAX_LINE_LENGTH as u64 + 2;
+        match (&mut reader)
+            .take(limit)
+            .read_until(b'\n', &mut buffer)
+            .await
+        {
+            Ok(0) => break,
+            Ok(_) if buffer.ends_with(b"\n") => {
+                let line = String::from_utf8_lossy(&buffer).into_owned();
+                if lines.send(Incoming::Line(line)).await.is_err() {
+                    break;
+                }
+            }
+            Ok(read) if (read as u64) < limit => {
+                // Connection closed in the middle of a line.
+                let line = String::from_utf8_lossy(&buffer).into_owned();
+                let _ = lines.send(Incoming::Line(line)).await;
+                break;
+            }
+            Ok(_) => {
+                if !discard_rest_of_line(&mut reader).await
+                    || lines.send(Incoming::TooLong).await.is_err()
+                {
+                    break;
+                }
+            }
+            Err(err) => {
+                debug!("Keypad: read error, closing connection: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+/// Skip input up to and including the next newline. Returns `false` on EOF or error.
+async fn discard_rest_of_line(reader: &mut BufReader<OwnedReadHalf>) -> bool {
+    loop {
+        let (consumed, found_newline) = match reader.fill_buf().await {
+            Ok([]) | Err(_) => return false,
+            Ok(available) => match available.iter().position(|b| *b == b'\n') {
+                Some(index) => (index + 1, true),
+                None => (available.len(), false),
+            },
+        };
+        reader.consume(consumed);
+        if found_newline {
+            return true;
+        }
+    }
+}
+
+/// Writes one line terminated by CRLF. Returns `false` when the connection is gone.
+async fn send_line(writer: &mut OwnedWriteHalf, line: &str) -> bool {
+    match writer.write_all(format!("{}\r\n", line).as_bytes()).await {
+        Ok(()) => true,
+        Err(err) => {
+            debug!("Keypad: write failed, closing connection: {}", err);
+            false
+        }
+    }
+}
+
+async fn execute(room: &RoomState, command: KeypadCommand) -> Result<String, String> {
+    let command = match command {
+        KeypadCommand::Call {
+            original_id,
+            location,
+        } => QueueCommand::Add {
+            original_id,
+            location,
+        },
+        KeypadCommand::Recall => QueueCommand::Recall,
+        KeypadCommand::Skip => QueueCommand::Skip,
+        KeypadCommand::Complete => QueueCommand::Complete,
+        KeypadCommand::Next { location } => return call_next(room, location).await,
+        KeypadCommand::Translator { location } => QueueCommand::TranslatorCall { location },
+        // Room switches change session state and are handled by `run_session`.
+        KeypadCommand::Room { .. } => return Err("ROOM cannot be executed here.".to_string()),
+    };
+    room.commands
+        .execute(command)
+        .await
+        .map(|outcome| outcome.message)
+}
+
+/// Calls the next waiting ticket at `location`, else the number after the
+/// last call. A ticket whose call fails goes back to the waiting list.
+async fn call_next(room: &RoomState, location: String) -> Result<String, String> {
+    validate_location(&location)?;
+    let ticket = room.tickets.call_next(None, &location);
+    let original_id = match &ticket {
+        Some(number) => number.clone(),
+        None => {
+            let snapshot = room.queue.snapshot().await;
+            let previous = last_called(&snapshot)
+                .ok_or_else(|| "No previous call to continue from. Use CALL.".to_string())?;
+            next_identifier(&previous.original_id).ok_or_else(|| {
+                format!(
+                    "Cannot determine the number after {}.",
+                    previous.original_id
+                )
+            })?
+        }
+    };
+    let result = room
+        .commands
+        .execute(QueueCommand::Add {
+            original_id,
+            location,
+        })
+        .await
+        .map(|outcome| outcome.message);
+    if let (Err(_), Some(number)) = (&result, &ticket) {
+        room.tickets.return_to_waiting(number);
+    }
+    result
+}
@@ -17,12 +17,26 @@
 pub mod announcements;
 pub mod api;
 pub mod application;
+pub mod appointments;
+pub mod cli;
 pub mod config;
+pub mod ctl;
+pub mod diagnostics;
+pub mod displays;
+pub mod event_log;
 pub mod health;
+pub mod keypad;
+pub mod mqtt;
+pub mod notifications;
 pub mod queue;
+pub mod settings;
+pub mod shutdown;
 pub mod sse;
 pub mod state;
+pub mod storage;
+pub mod tickets;
 pub mod tts;
+pub mod webhooks;
 
 use announcements::manager::AnnouncementStatus;
 use chrono::Utc;
@@ -48,6 +62,10 @@ pub struct Call {
     pub location: String,
     /// The timestamp when this call was created or added to the queue, in UTC.
     pub timestamp: chrono::DateTime<Utc>,
+    /// Why the call was skipped when it was not skipped by staff, e.g. `no_show`
+    /// for calls skipped by the no-show timer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
 }
 
 /// Defines the types of events that can be broadcast throughout the application
@@ -75,6 +93,9 @@ pub enum AppEvent {
         cooldown_seconds: u64,
         /// Remaining cooldown duration in seconds at the time of broadcast.
         cooldown_remaining_seconds: u64,
+        /// Display zone of the counter, when zones are configured.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        zone: Option<String>,
     },
     /// An event signaling that a Text-to-Speech audio generation is complete.
     /// Includes details about the generated audio file(s).
@@ -89,16 +110,119 @@ pub enum AppEvent {
         /// For online TTS, this will contain a single URL.
         /// For stem audio fallback, this will contain multiple URLs in playback order.
         audio_urls: Vec<String>,
+        /// Display zone of the counter, when zones are configured.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        zone: Option<String>,
     },
+    /// An event raised when a registered signage display has been disconnected
+    /// for longer than the configured alert threshold.
+    DisplayAlert {
+        /// Identifier the display used when it connected.
+        display_id: String,
+        /// When the display's last event stream closed.
+        disconnected_at: chrono::DateTime<Utc>,
+        /// How long the display had been offline when the alert was raised.
+        offline_seconds: u64,
+    },
+    /// A remote control command for one display, a zone or every display.
+    /// Displays acknowledge it via `POST /api/displays/commands/:command_id/ack`.
+    DisplayCommand {
+        /// Identifier the display must quote when acknowledging.
+        command_id: String,
+        /// Which displays should act on the command.
+        target: crate::displays::DisplayTarget,
+        /// The action to perform.
+        command: crate::displays::DisplayCommand,
+        /// When the command was issued.
+        issued_at: chrono::DateTime<Utc>,
+    },
+    /// A supervisor changed the room's adjustable settings, or a configuration
+    /// reload changed them. Carries the settings now in effect.
+    SettingsChanged(crate::settings::Settings),
+    /// The server is shutting down; clients should show that they are
+    /// reconnecting. Delivered to every subscriber regardless of filters, after
+    /// which event streams end.
+    ServerShutdown,
+}
+
+impl AppEvent {
+    /// Every event name that can appear on the wire, in declaration order.
+    /// Used to validate client-supplied subscription filters.
+    pub const EVENT_NAMES: &'static [&'static str] = &[
+        "queue_update",
+        "announcement_status",
+        "translator_call",
+        "tts_complete",
+        "display_alert",
+        "display_command",
+        "settings_changed",
+        "server_shutdown",
+    ];
+
+    /// Returns the snake_case name used as the SSE `event:` field for this event.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            AppEvent::QueueUpdate(_) => "queue_update",
+            AppEvent::AnnouncementStatus(_) => "announcement_status",
+            AppEvent::TranslatorCall { .. } => "translator_call",
+            AppEvent::TTSComplete { .. } => "tts_complete",
+            AppEvent::DisplayAlert { .. } => "display_alert",
+            AppEvent::DisplayCommand { .. } => "display_command",
+            AppEvent::SettingsChanged(_) => "settings_changed",
+            AppEvent::ServerShutdown => "server_shutdown",
+        }
+    }
+
+    /// Returns the counter location this event concerns, if any.
+    ///
+    /// Queue updates report the location of the current call; when no call is
+    /// active (or for announcement updates) the event is not tied to a counter.
+    /// Event filters still deliver every queue update, since each one is a
+    /// snapshot of the whole queue.
+    pub fn location(&self) -> Option<&str> {
+        match self {
+            AppEvent::QueueUpdate(state) => {
+                state.current_call.as_ref().map(|c| c.location.as_str())
+            }
+            AppEvent::AnnouncementStatus(_) => None,
+            AppEvent::TranslatorCall { location, .. } => Some(location.as_str()),
+            AppEvent::TTSComplete { location, .. } => Some(location.as_str()),
+            AppEvent::DisplayAlert { .. }
+            | AppEvent::DisplayCommand { .. }
+            | AppEvent::SettingsChanged(_)
+            | AppEvent::ServerShutdown => None,
+        }
+    }
+
+    /// Returns the display zone this event concerns, if any.
+    ///
+    /// Only call-related events carry a zone, and only when `DISPLAY_ZONES`
+    /// assigns their counter to one.
+    pub fn zone(&self) -> Option<&str> {
+        match self {
+            AppEvent::QueueUpdate(state) => state.current_zone.as_deref(),
+            AppEvent::TranslatorCall { zone, .. } | AppEvent::TTSComplete { zone, .. } => {
+                zone.as_deref()
+            }
+            AppEvent::AnnouncementStatus(_)
+            | AppEvent::DisplayAlert { .. }
+            | AppEvent::DisplayCommand { .. }
+            | AppEvent::SettingsChanged(_)
+            | AppEvent::ServerShutdown => None,
+        }
+    }
 }
 
 // Re-export key structs for easier access when using the crate.
 // This allows users to import `AppConfig` directly from `queue_calling_system`
 // instead of `queue_calling_system::config::AppConfig`.
-pub use application::{AnnouncementService, QueueService, TranslatorService, TtsService};
+pub use application::{
+    AnnouncementService, AppointmentService, DisplayService, HistoryService, QueueService,
+    SettingsService, TicketService, TranslatorService, TtsService, WebhookService,
+};
 pub use config::AppConfig;
 pub use queue::QueueState;
-pub use state::AppState;
+pub use state::{AppState, RoomState};
 
 /// Initializes the `tracing` logging subscriber for the application.
 ///
@@ -8,13 +8,18 @@
 //! and launches the Axum server.
 
 use axum::{routing::get, serve, Router};
-use queue_calling_system::config::AppConfig;
+use queue_calling_system::cli::{Cli, Command, EXIT_USAGE};
+use queue_calling_system::config::{AppConfig, ConfigError};
 use queue_calling_system::setup_logging;
 use queue_calling_system::state::AppState;
-use queue_calling_system::{api, health};
+use queue_calling_system::SettingsService;
+use queue_calling_system::{api, health, keypad, shutdown};
 use std::fs;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::time::Instant;
 use tower_http::services::ServeDir;
 
 type AppResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
@@ -22,8 +27,12 @@ type AppResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 /// The asynchronous main function that bootstraps and runs the Axum web server.
 ///
 /// This function performs the following steps:
+/// 0. Parses the command line; subcommands other than `serve` (see [`Cli`]) run
+///    and exit without starting the server.
 /// 1. Initializes the `tracing` logging system.
-/// 2. Loads the application configuration from environment variables or a configuration file.
+/// 2. Loads the application configuration from environment variables and the optional
+///    TOML file given by `--config` or `QUEUE_CONFIG_FILE`, exiting with a message
+///    naming the offending setting if it is invalid.
 /// 3. Ensures the necessary directories for GTTS cache and custom announcements exist,
 ///    creating them if they don't. The application will panic if these directories
 ///    cannot be created as they are essential for operation.
@@ -31,19 +40,52 @@ type AppResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 ///    like the queue management system.
 /// 5. Configures Axum to serve API routes, the GTTS cache files, and static public files.
 /// 6. Launches the Axum server, binding to the configured address and port.
+/// 7. On SIGTERM or Ctrl-C, stops accepting connections, broadcasts a
+///    `ServerShutdown` event and waits up to `SHUTDOWN_TIMEOUT_SECONDS` for open
+///    connections and in-flight TTS and history writes before returning.
 ///
 /// # Returns
 /// - `Ok(())` if the server starts and runs successfully.
 /// - `Err` if there's an error while starting the Axum server.
 #[tokio::main]
 async fn main() -> AppResult<()> {
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+    // Other commands print a report and exit; their output is not mixed with logs.
+    if cli.command != Command::Serve {
+        std::process::exit(cli.run());
+    }
+
     // Initialize the tracing subscriber. This should be done as early as possible.
     setup_logging();
     tracing::info!("Starting Queue Calling System backend...");
 
     // Load application configuration from environment variables or a configuration file.
-    let config = AppConfig::load();
+    let config_source = cli.source;
+    let loaded = AppConfig::load(&config_source).and_then(|config| {
+        let room_configs = config.load_rooms(&config_source)?;
+        Ok::<_, ConfigError>((config, room_configs))
+    });
+    let (config, room_configs) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::error!("CRITICAL: Failed to load application configuration: {}", e);
+            std::process::exit(2);
+        }
+    };
     tracing::info!("Configuration loaded: {:?}", config);
+    let settings = match SettingsService::open(&config) {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("CRITICAL: Failed to load settings overrides: {}", e);
+            std::process::exit(2);
+        }
+    };
     tracing::debug!("Loaded config details: {:?}", config); // Use debug for detailed config output
 
     // --- Ensure required directories exist ---
@@ -112,15 +154,49 @@ async fn main() -> AppResult<()> {
             banner_path
         );
     }
+    // 4. Announcement directories of additional rooms. Rooms without announcements
+    // simply have an empty rotation, so failures here are not fatal.
+    for (room_id, room_config) in &room_configs {
+        let room_announcements_path = room_config.announcement_audio_base_path();
+        if let Err(e) = fs::create_dir_all(&room_announcements_path) {
+            tracing::warn!(
+                "Failed to ensure announcements directory for room '{}' exists at {:?}: {}",
+                room_id,
+                room_announcements_path,
+                e
+            );
+        }
+    }
     tracing::info!("Directory setup complete.");
     // --- End of directory creation ---
 
     // Initialize the shared application state.
     // This state will be managed by Axum and made available to route handlers.
-    let app_state = Arc::new(AppState::new(config.clone()).await);
+    let app_state =
+        Arc::new(AppState::new(config_source, settings, config.clone(), room_configs).await);
     tracing::info!("Application state initialized.");
     tracing::debug!("AppState created with config: {:?}", config);
 
+    // Reload the configuration on SIGHUP (e.g. `systemctl reload queue-app`).
+    #[cfg(unix)]
+    {
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        let reload_state = app_state.clone();
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                tracing::info!("Received SIGHUP; reloading configuration.");
+                reload_state.reload_config().await;
+            }
+        });
+    }
+
+    // Optional TCP listener for hardware caller keypads.
+    if let Some(keypad_address) = config.keypad_socket_addr() {
+        keypad::server::start(app_state.clone(), keypad_address).await?;
+    } else {
+        tracing::info!("Keypad TCP listener disabled (KEYPAD_TCP_PORT is 0).");
+    }
+
     // Prepare static services served by Axum.
 
     // Path for serving cached TTS audio files.
@@ -159,12 +235,67 @@ async fn main() -> AppResult<()> {
 
     let app = Router::new()
         .route("/health", get(health::check))
+        .route("/ready", get(health::ready).with_state(app_state.clone()))
         .nest("/api", api_router)
         .nest_service(tts_cache_web_mount_point.as_str(), tts_cache_service)
         .nest_service("/", public_files_service);
 
     tracing::info!("Launching Axum server...");
     let listener = TcpListener::bind(server_address).await?;
-    serve(listener, app.into_make_service()).await?;
+
+    // Set when a shutdown signal arrives: the time by which everything must be done.
+    let (deadline_sender, deadline_receiver) = watch::channel(None::<Instant>);
+    let shutdown_state = app_state.clone();
+    let server = serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown::signal().await;
+        let timeout = shutdown_state.config.get().shutdown_timeout();
+        let _ = deadline_sender.send(Some(Instant::now() + timeout));
+        shutdown_state.announce_shutdown();
+        tracing::info!(
+            "Stopped accepting connections; waiting up to {:?} for clients and background work.",
+            timeout
+        );
+    });
+    let mut deadline_watch = deadline_receiver.clone();
+    let deadline = async move {
+        let deadline = match deadline_watch.wait_for(Option::is_some).await {
+            Ok(deadline) => deadline.expect("deadline is set"),
+            // The sender is gone only once the server has stopped by itself.
+            Err(_) => std::future::pending().await,
+        };
+        tokio::time::sleep_until(deadline).await;
+    };
+    tokio::pin!(deadline);
+
+    tokio::select! {
+        result = server => result?,
+        _ = &mut deadline => {
+            tracing::warn!("Connections still open at the shutdown deadline; closing them.");
+        }
+    }
+
+    let deadline = deadline_receiver.borrow().unwrap_or_else(Instant::now);
+    let pending = app_state.in_flight.pending();
+    if pending > 0 {
+        tracing::info!(
+            "Waiting for {} TTS or history write(s) to finish...",
+            pending
+        );
+    }
+    if !app_state
+        .in_flight
+        .drained(deadline.saturating_duration_since(Instant::now()))
+        .await
+    {
+        tracing::warn!(
+            "Shutdown deadline reached with {} TTS or history write(s) unfinished.",
+            app_state.in_flight.pending()
+        );
+    }
+    tracing::info!("Shutdown complete.");
     Ok(())
 }
@@ -0,0 +1,239 @@
+// src/mqtt/bridge.rs
+
+//! Broker connection of the MQTT bridge (requires the `mqtt` cargo feature).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use super::{
+    command_reply_topic, command_topic, event_topic, is_retained, parse_broker_url, MqttCommand,
+    MqttCommandReply, MqttCommandRequest,
+};
+use crate::application::{CommandService, QueueService};
+use crate::config::AppConfig;
+use crate::event_log::EventBusStats;
+use crate::AppEvent;
+
+type BroadcastSender = tokio::sync::broadcast::Sender<crate::AppEvent>;
+
+/// Pending outgoing packets buffered between the bridge and the connection task.
+const MQTT_CHANNEL_CAPACITY: usize = 64;
+/// Delay before reconnecting after the broker connection fails.
+const MQTT_RECONNECT_DELAY_SECONDS: u64 = 5;
+const MQTT_KEEP_ALIVE_SECONDS: u64 = 30;
+
+/// Connect a room to the configured broker and start publishing its events.
+///
+/// Does nothing when `MQTT_BROKER_URL` is empty; an invalid URL is logged and
+/// leaves the bridge disabled.
+pub fn start(
+    config: Arc<AppConfig>,
+    room_id: &str,
+    event_bus: &BroadcastSender,
+    stats: &EventBusStats,
+    queue: QueueService,
+    commands: CommandService,
+) {
+    if config.mqtt_broker_url.trim().is_empty() {
+        debug!("MQTT bridge disabled for room '{}'.", room_id);
+        return;
+    }
+    let broker = match parse_broker_url(&config.mqtt_broker_url) {
+        Ok(broker) => broker,
+        Err(err) => {
+            error!("{}. MQTT bridge disabled for room '{}'.", err, room_id);
+            return;
+        }
+    };
+
+    let mut options = MqttOptions::new(
+        format!("queue-calling-system-{}", room_id),
+        broker.host.clone(),
+        broker.port,
+    );
+    options.set_keep_alive(Duration::from_secs(MQTT_KEEP_ALIVE_SECONDS));
+    if let Some(username) = &broker.username {
+        options.set_credentials(
+            username.clone(),
+            broker.password.clone().unwrap_or_default(),
+        );
+    }
+    let (client, mut event_loop) = AsyncClient::new(options, MQTT_CHANNEL_CAPACITY);
+
+    let prefix = config.mqtt_topic_prefix.trim_matches('/').to_string();
+    info!(
+        "MQTT bridge for room '{}' publishing to {}:{} under '{}/{}/'.",
+        room_id, broker.host, broker.port, prefix, room_id
+    );
+
+    let bridge = Bridge {
+        client,
+        prefix,
+        room_id: room_id.to_string(),
+        commands_enabled: config.mqtt_commands_enabled,
+        queue,
+        commands,
+    };
+
+    let publisher = bridge.clone();
+    let mut events = event_bus.subscribe();
+    let stats = stats.clone();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => publisher.publish_event(&event),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "MQTT: publisher lagged and lost {} events. Consider increasing SSE_EVENT_BUFFER_SIZE.",
+                        skipped
+                    );
+                    stats.record_lag("mqtt", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+        debug!("MQTT: event bus closed, publisher stopped.");
+    });
+
+    // The connection task must keep polling for anything to be sent, so work
+    // that publishes is handed off to separate tasks.
+    tokio::spawn(async move {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!("MQTT: connected to broker for room '{}'.", bridge.room_id);
+                    let bridge = bridge.clone();
+                    tokio::spawn(async move { bridge.on_connected().await });
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let bridge = bridge.clone();
+                    tokio::spawn(async move {
+                        bridge
+                            .handle_command(&publish.topic, &publish.payload)
+                            .await
+                    });
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    warn!(
+                        "MQTT: connection error for room '{}': {}. Reconnecting in {}s.",
+                        bridge.room_id, err, MQTT_RECONNECT_DELAY_SECONDS
+                    );
+                    tokio::time::sleep(Duration::from_secs(MQTT_RECONNECT_DELAY_SECONDS)).await;
+                }
+            }
+        }
+    });
+}
+
+#[derive(Clone)]
+struct Bridge {
+    client: AsyncClient,
+    prefix: String,
+    room_id: String,
+    commands_enabled: bool,
+    queue: QueueService,
+    commands: CommandService,
+}
+
+impl Bridge {
+    /// (Re)subscribe to the command topic and refresh the retained queue state.
+    async fn on_connected(&self) {
+        if self.commands_enabled {
+            let topic = command_topic(&self.prefix, &self.room_id);
+            match self.client.subscribe(topic.clone(), QoS::AtLeastOnce).await {
+                Ok(()) => info!("MQTT: listening for commands on '{}'.", topic),
+                Err(err) => error!("MQTT: failed to subscribe to '{}': {}", topic, err),
+            }
+        }
+        let state = self.queue.snapshot().await;
+        self.publish_event(&AppEvent::QueueUpdate(state));
+    }
+
+    fn publish_event(&self, event: &AppEvent) {
+        let payload = match serde_json::to_vec(event) {
+            Ok(payload) => payload,
+            Err(err) => {
+                error!("MQTT: failed to serialise event: {}", err);
+                return;
+            }
+        };
+        let topic = event_topic(&self.prefix, &self.room_id, event);
+        // `try_publish` so a broker outage drops events instead of stalling the
+        // publisher; retained state is refreshed on reconnect.
+        if let Err(err) =
+            self.client
+                .try_publish(topic.clone(), QoS::AtLeastOnce, is_retained(event), payload)
+        {
+            warn!("MQTT: failed to publish to '{}': {}", topic, err);
+        }
+    }
+
+    async fn handle_command(&self, topic: &str, payload: &[u8]) {
+        if topic != command_topic(&self.prefix, &self.room_id) {
+            debug!("MQTT: ignoring message on unexpected topic '{}'.", topic);
+            return;
+        }
+
+        let reply = match serde_json::from_slice::<MqttCommandRequest>(payload) {
+            Ok(request) => {
+                let command = request.command.name();
+                debug!(
+                    "MQTT: executing '{}' command (id: {:?}).",
+                    command, request.id
+                );
+                match self.execute(request.command).await {
+                    Ok(message) => MqttCommandReply {
+                        id: request.id,
+                        command: Some(command),
+                        ok: true,
+                        message,
+                    },
+                    Err(message) => {
+                        warn!("MQTT: '{}' command failed: {}", command, message);
+                        MqttCommandReply {
+                            id: request.id,
+                            command: Some(command),
+                            ok: false,
+                            message,
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                warn!("MQTT: rejecting malformed command: {}", err);
+                MqttCommandReply {
+                    id: None,
+                    command: None,
+                    ok: false,
+                    message: format!("Malformed command: {}", err),
+                }
+            }
+        };
+
+        let topic = command_reply_topic(&self.prefix, &self.room_id);
+        match serde_json::to_vec(&reply) {
+            Ok(payload) => {
+                if let Err(err) = self
+                    .client
+                    .publish(topic.clone(), QoS::AtLeastOnce, false, payload)
+                    .await
+                {
+                    warn!("MQTT: failed to publish reply to '{}': {}", topic, err);
+                }
+            }
+            Err(err) => error!("MQTT: failed to serialise command reply: {}", err),
+        }
+    }
+
+    async fn execute(&self, command: MqttCommand) -> Result<String, String> {
+        self.commands
+            .execute(command.into())
+            .await
+            .map(|outcome| outcome.message)
+    }
+}
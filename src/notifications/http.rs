@@ -0,0 +1,51 @@
+// src/notifications/http.rs
+
+//! Notifier posting JSON to a generic HTTP endpoint, e.g. an SMS or chat gateway.
+
+use std::time::Duration;
+
+use axum::async_trait;
+use reqwest::Client as ReqwestClient;
+
+use super::{Notification, Notifier, NotifyError};
+
+/// Upper bound for one notification request.
+pub const HTTP_NOTIFIER_TIMEOUT_SECONDS: u64 = 10;
+
+/// POSTs each notification as JSON. Any 2xx response counts as delivered.
+pub struct HttpNotifier {
+    url: url::Url,
+    client: ReqwestClient,
+}
+
+impl HttpNotifier {
+    pub fn new(url: url::Url) -> Self {
+        Self {
+            url,
+            client: ReqwestClient::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for HttpNotifier {
+    fn describe(&self) -> String {
+        self.url.to_string()
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let response = self
+            .client
+            .post(self.url.clone())
+            .timeout(Duration::from_secs(HTTP_NOTIFIER_TIMEOUT_SECONDS))
+            .json(notification)
+            .send()
+            .await
+            .map_err(|err| NotifyError::Http(err.to_string()))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(NotifyError::Http(format!("HTTP {}", response.status())))
+        }
+    }
+}
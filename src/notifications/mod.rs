@@ -0,0 +1,188 @@
+// src/notifications/mod.rs
+
+//! Notifications sent to patients whose ticket is about to be called.
+//!
+//! Contact details (a phone number, chat id or push token) can be attached when a
+//! ticket is issued. The ticket service then asks a [`Notifier`] to reach the
+//! patient once when few people are left ahead of them and again when they are
+//! called. Which notifier is used comes from `TICKET_NOTIFIER`.
+
+pub mod http;
+pub mod sink;
+
+pub use http::HttpNotifier;
+pub use sink::{FileNotifier, LogNotifier};
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Why a patient is notified.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Few enough people are ahead that the patient should come back.
+    Approaching,
+    /// The ticket has been called to a counter.
+    Called,
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationKind::Approaching => write!(f, "approaching"),
+            NotificationKind::Called => write!(f, "called"),
+        }
+    }
+}
+
+/// Message handed to a notifier.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub room: String,
+    /// Ticket number, e.g. "A12".
+    pub ticket: String,
+    pub service_name: String,
+    /// Contact details given when the ticket was issued.
+    pub contact: String,
+    pub people_ahead: usize,
+    /// Counter the ticket was called to (`called` notifications only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Delivery state of one notification.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+/// Delivery record kept on the ticket.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct NotificationRecord {
+    pub kind: NotificationKind,
+    pub status: NotificationStatus,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Errors raised while configuring or using a notifier.
+#[derive(Debug)]
+pub enum NotifyError {
+    /// `TICKET_NOTIFIER` is not a recognised destination.
+    InvalidTarget(String),
+    /// The notification could not be serialised.
+    Serialize(serde_json::Error),
+    /// The sink file could not be written.
+    Io(std::io::Error),
+    /// The HTTP endpoint could not be reached or rejected the notification.
+    Http(String),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::InvalidTarget(raw) => write!(
+                f,
+                "Invalid ticket notifier '{}'. Use an http(s):// URL, file:/path or log",
+                raw
+            ),
+            NotifyError::Serialize(err) => write!(f, "Failed to serialise notification: {}", err),
+            NotifyError::Io(err) => write!(f, "Notification sink I/O error: {}", err),
+            NotifyError::Http(reason) => write!(f, "Notification endpoint error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+impl From<std::io::Error> for NotifyError {
+    fn from(err: std::io::Error) -> Self {
+        NotifyError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for NotifyError {
+    fn from(err: serde_json::Error) -> Self {
+        NotifyError::Serialize(err)
+    }
+}
+
+/// A channel that delivers notifications to patients.
+///
+/// Implementations only deliver; deciding when to notify and tracking the
+/// outcome is done by the ticket service.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Short description used in log messages, e.g. the endpoint URL.
+    fn describe(&self) -> String;
+
+    /// Delivers one notification.
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// Builds the notifier configured by `TICKET_NOTIFIER`. Returns `Ok(None)` when
+/// the value is empty.
+pub fn from_target(raw: &str) -> Result<Option<Arc<dyn Notifier>>, NotifyError> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    if raw.eq_ignore_ascii_case("log") {
+        return Ok(Some(Arc::new(LogNotifier)));
+    }
+    if let Some(path) = raw.strip_prefix("file:") {
+        if path.is_empty() {
+            return Err(NotifyError::InvalidTarget(raw.to_string()));
+        }
+        return Ok(Some(Arc::new(FileNotifier::new(PathBuf::from(path)))));
+    }
+    match url::Url::parse(raw) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            Ok(Some(Arc::new(HttpNotifier::new(url))))
+        }
+        _ => Err(NotifyError::InvalidTarget(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_notifier_targets() {
+        assert!(from_target("  ").unwrap().is_none());
+        assert_eq!(from_target("LOG").unwrap().unwrap().describe(), "log");
+        assert_eq!(
+            from_target("file:/tmp/notifications.jsonl")
+                .unwrap()
+                .unwrap()
+                .describe(),
+            "file:/tmp/notifications.jsonl"
+        );
+        assert_eq!(
+            from_target("https://sms.example/send")
+                .unwrap()
+                .unwrap()
+                .describe(),
+            "https://sms.example/send"
+        );
+        assert!(matches!(
+            from_target("ftp://sms.example"),
+            Err(NotifyError::InvalidTarget(_))
+        ));
+        assert!(matches!(
+            from_target("file:"),
+            Err(NotifyError::InvalidTarget(_))
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+// src/notifications/sink.rs
+
+//! Local notifiers for testing and for integrations that tail a file.
+
+use std::path::PathBuf;
+
+use axum::async_trait;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use super::{Notification, Notifier, NotifyError};
+
+/// Appends each notification as one JSON line to a file.
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    fn describe(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut line = serde_json::to_vec(notification)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Writes notifications to the server log.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    fn describe(&self) -> String {
+        "log".to_string()
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        info!(
+            "Notification ({}) for ticket {} to '{}': {}",
+            notification.kind,
+            notification.ticket,
+            notification.contact,
+            serde_json::to_string(notification)?
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::NotificationKind;
+
+    #[tokio::test]
+    async fn file_notifier_appends_json_lines() {
+        let path =
+            std::env::temp_dir().join(format!("queue-notifications-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let notifier = FileNotifier::new(path.clone());
+        let mut notification = Notification {
+            kind: NotificationKind::Approaching,
+            room: "default".to_string(),
+            ticket: "A3".to_string(),
+            service_name: "General".to_string(),
+            contact: "+66800000000".to_string(),
+            people_ahead: 2,
+            location: None,
+            created_at: chrono::Utc::now(),
+        };
+        notifier.notify(&notification).await.unwrap();
+        notification.kind = NotificationKind::Called;
+        notification.location = Some("4".to_string());
+        notifier.notify(&notification).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = written
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], "approaching");
+        assert!(lines[0].get("location").is_none());
+        assert_eq!(lines[1]["location"], "4");
+        let _ = std::fs::remove_file(&path);
+    }
+}
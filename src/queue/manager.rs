@@ -9,7 +9,7 @@
 //! - Moving calls between the current slot, completed history, and skipped history.
 //! - Formatting call identifiers consistently.
 
-use super::QueueState;
+use super::{HistoryStatus, QueueState};
 use crate::Call;
 use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
@@ -41,6 +41,9 @@ pub struct QueueManager {
     max_skipped_history_size: usize,
     /// Whether queue identifiers must follow the legacy letter+digits format.
     identifier_prefix_required: bool,
+    /// Calls moved into either history since the last [`QueueManager::take_archived`],
+    /// oldest first. Lets callers persist history beyond the in-memory limits.
+    archived: Vec<(HistoryStatus, Call)>,
 }
 
 impl QueueManager {
@@ -71,6 +74,7 @@ impl QueueManager {
             max_history_size,
             max_skipped_history_size,
             identifier_prefix_required,
+            archived: Vec::new(),
         }
     }
 
@@ -245,6 +249,7 @@ impl QueueManager {
                     original_id: original_id_param.clone(),
                     location: location_param.clone(),
                     timestamp: now,
+                    skip_reason: None,
                 },
                 // Case 2: Existing call found, update its details and timestamp.
                 |mut existing_call| {
@@ -254,6 +259,7 @@ impl QueueManager {
                     existing_call.original_id = original_id_param.clone(); // Update original_id
                     existing_call.location = location_param.clone();     // Update location
                     existing_call.timestamp = now; // Update timestamp to now
+                    existing_call.skip_reason = None;
                     existing_call
                 }
             );
@@ -267,6 +273,8 @@ impl QueueManager {
                     "QueueManager::add_call: Moving previous current_call '{}' to completed_history.",
                     previous_current_call.id
                 );
+                self.archived
+                    .push((HistoryStatus::Completed, previous_current_call.clone()));
                 self.completed_history.push_back(previous_current_call);
                 self.enforce_max_completed_history_size(); // Enforce history size limit
             } else {
@@ -294,6 +302,15 @@ impl QueueManager {
     /// - `Some(Call)` containing the `Call` that was skipped.
     /// - `None` if there was no `current_call` to skip.
     pub fn skip_current_call(&mut self) -> Option<Call> {
+        self.skip_current_call_with_reason(None)
+    }
+
+    /// Moves the `current_call` to the `skipped_history` like
+    /// [`QueueManager::skip_current_call`], recording why it was skipped.
+    ///
+    /// # Arguments
+    /// - `reason`: Stored as the skipped call's `skip_reason` (e.g. `no_show`).
+    pub fn skip_current_call_with_reason(&mut self, reason: Option<&str>) -> Option<Call> {
         info!(
             "QueueManager::skip_current_call: Attempting. Current: {:?}",
             self.current_call.as_ref().map(|c| &c.id)
@@ -307,6 +324,9 @@ impl QueueManager {
             self.remove_call_from_all_queues(&call_to_skip.id);
 
             call_to_skip.timestamp = SystemTime::now().into(); // Update timestamp to when it was skipped.
+            call_to_skip.skip_reason = reason.map(str::to_string);
+            self.archived
+                .push((HistoryStatus::Skipped, call_to_skip.clone()));
             self.skipped_history.push_back(call_to_skip.clone()); // Push a clone to history.
             self.enforce_max_skipped_history_size(); // Enforce history size limit.
             info!("QueueManager::skip_current_call: '{}' moved to skipped_history. Current call is now None.", call_to_skip.id);
@@ -340,6 +360,8 @@ impl QueueManager {
             self.remove_call_from_all_queues(&call_to_complete.id);
 
             call_to_complete.timestamp = SystemTime::now().into(); // Update timestamp to when it was completed.
+            self.archived
+                .push((HistoryStatus::Completed, call_to_complete.clone()));
             self.completed_history.push_back(call_to_complete.clone()); // Push a clone to history.
             self.enforce_max_completed_history_size(); // Enforce history size limit.
             info!("QueueManager::complete_current_call: '{}' moved to completed_history. Current call is now None.", call_to_complete.id);
@@ -382,6 +404,7 @@ impl QueueManager {
                     original_id: original_id_param.clone(),
                     location: location_param.clone(),
                     timestamp: now,
+                    skip_reason: None,
                 },
                 // Case 2: Existing call found, update its details and timestamp.
                 |mut existing_call| {
@@ -391,11 +414,14 @@ impl QueueManager {
                     existing_call.original_id = original_id_param.clone();
                     existing_call.location = location_param.clone();
                     existing_call.timestamp = now;
+                    existing_call.skip_reason = None;
                     existing_call
                 }
             );
 
         info!("QueueManager::add_to_skipped_directly: Adding call id='{}' directly to skipped_history.", call_data.id);
+        self.archived
+            .push((HistoryStatus::Skipped, call_data.clone()));
         self.skipped_history.push_back(call_data.clone()); // Add the call to the skipped history.
         self.enforce_max_skipped_history_size(); // Enforce history size limit.
 
@@ -438,13 +464,45 @@ impl QueueManager {
         &self.skipped_history
     }
 
+    /// Changes the history limits, dropping the oldest calls when a limit shrinks.
+    ///
+    /// # Returns
+    /// `true` when either history lost calls.
+    pub fn set_history_limits(
+        &mut self,
+        max_history_size: usize,
+        max_skipped_history_size: usize,
+    ) -> bool {
+        info!(
+            "QueueManager: history limits changed to max_history_size={}, max_skipped_history_size={}",
+            max_history_size, max_skipped_history_size
+        );
+        let before = self.completed_history.len() + self.skipped_history.len();
+        self.max_history_size = max_history_size;
+        self.max_skipped_history_size = max_skipped_history_size;
+        self.enforce_max_completed_history_size();
+        self.enforce_max_skipped_history_size();
+        self.completed_history.len() + self.skipped_history.len() != before
+    }
+
+    /// Returns and clears the calls moved into either history since the last call,
+    /// oldest first.
+    ///
+    /// Unlike the in-memory histories these are not trimmed, so a persistent
+    /// history store sees every completed and skipped call.
+    pub fn take_archived(&mut self) -> Vec<(HistoryStatus, Call)> {
+        std::mem::take(&mut self.archived)
+    }
+
     /// Create a clone of the current queue state for external consumers.
+    /// The manager does not know about display zones, so `current_zone` is left unset.
     pub fn snapshot(&self) -> QueueState {
         QueueState {
             identifier_prefix_required: self.identifier_prefix_required,
             current_call: self.current_call.clone(),
             completed_history: self.completed_history.clone(),
             skipped_history: self.skipped_history.clone(),
+            current_zone: None,
         }
     }
 
@@ -857,4 +915,57 @@ mod tests {
         let snapshot = manager.snapshot();
         assert!(!snapshot.identifier_prefix_required);
     }
+
+    /// Calls trimmed from the in-memory histories are still reported as archived.
+    #[test]
+    #[traced_test]
+    fn test_take_archived_reports_every_history_move() {
+        let mut manager = QueueManager::new(1, 1, true);
+        manager.add_call("A1".to_string(), "1".to_string());
+        manager.add_call("A2".to_string(), "1".to_string()); // A1 completed
+        manager.add_call("A3".to_string(), "1".to_string()); // A2 completed, A1 trimmed
+        manager.skip_current_call(); // A3 skipped
+        manager.add_to_skipped_directly("A4".to_string(), "2".to_string());
+
+        let archived: Vec<_> = manager
+            .take_archived()
+            .into_iter()
+            .map(|(status, call)| (status, call.original_id))
+            .collect();
+        assert_eq!(
+            archived,
+            vec![
+                (HistoryStatus::Completed, "A1".to_string()),
+                (HistoryStatus::Completed, "A2".to_string()),
+                (HistoryStatus::Skipped, "A3".to_string()),
+                (HistoryStatus::Skipped, "A4".to_string()),
+            ]
+        );
+        assert_eq!(manager.get_completed_history().len(), 1);
+        assert!(manager.take_archived().is_empty());
+    }
+
+    /// Shrinking the history limits trims the oldest calls right away.
+    #[test]
+    #[traced_test]
+    fn test_set_history_limits_trims_histories() {
+        let mut manager = QueueManager::new(5, 5, true);
+        for id in ["A1", "A2", "A3", "A4"] {
+            manager.add_call(id.to_string(), "1".to_string());
+        }
+        manager.add_to_skipped_directly("B1".to_string(), "2".to_string());
+
+        assert!(!manager.set_history_limits(5, 1));
+        assert!(manager.set_history_limits(2, 0));
+        let completed: Vec<_> = manager
+            .get_completed_history()
+            .iter()
+            .map(|call| call.original_id.as_str())
+            .collect();
+        assert_eq!(completed, vec!["A2", "A3"]);
+        assert!(manager.get_skipped_history().is_empty());
+
+        manager.add_call("A5".to_string(), "1".to_string());
+        assert_eq!(manager.get_completed_history().len(), 2);
+    }
 }
@@ -3,12 +3,54 @@
 //! Queue data structures and manager re-exports.
 
 pub mod manager;
+pub mod no_show;
 
 pub use manager::QueueManager;
+pub use no_show::{NoShowAction, NoShowPolicy, NO_SHOW_SKIP_REASON};
 
 use crate::Call;
 use serde::Serialize;
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+/// History a call ends up in once it stops being current.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryStatus {
+    Completed,
+    Skipped,
+}
+
+impl HistoryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryStatus::Completed => "completed",
+            HistoryStatus::Skipped => "skipped",
+        }
+    }
+}
+
+impl fmt::Display for HistoryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HistoryStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "completed" => Ok(HistoryStatus::Completed),
+            "skipped" => Ok(HistoryStatus::Skipped),
+            other => Err(format!(
+                "Unknown history status '{}'. Use completed or skipped.",
+                other
+            )),
+        }
+    }
+}
 
 /// Snapshot of the queue state shared with API consumers.
 #[derive(Debug, Clone, Serialize, PartialEq)]
@@ -17,4 +59,7 @@ pub struct QueueState {
     pub current_call: Option<Call>,
     pub completed_history: VecDeque<Call>,
     pub skipped_history: VecDeque<Call>,
+    /// Display zone of the current call's counter, when zones are configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_zone: Option<String>,
 }
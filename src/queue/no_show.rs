@@ -0,0 +1,66 @@
+// src/queue/no_show.rs
+
+//! Policy for calls whose patient never arrives at the counter.
+//!
+//! A call that stays current for `timeout` is announced again, up to `recalls`
+//! times, each recall starting a new timeout. When the last timeout runs out
+//! the call is skipped with the [`NO_SHOW_SKIP_REASON`] reason.
+
+use std::time::Duration;
+
+/// `skip_reason` recorded on calls skipped by the no-show timer.
+pub const NO_SHOW_SKIP_REASON: &str = "no_show";
+
+/// What the no-show watcher should do with the current call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoShowAction {
+    Wait,
+    Recall,
+    Skip,
+}
+
+/// No-show timeout and number of automatic recalls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoShowPolicy {
+    pub timeout: Duration,
+    pub recalls: u32,
+}
+
+impl NoShowPolicy {
+    /// Decides the next step for a call that has been current for `elapsed` and
+    /// has already been recalled `recalls_done` times by the watcher.
+    pub fn action(&self, elapsed: Duration, recalls_done: u32) -> NoShowAction {
+        if elapsed < self.timeout.saturating_mul(recalls_done.saturating_add(1)) {
+            NoShowAction::Wait
+        } else if recalls_done < self.recalls {
+            NoShowAction::Recall
+        } else {
+            NoShowAction::Skip
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recalls_before_skipping() {
+        let policy = NoShowPolicy {
+            timeout: Duration::from_secs(60),
+            recalls: 2,
+        };
+        let secs = Duration::from_secs;
+        assert_eq!(policy.action(secs(59), 0), NoShowAction::Wait);
+        assert_eq!(policy.action(secs(60), 0), NoShowAction::Recall);
+        assert_eq!(policy.action(secs(90), 1), NoShowAction::Wait);
+        assert_eq!(policy.action(secs(120), 1), NoShowAction::Recall);
+        assert_eq!(policy.action(secs(180), 2), NoShowAction::Skip);
+
+        let without_recalls = NoShowPolicy {
+            timeout: Duration::from_secs(60),
+            recalls: 0,
+        };
+        assert_eq!(without_recalls.action(secs(60), 0), NoShowAction::Skip);
+    }
+}
@@ -0,0 +1,326 @@
+// src/settings/mod.rs
+
+//! Settings supervisors may change at runtime through `/api/admin/settings`.
+//!
+//! Changes are kept as [`SettingsOverrides`] per room, persisted in the JSON
+//! file named by `SETTINGS_OVERRIDES_PATH` and applied over the configuration
+//! read from the environment and configuration file, at startup and on every
+//! reload. Fields a supervisor never changed keep following that configuration.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+
+/// A language announced by TTS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageSetting {
+    /// Language code as configured, e.g. `th` or `en-GB`.
+    pub code: String,
+    /// Name shown to staff; defaults to the code.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+}
+
+/// The adjustable settings of a room as currently in effect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Settings {
+    pub tts_announcement_template_th: String,
+    pub tts_announcement_template_en: String,
+    /// Announced languages in playback order.
+    pub languages: Vec<LanguageSetting>,
+    pub max_history_size: usize,
+    pub max_skipped_history_size: usize,
+    pub announcement_manual_trigger_cooldown_seconds: u64,
+}
+
+impl Settings {
+    /// Reads the adjustable settings from a room's configuration.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let languages = config
+            .tts_supported_languages
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let (code, name) = entry.split_once(':').unwrap_or((entry, ""));
+                let code = code.trim();
+                let name = name.trim();
+                (!code.is_empty()).then(|| LanguageSetting {
+                    code: code.to_string(),
+                    name: if name.is_empty() { code } else { name }.to_string(),
+                })
+            })
+            .collect();
+        Self {
+            tts_announcement_template_th: config.tts_announcement_template_th.clone(),
+            tts_announcement_template_en: config.tts_announcement_template_en.clone(),
+            languages,
+            max_history_size: config.max_history_size,
+            max_skipped_history_size: config.max_skipped_history_size,
+            announcement_manual_trigger_cooldown_seconds: config
+                .announcement_manual_trigger_cooldown_seconds,
+        }
+    }
+}
+
+/// Settings changed by a supervisor; unset fields keep the configured value.
+///
+/// Also the body of `PUT /api/admin/settings`, which only changes the fields it sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tts_announcement_template_th: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tts_announcement_template_en: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<LanguageSetting>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_history_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_skipped_history_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub announcement_manual_trigger_cooldown_seconds: Option<u64>,
+}
+
+impl SettingsOverrides {
+    /// Takes every field `patch` sets, keeping the others.
+    pub fn merge(&mut self, patch: SettingsOverrides) {
+        let SettingsOverrides {
+            tts_announcement_template_th,
+            tts_announcement_template_en,
+            languages,
+            max_history_size,
+            max_skipped_history_size,
+            announcement_manual_trigger_cooldown_seconds,
+        } = patch;
+        if tts_announcement_template_th.is_some() {
+            self.tts_announcement_template_th = tts_announcement_template_th;
+        }
+        if tts_announcement_template_en.is_some() {
+            self.tts_announcement_template_en = tts_announcement_template_en;
+        }
+        if languages.is_some() {
+            self.languages = languages;
+        }
+        if max_history_size.is_some() {
+            self.max_history_size = max_history_size;
+        }
+        if max_skipped_history_size.is_some() {
+            self.max_skipped_history_size = max_skipped_history_size;
+        }
+        if announcement_manual_trigger_cooldown_seconds.is_some() {
+            self.announcement_manual_trigger_cooldown_seconds =
+                announcement_manual_trigger_cooldown_seconds;
+        }
+    }
+
+    /// Writes the set fields into `config`.
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(template) = &self.tts_announcement_template_th {
+            config.tts_announcement_template_th = template.clone();
+        }
+        if let Some(template) = &self.tts_announcement_template_en {
+            config.tts_announcement_template_en = template.clone();
+        }
+        if let Some(languages) = &self.languages {
+            config.tts_supported_languages = languages
+                .iter()
+                .map(|language| {
+                    let code = language.code.trim();
+                    let name = language.name.trim();
+                    if name.is_empty() {
+                        code.to_string()
+                    } else {
+                        format!("{}:{}", code, name)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+        }
+        if let Some(size) = self.max_history_size {
+            config.max_history_size = size;
+        }
+        if let Some(size) = self.max_skipped_history_size {
+            config.max_skipped_history_size = size;
+        }
+        if let Some(seconds) = self.announcement_manual_trigger_cooldown_seconds {
+            config.announcement_manual_trigger_cooldown_seconds = seconds;
+        }
+    }
+
+    /// Checks what [`AppConfig::validate`] cannot see once the overrides are
+    /// applied: language entries that would not survive the
+    /// `TTS_SUPPORTED_LANGUAGES` format.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (index, language) in self.languages.iter().flatten().enumerate() {
+            let code = language.code.trim();
+            if code.is_empty() || code.contains([',', ':']) {
+                errors.push(format!(
+                    "languages[{}].code: '{}' is not a language code",
+                    index, code
+                ));
+            } else if self
+                .languages
+                .iter()
+                .flatten()
+                .take(index)
+                .any(|other| other.code.trim().eq_ignore_ascii_case(code))
+            {
+                errors.push(format!(
+                    "languages[{}].code: '{}' is listed twice",
+                    index, code
+                ));
+            }
+            if language.name.contains(',') {
+                errors.push(format!("languages[{}].name: must not contain ','", index));
+            }
+        }
+        errors
+    }
+
+    /// Returns true when no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Overrides of every room, keyed by room id.
+pub type RoomOverrides = BTreeMap<String, SettingsOverrides>;
+
+/// Errors raised while reading, validating or saving settings.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// The settings would make the configuration invalid; one message per problem.
+    Invalid(Vec<String>),
+    /// The overrides file could not be read or written.
+    Io(PathBuf, std::io::Error),
+    /// The overrides file is not a JSON object of room overrides.
+    Parse(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Invalid(errors) => {
+                write!(f, "Invalid settings: {}", errors.join("; "))
+            }
+            SettingsError::Io(path, err) => {
+                write!(f, "Failed to access settings file {:?}: {}", path, err)
+            }
+            SettingsError::Parse(path, err) => {
+                write!(f, "Failed to parse settings file {:?}: {}", path, err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Reads the overrides file; a missing file means nothing was changed yet.
+pub fn load_overrides(path: &Path) -> Result<RoomOverrides, SettingsError> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(RoomOverrides::new()),
+        Err(err) => return Err(SettingsError::Io(path.to_path_buf(), err)),
+    };
+    serde_json::from_str(&raw).map_err(|err| SettingsError::Parse(path.to_path_buf(), err))
+}
+
+/// Replaces the overrides file, writing a temporary file first so a crash never
+/// leaves it half written.
+pub fn save_overrides(path: &Path, overrides: &RoomOverrides) -> Result<(), SettingsError> {
+    let io_error = |err| SettingsError::Io(path.to_path_buf(), err);
+    let json = serde_json::to_string_pretty(overrides)
+        .map_err(|err| SettingsError::Parse(path.to_path_buf(), err))?;
+    if let Some(parent) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, json + "\n").map_err(io_error)?;
+    std::fs::rename(&temporary, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use envconfig::Envconfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_overrides_merge_and_apply() {
+        let mut config = AppConfig::init_from_hashmap(&HashMap::new()).unwrap();
+        let mut overrides: SettingsOverrides =
+            serde_json::from_str(r#"{"max_history_size": 9}"#).unwrap();
+        overrides.merge(
+            serde_json::from_str(
+                r#"{"languages": [{"code": "en-GB", "name": "English"}, {"code": "th"}],
+                    "announcement_manual_trigger_cooldown_seconds": 30}"#,
+            )
+            .unwrap(),
+        );
+        assert!(overrides.validate().is_empty());
+        overrides.apply(&mut config);
+
+        let settings = Settings::from_config(&config);
+        assert_eq!(config.tts_supported_languages, "en-GB:English,th");
+        assert_eq!(
+            settings.languages,
+            vec![
+                LanguageSetting {
+                    code: "en-GB".to_string(),
+                    name: "English".to_string()
+                },
+                LanguageSetting {
+                    code: "th".to_string(),
+                    name: "th".to_string()
+                },
+            ]
+        );
+        assert_eq!(settings.max_history_size, 9);
+        assert_eq!(settings.max_skipped_history_size, 5);
+        assert_eq!(settings.announcement_manual_trigger_cooldown_seconds, 30);
+
+        assert!(serde_json::from_str::<SettingsOverrides>(r#"{"max_history": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_overrides_validation() {
+        let overrides: SettingsOverrides = serde_json::from_str(
+            r#"{"languages": [{"code": "th"}, {"code": "en:GB"}, {"code": "TH", "name": "Thai, again"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            overrides.validate(),
+            vec![
+                "languages[1].code: 'en:GB' is not a language code",
+                "languages[2].code: 'TH' is listed twice",
+                "languages[2].name: must not contain ','",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overrides_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("queue-settings-{}", std::process::id()));
+        let path = dir.join("settings.json");
+        assert!(load_overrides(&path).unwrap().is_empty());
+
+        let mut overrides = RoomOverrides::new();
+        overrides.insert(
+            "default".to_string(),
+            SettingsOverrides {
+                max_history_size: Some(3),
+                ..SettingsOverrides::default()
+            },
+        );
+        save_overrides(&path, &overrides).unwrap();
+        assert_eq!(load_overrides(&path).unwrap(), overrides);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
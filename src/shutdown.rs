@@ -0,0 +1,130 @@
+// src/shutdown.rs
+
+//! Support for graceful shutdown.
+//!
+//! On SIGTERM or Ctrl-C the server stops accepting connections, broadcasts
+//! [`crate::AppEvent::ServerShutdown`] so displays show that they are
+//! reconnecting (event streams end right after it), and then waits for the
+//! background work tracked by [`InFlight`] — TTS cache writes and history
+//! writes — before exiting, all within `SHUTDOWN_TIMEOUT_SECONDS`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::info;
+
+/// Counts background work that must finish before the process exits.
+///
+/// Clones share the same count.
+#[derive(Clone, Debug)]
+pub struct InFlight {
+    count: Arc<watch::Sender<usize>>,
+}
+
+/// Marks one piece of tracked work; the work is done when the guard is dropped.
+#[derive(Debug)]
+pub struct InFlightGuard {
+    count: Arc<watch::Sender<usize>>,
+}
+
+impl Default for InFlight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        let (count, _) = watch::channel(0);
+        Self {
+            count: Arc::new(count),
+        }
+    }
+
+    /// Starts tracking a piece of work until the returned guard is dropped.
+    pub fn start(&self) -> InFlightGuard {
+        self.count.send_modify(|count| *count += 1);
+        InFlightGuard {
+            count: Arc::clone(&self.count),
+        }
+    }
+
+    /// Number of pieces of work still running.
+    pub fn pending(&self) -> usize {
+        *self.count.borrow()
+    }
+
+    /// Waits until no work is running, for at most `timeout`.
+    ///
+    /// # Returns
+    /// `true` when all work finished in time.
+    pub async fn drained(&self, timeout: Duration) -> bool {
+        let mut receiver = self.count.subscribe();
+        let drained = tokio::time::timeout(timeout, receiver.wait_for(|count| *count == 0))
+            .await
+            .is_ok();
+        drained
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.count.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Completes when the process is asked to stop: Ctrl-C, or SIGTERM on Unix
+/// (e.g. `systemctl stop` or `podman stop`).
+pub async fn signal() {
+    let interrupt = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl-C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => info!("Received Ctrl-C; shutting down."),
+        _ = terminate => info!("Received SIGTERM; shutting down."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_flight_drains_when_guards_drop() {
+        let in_flight = InFlight::new();
+        assert!(in_flight.drained(Duration::from_millis(10)).await);
+
+        let first = in_flight.start();
+        let second = in_flight.clone().start();
+        assert_eq!(in_flight.pending(), 2);
+        drop(first);
+        assert!(!in_flight.drained(Duration::from_millis(10)).await);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(second);
+        });
+        assert!(in_flight.drained(Duration::from_secs(5)).await);
+        assert_eq!(in_flight.pending(), 0);
+    }
+}
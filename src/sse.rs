@@ -1,28 +1,389 @@
 //! Server-Sent Events helpers.
 
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use axum::response::sse::Event;
+use serde::Deserialize;
 use serde_json::to_string;
+use tokio_stream::Stream;
 use tracing::error;
 
+use crate::config::DisplayZone;
+use crate::displays::{validate_display_id, validate_zone};
+use crate::event_log::LoggedEvent;
 use crate::AppEvent;
 
+/// Query parameters accepted by the SSE endpoint to narrow down the event stream.
+///
+/// Both parameters are comma-separated lists, e.g.
+/// `?types=queue_update,tts_complete&locations=1,2`.
+#[derive(Debug, Default, Deserialize)]
+pub struct EventSubscriptionQuery {
+    /// Event names to deliver (see [`AppEvent::EVENT_NAMES`]).
+    pub types: Option<String>,
+    /// Counter locations to deliver events for.
+    pub locations: Option<String>,
+    /// Identifier of the signage display opening the stream, used for the display registry.
+    pub display_id: Option<String>,
+    /// Zone of the display. Call events for counters in other zones are dropped,
+    /// and display commands can target every display in the zone.
+    pub zone: Option<String>,
+}
+
+/// Per-connection filter deciding which [`AppEvent`]s are forwarded to a client.
+///
+/// An absent list means "everything". Events that are not tied to a counter
+/// (see [`AppEvent::location`]) always pass the location filter so that
+/// displays still receive announcement updates and cleared queue states.
+/// Likewise, a zone only filters out events tagged with a different zone
+/// (see [`AppEvent::zone`]); counters outside every zone reach all displays.
+///
+/// Display commands are only delivered to streams opened with a `display_id`
+/// that the command's target addresses. [`AppEvent::ServerShutdown`] reaches
+/// every stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    types: Option<HashSet<String>>,
+    locations: Option<HashSet<String>>,
+    display_id: Option<String>,
+    zone: Option<String>,
+}
+
+impl EventFilter {
+    /// Build a filter from the raw query parameters.
+    /// Returns an error message when an unknown event type is requested or the
+    /// display id / zone is malformed.
+    pub fn from_query(query: &EventSubscriptionQuery) -> Result<Self, String> {
+        let types = parse_list(query.types.as_deref());
+        if let Some(types) = &types {
+            let mut unknown: Vec<&str> = types
+                .iter()
+                .map(String::as_str)
+                .filter(|name| !AppEvent::EVENT_NAMES.contains(name))
+                .collect();
+            if !unknown.is_empty() {
+                unknown.sort_unstable();
+                return Err(format!(
+                    "Unknown event type(s): {}. Supported types: {}.",
+                    unknown.join(", "),
+                    AppEvent::EVENT_NAMES.join(", ")
+                ));
+            }
+        }
+
+        let display_id = query
+            .display_id
+            .as_deref()
+            .map(validate_display_id)
+            .transpose()?;
+        let zone = query.zone.as_deref().map(validate_zone).transpose()?;
+
+        Ok(Self {
+            types,
+            locations: parse_list(query.locations.as_deref()),
+            display_id,
+            zone,
+        })
+    }
+
+    /// Returns true when the filter does not restrict anything.
+    pub fn is_unrestricted(&self) -> bool {
+        self.types.is_none() && self.locations.is_none() && self.zone.is_none()
+    }
+
+    /// Checks the requested zone against `DISPLAY_ZONES`. Any zone is accepted
+    /// when no zones are configured, since it then only serves as a command label.
+    pub fn check_zone(&self, zones: &[DisplayZone]) -> Result<(), String> {
+        let Some(zone) = self.zone.as_deref() else {
+            return Ok(());
+        };
+        if zones.is_empty() || zones.iter().any(|z| z.name == zone) {
+            return Ok(());
+        }
+        let names: Vec<&str> = zones.iter().map(|z| z.name.as_str()).collect();
+        Err(format!(
+            "Unknown zone '{}'. Configured zones: {}.",
+            zone,
+            names.join(", ")
+        ))
+    }
+
+    /// The validated display id of the subscriber, if it identified itself.
+    pub fn display_id(&self) -> Option<&str> {
+        self.display_id.as_deref()
+    }
+
+    /// The validated zone label of the subscriber, if any.
+    pub fn zone(&self) -> Option<&str> {
+        self.zone.as_deref()
+    }
+
+    /// Returns true when the event should be delivered to the subscriber.
+    pub fn matches(&self, event: &AppEvent) -> bool {
+        if let AppEvent::ServerShutdown = event {
+            return true;
+        }
+
+        if let Some(types) = &self.types {
+            if !types.contains(event.event_name()) {
+                return false;
+            }
+        }
+
+        if let AppEvent::DisplayCommand { target, .. } = event {
+            return match &self.display_id {
+                Some(display_id) => target.includes(display_id, self.zone.as_deref()),
+                None => false,
+            };
+        }
+
+        // A queue update is a snapshot of the whole queue, including calls made
+        // at other counters and zones; dropping it would leave the subscriber's
+        // current call and history stale, so neither filter applies to it.
+        if let AppEvent::QueueUpdate(_) = event {
+            return true;
+        }
+
+        if let (Some(zone), Some(event_zone)) = (&self.zone, event.zone()) {
+            if zone != event_zone {
+                return false;
+            }
+        }
+
+        match (&self.locations, event.location()) {
+            (Some(locations), Some(location)) => locations.contains(location),
+            _ => true,
+        }
+    }
+}
+
+fn parse_list(raw: Option<&str>) -> Option<HashSet<String>> {
+    let values: HashSet<String> = raw?
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// An SSE stream item: the event and whether it is the
+/// [`AppEvent::ServerShutdown`] that ends the stream.
+pub type StreamItem = (Event, bool);
+
+/// Ends an SSE stream right after the item flagged as the shutdown event, so
+/// open connections do not hold up a graceful shutdown. Keep-alive comments
+/// are merged into the stream, so it would otherwise never end on its own.
+pub struct EndAfterShutdown<S> {
+    inner: Pin<Box<S>>,
+    ended: bool,
+}
+
+impl<S: Stream<Item = StreamItem>> EndAfterShutdown<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner: Box::pin(inner),
+            ended: false,
+        }
+    }
+}
+
+impl<S: Stream<Item = StreamItem>> Stream for EndAfterShutdown<S> {
+    type Item = Result<Event, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.ended {
+            return Poll::Ready(None);
+        }
+        match self.inner.as_mut().poll_next(cx) {
+            Poll::Ready(Some((event, last))) => {
+                self.ended = last;
+                Poll::Ready(Some(Ok(event)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Convert a [`LoggedEvent`] into an Axum SSE event carrying its log id, so
+/// clients can resume with `Last-Event-ID`.
+pub fn format_logged_event(logged: &LoggedEvent) -> Option<Event> {
+    format_app_event(&logged.event).map(|event| event.id(logged.id.to_string()))
+}
+
 /// Convert an [`AppEvent`] into an Axum SSE event.
 /// Returns `None` when serialization fails, which signals the caller to drop the event.
 pub fn format_app_event(event: &AppEvent) -> Option<Event> {
     match to_string(event) {
-        Ok(json_payload) => {
-            let event_name = match event {
-                AppEvent::QueueUpdate(_) => "queue_update",
-                AppEvent::AnnouncementStatus(_) => "announcement_status",
-                AppEvent::TranslatorCall { .. } => "translator_call",
-                AppEvent::TTSComplete { .. } => "tts_complete",
-            };
-
-            Some(Event::default().event(event_name).data(json_payload))
-        }
+        Ok(json_payload) => Some(
+            Event::default()
+                .event(event.event_name())
+                .data(json_payload),
+        ),
         Err(err) => {
             error!("Failed to serialise AppEvent for SSE: {}", err);
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::displays::{DisplayCommand, DisplayTarget};
+    use crate::queue::QueueState;
+    use crate::Call;
+
+    fn translator_event(location: &str) -> AppEvent {
+        AppEvent::TranslatorCall {
+            location: location.to_string(),
+            audio_urls: Vec::new(),
+            cooldown_seconds: 0,
+            cooldown_remaining_seconds: 0,
+            zone: None,
+        }
+    }
+
+    fn tts_event(location: &str) -> AppEvent {
+        zoned_tts_event(location, None)
+    }
+
+    fn zoned_tts_event(location: &str, zone: Option<&str>) -> AppEvent {
+        AppEvent::TTSComplete {
+            id: "A01".to_string(),
+            location: location.to_string(),
+            lang: "th".to_string(),
+            audio_urls: Vec::new(),
+            zone: zone.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        let filter = EventFilter::from_query(&EventSubscriptionQuery {
+            types: Some(" , ".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(filter.is_unrestricted());
+        assert!(filter.matches(&translator_event("7")));
+    }
+
+    #[test]
+    fn filters_by_type_and_location() {
+        let filter = EventFilter::from_query(&EventSubscriptionQuery {
+            types: Some("tts_complete,queue_update".to_string()),
+            locations: Some("1, 2".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(filter.matches(&tts_event("2")));
+        assert!(!filter.matches(&tts_event("5")));
+        assert!(!filter.matches(&translator_event("1")));
+        assert!(filter.matches(&AppEvent::ServerShutdown));
+    }
+
+    fn queue_update(location: &str, zone: Option<&str>) -> AppEvent {
+        AppEvent::QueueUpdate(QueueState {
+            identifier_prefix_required: true,
+            current_call: Some(Call {
+                id: "A1-1".to_string(),
+                original_id: "A1".to_string(),
+                location: location.to_string(),
+                timestamp: chrono::Utc::now(),
+                skip_reason: None,
+            }),
+            completed_history: Default::default(),
+            skipped_history: Default::default(),
+            current_zone: zone.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn queue_updates_ignore_the_location_filter() {
+        let filter = EventFilter::from_query(&EventSubscriptionQuery {
+            locations: Some("3".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let update = queue_update("5", None);
+
+        assert_eq!(update.location(), Some("5"));
+        assert!(filter.matches(&update));
+        assert!(!filter.matches(&tts_event("5")));
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_shutdown_event() {
+        use tokio_stream::StreamExt;
+
+        let items = tokio_stream::iter(vec![
+            (Event::default().comment("keep-alive"), false),
+            (Event::default().event("server_shutdown"), true),
+            (Event::default().comment("keep-alive"), false),
+        ]);
+        let delivered: Vec<_> = EndAfterShutdown::new(items).collect().await;
+        assert_eq!(delivered.len(), 2);
+    }
+
+    #[test]
+    fn filters_by_zone() {
+        let filter = EventFilter::from_query(&EventSubscriptionQuery {
+            zone: Some("lobby".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(filter.matches(&zoned_tts_event("1", Some("lobby"))));
+        assert!(!filter.matches(&zoned_tts_event("5", Some("upstairs"))));
+        assert!(
+            filter.matches(&tts_event("12")),
+            "unzoned counters reach everyone"
+        );
+        assert!(
+            filter.matches(&queue_update("5", Some("upstairs"))),
+            "queue snapshots reach every zone"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let err = EventFilter::from_query(&EventSubscriptionQuery {
+            types: Some("queue_update,bogus".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.contains("bogus"));
+    }
+
+    #[test]
+    fn display_commands_reach_only_targeted_displays() {
+        let command = |target: DisplayTarget| AppEvent::DisplayCommand {
+            command_id: "cmd-1".to_string(),
+            target,
+            command: DisplayCommand::Reload,
+            issued_at: chrono::Utc::now(),
+        };
+        let lobby_tv = EventFilter::from_query(&EventSubscriptionQuery {
+            display_id: Some("tv-1".to_string()),
+            zone: Some("lobby".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let anonymous = EventFilter::default();
+
+        assert!(lobby_tv.matches(&command(DisplayTarget::All)));
+        assert!(lobby_tv.matches(&command(DisplayTarget::Zone("lobby".to_string()))));
+        assert!(!lobby_tv.matches(&command(DisplayTarget::Display("tv-2".to_string()))));
+        assert!(!anonymous.matches(&command(DisplayTarget::All)));
+    }
+}
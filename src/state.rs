@@ -2,67 +2,377 @@
 
 //! Shared application state wiring for Axum routes.
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::application::{AnnouncementService, QueueService, TranslatorService, TtsService};
-use crate::config::AppConfig;
+use crate::application::{
+    AnnouncementService, AppointmentService, CommandService, DisplayService, HistoryService,
+    QueueService, SettingsService, TicketService, TranslatorService, TtsService, WebhookService,
+};
+use crate::config::{AppConfig, ConfigReloadReport, ConfigSource, SharedConfig, DEFAULT_ROOM_ID};
+use crate::event_log::EventLog;
+use crate::settings::{Settings, SettingsError, SettingsOverrides};
+use crate::shutdown::InFlight;
 use crate::AppEvent;
 
-/// Shared managers and configuration exposed to request handlers.
+/// Managers, configuration and event bus of a single independent room.
+///
+/// Rooms share nothing but the HTTP server: each has its own queue history,
+/// languages, announcements, displays and event stream.
 #[derive(Clone)]
-pub struct AppState {
-    pub config: Arc<AppConfig>,
+pub struct RoomState {
+    pub id: String,
+    /// Live configuration, swapped in place by [`AppState::reload_config`].
+    pub config: SharedConfig,
     pub queue: QueueService,
     pub announcements: AnnouncementService,
     pub tts: TtsService,
     pub translator: TranslatorService,
+    /// Queue commands of the WebSocket, MQTT and keypad controls.
+    pub commands: CommandService,
+    pub displays: DisplayService,
+    pub tickets: TicketService,
+    pub appointments: AppointmentService,
     pub event_bus_sender: broadcast::Sender<AppEvent>,
+    /// Numbered replay buffer of the events broadcast on `event_bus_sender`.
+    pub events: EventLog,
+    pub webhooks: WebhookService,
+    /// Complete call and event history, beyond what `QueueState` carries.
+    pub history: HistoryService,
 }
 
-impl AppState {
-    /// Initialise application state and supporting managers.
-    pub async fn new(config: AppConfig) -> Self {
-        info!("Initialising application state...");
+impl RoomState {
+    /// Initialise the managers of one room. Background work that must finish
+    /// before the process exits is registered with `in_flight`.
+    pub async fn new(id: &str, config: AppConfig, in_flight: &InFlight) -> Self {
+        info!("Initialising room '{}'...", id);
 
         let (event_bus_sender, _event_bus_receiver) =
             broadcast::channel::<AppEvent>(config.sse_event_buffer_size);
         debug!(
-            "Created AppEvent broadcast channel with buffer size {}",
-            config.sse_event_buffer_size
+            "Created AppEvent broadcast channel for room '{}' with buffer size {}",
+            id, config.sse_event_buffer_size
+        );
+
+        let events = EventLog::new(
+            &event_bus_sender,
+            config.event_replay_buffer_size,
+            config.sse_event_buffer_size,
+        );
+        debug!(
+            "Event replay buffer for room '{}' keeps {} events",
+            id, config.event_replay_buffer_size
         );
 
-        let config_arc = Arc::new(config);
+        let shared_config = SharedConfig::new(config);
+        // Snapshot for the services that only read settings while starting.
+        let config_arc = shared_config.get();
 
-        let tts_service = TtsService::new(Arc::clone(&config_arc), event_bus_sender.clone());
+        let tts_service = TtsService::new(
+            shared_config.clone(),
+            event_bus_sender.clone(),
+            in_flight.clone(),
+        );
         info!("TTS service initialised.");
 
+        let history_service = HistoryService::new(&config_arc, id, &events, in_flight.clone());
+        info!(
+            "History service initialised ({}).",
+            history_service.describe()
+        );
+
         let queue_service = QueueService::new(
-            Arc::clone(&config_arc),
+            shared_config.clone(),
             event_bus_sender.clone(),
             tts_service.clone(),
+            history_service.clone(),
         );
         info!(
             "Queue service initialised with history limits completed={}, skipped={}",
             config_arc.max_history_size, config_arc.max_skipped_history_size
         );
 
+        let translator_service =
+            TranslatorService::new(shared_config.clone(), event_bus_sender.clone());
+        info!("Translator service initialised.");
+
+        let command_service = CommandService::new(
+            shared_config.clone(),
+            queue_service.clone(),
+            translator_service.clone(),
+        );
+
+        #[cfg(feature = "mqtt")]
+        crate::mqtt::bridge::start(
+            Arc::clone(&config_arc),
+            id,
+            &event_bus_sender,
+            events.stats(),
+            queue_service.clone(),
+            command_service.clone(),
+        );
+        #[cfg(not(feature = "mqtt"))]
+        if !config_arc.mqtt_broker_url.trim().is_empty() {
+            warn!(
+                "MQTT_BROKER_URL is set for room '{}' but this build lacks the 'mqtt' feature; the MQTT bridge is disabled.",
+                id
+            );
+        }
+
         let announcement_service =
-            AnnouncementService::new(Arc::clone(&config_arc), event_bus_sender.clone()).await;
+            AnnouncementService::new(shared_config.clone(), event_bus_sender.clone()).await;
         info!("Announcement service initialised.");
 
-        let translator_service =
-            TranslatorService::new(Arc::clone(&config_arc), event_bus_sender.clone());
-        info!("Translator service initialised.");
+        let display_service =
+            DisplayService::new(Arc::clone(&config_arc), event_bus_sender.clone());
+        info!("Display registry initialised.");
 
-        AppState {
-            config: config_arc,
+        let ticket_service = TicketService::new(
+            Arc::clone(&config_arc),
+            id,
+            event_bus_sender.clone(),
+            events.stats().clone(),
+        );
+        info!("Ticket service initialised.");
+
+        let appointment_service = AppointmentService::new(ticket_service.clone());
+
+        let webhook_service = WebhookService::new(Arc::clone(&config_arc), &events, id);
+        info!("Webhook service initialised.");
+
+        RoomState {
+            id: id.to_string(),
+            config: shared_config,
             queue: queue_service,
             announcements: announcement_service,
             tts: tts_service,
             translator: translator_service,
+            commands: command_service,
+            displays: display_service,
+            tickets: ticket_service,
+            appointments: appointment_service,
             event_bus_sender,
+            events,
+            webhooks: webhook_service,
+            history: history_service,
+        }
+    }
+
+    /// Apply adjustable settings the services do not re-read on every use (the
+    /// queue's history limits) and broadcast `settings` to the room.
+    pub async fn settings_changed(&self, settings: Settings) {
+        self.queue.apply_history_limits().await;
+        if let Err(err) = self
+            .event_bus_sender
+            .send(AppEvent::SettingsChanged(settings))
+        {
+            debug!(
+                "Room '{}': failed to broadcast settings change ({}). no active listeners?",
+                self.id, err
+            );
+        }
+    }
+}
+
+/// Shared state exposed to request handlers: the server-wide configuration and
+/// every room served by this process.
+#[derive(Clone)]
+pub struct AppState {
+    /// Configuration of the default room, which also holds server-wide settings.
+    pub config: SharedConfig,
+    /// Where the configuration was read from, read again on reload.
+    source: ConfigSource,
+    /// Settings changed through `/api/admin/settings`, applied over `source`.
+    pub settings: SettingsService,
+    /// Background work of every room that a graceful shutdown waits for.
+    pub in_flight: InFlight,
+    started: Instant,
+    rooms: HashMap<String, Arc<RoomState>>,
+}
+
+impl AppState {
+    /// Initialise the default room from `config` plus one room per entry of
+    /// `rooms`, both loaded from `source`, with the overrides in `settings`
+    /// applied on top.
+    pub async fn new(
+        source: ConfigSource,
+        settings: SettingsService,
+        mut config: AppConfig,
+        rooms: Vec<(String, AppConfig)>,
+    ) -> Self {
+        info!("Initialising application state...");
+
+        let in_flight = InFlight::new();
+        settings.apply_to(DEFAULT_ROOM_ID, &mut config).await;
+        let default_room = RoomState::new(DEFAULT_ROOM_ID, config, &in_flight).await;
+        let config = default_room.config.clone();
+
+        let mut room_map = HashMap::new();
+        room_map.insert(DEFAULT_ROOM_ID.to_string(), Arc::new(default_room));
+        for (room_id, mut room_config) in rooms {
+            settings.apply_to(&room_id, &mut room_config).await;
+            let room = RoomState::new(&room_id, room_config, &in_flight).await;
+            room_map.insert(room_id, Arc::new(room));
+        }
+        info!(
+            "Application state initialised with {} room(s).",
+            room_map.len()
+        );
+
+        AppState {
+            config,
+            source,
+            settings,
+            in_flight,
+            started: Instant::now(),
+            rooms: room_map,
+        }
+    }
+
+    /// Time since the application state was initialised.
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Look up a room by id.
+    pub fn room(&self, room_id: &str) -> Option<Arc<RoomState>> {
+        self.rooms.get(room_id).cloned()
+    }
+
+    /// The room served by the un-prefixed `/api/...` routes.
+    pub fn default_room(&self) -> Arc<RoomState> {
+        self.room(DEFAULT_ROOM_ID)
+            .expect("default room is created in AppState::new")
+    }
+
+    /// Re-read the configuration and apply it to every room.
+    ///
+    /// The default room is built from the configuration file and environment
+    /// (plus `CONFIG_ENV_FILE`) and each additional room from its `ROOM_<ID>_`
+    /// overrides, with the settings changed through `/api/admin/settings` on
+    /// top, as at startup.
+    /// Every configuration is validated first: if any is invalid, no room
+    /// changes. Settings only read at startup keep their running values and are
+    /// reported as needing a restart.
+    pub async fn reload_config(&self) -> ConfigReloadReport {
+        info!("Reloading configuration...");
+        let mut report = ConfigReloadReport::default();
+
+        let vars = match self.source.vars() {
+            Ok(vars) => vars,
+            Err(e) => {
+                report.errors.push(e.to_string());
+                return Self::rejected(report);
+            }
+        };
+        let mut default_next = match AppConfig::from_vars(&vars) {
+            Ok(next) => next,
+            Err(e) => {
+                report.errors.push(e.to_string());
+                return Self::rejected(report);
+            }
+        };
+        self.settings
+            .apply_to(DEFAULT_ROOM_ID, &mut default_next)
+            .await;
+        let (default_next, changes) = self.config.get().reloaded(default_next);
+
+        let mut pending = Vec::new();
+        for room_id in self.room_ids() {
+            let Some(room) = self.room(&room_id) else {
+                continue;
+            };
+            let (next, changes) = if room_id == DEFAULT_ROOM_ID {
+                (default_next.clone(), changes.clone())
+            } else {
+                match default_next.room_config_from_vars(&room_id, &vars) {
+                    Ok(mut next) => {
+                        self.settings.apply_to(&room_id, &mut next).await;
+                        room.config.get().reloaded(next)
+                    }
+                    Err(e) => {
+                        report.errors.push(e.to_string());
+                        continue;
+                    }
+                }
+            };
+            for error in next.validate() {
+                if room_id == DEFAULT_ROOM_ID {
+                    report.errors.push(error);
+                } else {
+                    report.errors.push(format!("room '{}': {}", room_id, error));
+                }
+            }
+            report.rooms.insert(room_id, changes);
+            pending.push((room, next));
         }
+
+        if !report.errors.is_empty() {
+            return Self::rejected(report);
+        }
+        for (room, next) in pending {
+            let before = Settings::from_config(&room.config.get());
+            let after = Settings::from_config(&next);
+            room.config.replace(next);
+            if before != after {
+                room.settings_changed(after).await;
+            }
+        }
+        report.applied = true;
+        for (room_id, changes) in &report.rooms {
+            info!(
+                "Configuration of room '{}' reloaded. Changed: {:?}; restart required for: {:?}",
+                room_id, changes.changed, changes.restart_required
+            );
+        }
+        report
+    }
+
+    /// Change a room's adjustable settings, persist them and apply them live.
+    ///
+    /// # Errors
+    /// [`SettingsError::Invalid`] when the room's configuration would become
+    /// invalid, or the overrides file could not be written; the room is unchanged.
+    pub async fn update_settings(
+        &self,
+        room: &RoomState,
+        patch: SettingsOverrides,
+    ) -> Result<Settings, SettingsError> {
+        let settings = self.settings.update(&room.id, &room.config, patch).await?;
+        room.settings_changed(settings.clone()).await;
+        Ok(settings)
+    }
+
+    /// Tell every room's clients that the server is going away. Event streams
+    /// end after delivering the event, so open connections do not hold up the
+    /// shutdown.
+    pub fn announce_shutdown(&self) {
+        for room in self.rooms.values() {
+            if let Err(err) = room.event_bus_sender.send(AppEvent::ServerShutdown) {
+                debug!(
+                    "Room '{}': failed to broadcast shutdown ({}). no active listeners?",
+                    room.id, err
+                );
+            }
+        }
+    }
+
+    fn rejected(mut report: ConfigReloadReport) -> ConfigReloadReport {
+        warn!(
+            "Configuration reload rejected, keeping the running configuration: {}",
+            report.errors.join("; ")
+        );
+        report.applied = false;
+        report
+    }
+
+    /// Every room id, sorted with the default room first.
+    pub fn room_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.rooms.keys().cloned().collect();
+        ids.sort_by_key(|id| (id != DEFAULT_ROOM_ID, id.clone()));
+        ids
     }
 }
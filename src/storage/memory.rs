@@ -0,0 +1,169 @@
+// src/storage/memory.rs
+
+//! In-memory storage with the same limits as the queue's own histories.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::{
+    paginate, HistoryPage, HistoryRecord, HistoryStatus, Storage, StorageError, StoredEvent,
+};
+use crate::Call;
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Oldest first.
+    calls: VecDeque<HistoryRecord>,
+    /// Oldest first.
+    events: VecDeque<StoredEvent>,
+}
+
+/// Keeps the last `completed_limit` completed calls, `skipped_limit` skipped
+/// calls and `event_limit` events. Nothing survives a restart.
+#[derive(Debug)]
+pub struct MemoryStorage {
+    completed_limit: usize,
+    skipped_limit: usize,
+    event_limit: usize,
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryStorage {
+    pub fn new(completed_limit: usize, skipped_limit: usize, event_limit: usize) -> Self {
+        Self {
+            completed_limit,
+            skipped_limit,
+            event_limit,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn describe(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn record_call(&self, status: HistoryStatus, call: &Call) -> Result<(), StorageError> {
+        let limit = match status {
+            HistoryStatus::Completed => self.completed_limit,
+            HistoryStatus::Skipped => self.skipped_limit,
+        };
+        let mut state = self.state();
+        state.calls.push_back(HistoryRecord {
+            call: call.clone(),
+            status,
+        });
+        let kept = state
+            .calls
+            .iter()
+            .filter(|record| record.status == status)
+            .count();
+        if kept > limit {
+            let mut excess = kept - limit;
+            state.calls.retain(|record| {
+                if excess > 0 && record.status == status {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn call_history(
+        &self,
+        status: Option<HistoryStatus>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<HistoryPage, StorageError> {
+        let records = self
+            .state()
+            .calls
+            .iter()
+            .rev()
+            .filter(|record| status.is_none_or(|status| record.status == status))
+            .cloned()
+            .collect();
+        Ok(paginate(records, page, page_size))
+    }
+
+    fn record_event(&self, event: &StoredEvent) -> Result<(), StorageError> {
+        let mut state = self.state();
+        state.events.push_back(event.clone());
+        while state.events.len() > self.event_limit {
+            state.events.pop_front();
+        }
+        Ok(())
+    }
+
+    fn recent_events(&self, limit: usize) -> Result<Vec<StoredEvent>, StorageError> {
+        Ok(self
+            .state()
+            .events
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn call(id: &str) -> Call {
+        Call {
+            id: id.to_string(),
+            original_id: id.to_string(),
+            location: "1".to_string(),
+            timestamp: Utc::now(),
+            skip_reason: None,
+        }
+    }
+
+    #[test]
+    fn keeps_per_status_limits_and_pages_newest_first() {
+        let storage = MemoryStorage::new(2, 1, 1);
+        for id in ["A1", "A2", "A3"] {
+            storage
+                .record_call(HistoryStatus::Completed, &call(id))
+                .unwrap();
+        }
+        storage
+            .record_call(HistoryStatus::Skipped, &call("B1"))
+            .unwrap();
+        storage
+            .record_call(HistoryStatus::Skipped, &call("B2"))
+            .unwrap();
+
+        let ids = |page: HistoryPage| -> Vec<String> {
+            page.items
+                .into_iter()
+                .map(|record| record.call.id)
+                .collect()
+        };
+        let all = storage.call_history(None, 1, 10).unwrap();
+        assert_eq!(all.total, 3);
+        assert_eq!(ids(all), vec!["B2", "A3", "A2"]);
+        assert_eq!(
+            ids(storage
+                .call_history(Some(HistoryStatus::Completed), 2, 1)
+                .unwrap()),
+            vec!["A2"]
+        );
+        assert!(storage
+            .call_history(Some(HistoryStatus::Skipped), 3, 1)
+            .unwrap()
+            .items
+            .is_empty());
+    }
+}
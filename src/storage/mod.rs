@@ -0,0 +1,186 @@
+// src/storage/mod.rs
+
+//! Persistent history of calls and events.
+//!
+//! [`QueueState`](crate::queue::QueueState) only carries the last few completed
+//! and skipped calls for signage. Every call leaving the current slot and every
+//! broadcast event is also written to a [`Storage`], which can keep far more:
+//!
+//! - [`MemoryStorage`] keeps the same bounded history as the queue (the default).
+//! - `SqliteStorage` (cargo feature `sqlite`) keeps everything in a database file
+//!   set by `HISTORY_DATABASE_PATH`.
+
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(not(feature = "sqlite"))]
+use tracing::warn;
+#[cfg(feature = "sqlite")]
+use tracing::{error, info};
+
+use crate::config::AppConfig;
+use crate::event_log::LoggedEvent;
+pub use crate::queue::HistoryStatus;
+use crate::Call;
+
+/// Page size used by `GET /api/queue/history` when none is requested.
+pub const DEFAULT_HISTORY_PAGE_SIZE: usize = 50;
+/// Largest page size accepted by `GET /api/queue/history`.
+pub const MAX_HISTORY_PAGE_SIZE: usize = 200;
+
+/// A call that left the current slot.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct HistoryRecord {
+    #[serde(flatten)]
+    pub call: Call,
+    pub status: HistoryStatus,
+}
+
+/// One page of call history, newest first.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct HistoryPage {
+    /// 1-based page number.
+    pub page: usize,
+    pub page_size: usize,
+    /// Number of records matching the filter across all pages.
+    pub total: usize,
+    pub items: Vec<HistoryRecord>,
+}
+
+/// A broadcast event as kept by the storage.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StoredEvent {
+    /// Id assigned by the room's event log (restarts at 1 with the server).
+    pub event_id: u64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl StoredEvent {
+    pub fn from_logged(logged: &LoggedEvent, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            event_id: logged.id,
+            event_type: logged.event.event_name().to_string(),
+            payload: serde_json::to_value(&logged.event).unwrap_or(serde_json::Value::Null),
+            recorded_at,
+        }
+    }
+}
+
+/// Errors raised by a storage backend.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The backend could not be opened or prepared.
+    Open(String),
+    /// Reading or writing failed.
+    Query(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Open(reason) => write!(f, "Failed to open history storage: {}", reason),
+            StorageError::Query(reason) => write!(f, "History storage error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Backend keeping the call and event history of one room.
+///
+/// Methods are blocking; async callers should use `spawn_blocking`.
+pub trait Storage: Send + Sync {
+    /// Short description used in log messages.
+    fn describe(&self) -> String;
+
+    /// Stores a call that moved into the completed or skipped history.
+    fn record_call(&self, status: HistoryStatus, call: &Call) -> Result<(), StorageError>;
+
+    /// Returns one page (1-based) of call history, newest first, optionally
+    /// limited to one status.
+    fn call_history(
+        &self,
+        status: Option<HistoryStatus>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<HistoryPage, StorageError>;
+
+    /// Stores a broadcast event.
+    fn record_event(&self, event: &StoredEvent) -> Result<(), StorageError>;
+
+    /// Returns up to `limit` of the most recent events, newest first.
+    fn recent_events(&self, limit: usize) -> Result<Vec<StoredEvent>, StorageError>;
+}
+
+/// Opens the storage configured for a room: SQLite when `HISTORY_DATABASE_PATH`
+/// is set (and the `sqlite` feature is built), memory otherwise. Failures fall
+/// back to memory so the queue keeps working.
+pub fn open(config: &AppConfig, room_id: &str) -> Arc<dyn Storage> {
+    let memory = || -> Arc<dyn Storage> {
+        Arc::new(MemoryStorage::new(
+            config.max_history_size,
+            config.max_skipped_history_size,
+            config.event_replay_buffer_size,
+        ))
+    };
+    let path = config.history_database_path.trim();
+    if path.is_empty() {
+        return memory();
+    }
+
+    #[cfg(feature = "sqlite")]
+    {
+        match SqliteStorage::open(std::path::Path::new(path), room_id) {
+            Ok(storage) => {
+                info!(
+                    "History of room '{}' is stored in {}.",
+                    room_id,
+                    storage.describe()
+                );
+                Arc::new(storage)
+            }
+            Err(err) => {
+                error!(
+                    "{}. Room '{}' keeps its history in memory only.",
+                    err, room_id
+                );
+                memory()
+            }
+        }
+    }
+    #[cfg(not(feature = "sqlite"))]
+    {
+        warn!(
+            "HISTORY_DATABASE_PATH is set for room '{}' but this build lacks the 'sqlite' feature; history is kept in memory only.",
+            room_id
+        );
+        memory()
+    }
+}
+
+/// Applies `page`/`page_size` to records ordered newest first.
+pub(crate) fn paginate(records: Vec<HistoryRecord>, page: usize, page_size: usize) -> HistoryPage {
+    let total = records.len();
+    let items = records
+        .into_iter()
+        .skip(page.saturating_sub(1).saturating_mul(page_size))
+        .take(page_size)
+        .collect();
+    HistoryPage {
+        page,
+        page_size,
+        total,
+        items,
+    }
+}
@@ -0,0 +1,264 @@
+// src/storage/sqlite.rs
+
+//! SQLite storage keeping the complete call and event history on disk.
+//!
+//! Rooms may share one database file; every row carries its room id.
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+
+use super::{HistoryPage, HistoryRecord, HistoryStatus, Storage, StorageError, StoredEvent};
+use crate::Call;
+
+/// How long a write waits for another connection (e.g. another room) to
+/// release the database.
+const BUSY_TIMEOUT_SECONDS: u64 = 5;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS call_history (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    room TEXT NOT NULL,
+    status TEXT NOT NULL,
+    id TEXT NOT NULL,
+    original_id TEXT NOT NULL,
+    location TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    skip_reason TEXT
+);
+CREATE INDEX IF NOT EXISTS call_history_room_status ON call_history (room, status, seq);
+CREATE TABLE IF NOT EXISTS events (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    room TEXT NOT NULL,
+    event_id INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_room ON events (room, seq);
+";
+
+/// History of one room stored in a SQLite database.
+pub struct SqliteStorage {
+    description: String,
+    room: String,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Opens (creating if needed) the database at `path`. `:memory:` opens a
+    /// private in-memory database.
+    pub fn open(path: &Path, room: &str) -> Result<Self, StorageError> {
+        let connection = Connection::open(path)
+            .map_err(|err| StorageError::Open(format!("{}: {}", path.display(), err)))?;
+        connection
+            .busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECONDS))
+            .and_then(|()| connection.execute_batch(SCHEMA))
+            .map_err(|err| StorageError::Open(format!("{}: {}", path.display(), err)))?;
+        Ok(Self {
+            description: format!("sqlite:{}", path.display()),
+            room: room.to_string(),
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn query_error(err: rusqlite::Error) -> StorageError {
+    StorageError::Query(err.to_string())
+}
+
+fn parse_time(raw: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+impl Storage for SqliteStorage {
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+
+    fn record_call(&self, status: HistoryStatus, call: &Call) -> Result<(), StorageError> {
+        self.connection()
+            .execute(
+                "INSERT INTO call_history (room, status, id, original_id, location, timestamp, skip_reason)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    self.room,
+                    status.as_str(),
+                    call.id,
+                    call.original_id,
+                    call.location,
+                    call.timestamp.to_rfc3339(),
+                    call.skip_reason,
+                ],
+            )
+            .map(|_| ())
+            .map_err(query_error)
+    }
+
+    fn call_history(
+        &self,
+        status: Option<HistoryStatus>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<HistoryPage, StorageError> {
+        let connection = self.connection();
+        let status = status.map(|status| status.as_str());
+        let total: i64 = connection
+            .query_row(
+                "SELECT COUNT(*) FROM call_history WHERE room = ?1 AND (?2 IS NULL OR status = ?2)",
+                params![self.room, status],
+                |row| row.get(0),
+            )
+            .map_err(query_error)?;
+
+        let offset = page.saturating_sub(1).saturating_mul(page_size);
+        let mut statement = connection
+            .prepare(
+                "SELECT status, id, original_id, location, timestamp, skip_reason
+                 FROM call_history WHERE room = ?1 AND (?2 IS NULL OR status = ?2)
+                 ORDER BY seq DESC LIMIT ?3 OFFSET ?4",
+            )
+            .map_err(query_error)?;
+        let items = statement
+            .query_map(
+                params![
+                    self.room,
+                    status,
+                    i64::try_from(page_size).unwrap_or(i64::MAX),
+                    i64::try_from(offset).unwrap_or(i64::MAX),
+                ],
+                |row| {
+                    let status: String = row.get(0)?;
+                    let timestamp: String = row.get(4)?;
+                    Ok(HistoryRecord {
+                        status: status.parse().unwrap_or(HistoryStatus::Completed),
+                        call: Call {
+                            id: row.get(1)?,
+                            original_id: row.get(2)?,
+                            location: row.get(3)?,
+                            timestamp: parse_time(&timestamp),
+                            skip_reason: row.get(5)?,
+                        },
+                    })
+                },
+            )
+            .map_err(query_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(query_error)?;
+
+        Ok(HistoryPage {
+            page,
+            page_size,
+            total: usize::try_from(total).unwrap_or_default(),
+            items,
+        })
+    }
+
+    fn record_event(&self, event: &StoredEvent) -> Result<(), StorageError> {
+        self.connection()
+            .execute(
+                "INSERT INTO events (room, event_id, event_type, payload, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    self.room,
+                    i64::try_from(event.event_id).unwrap_or(i64::MAX),
+                    event.event_type,
+                    event.payload.to_string(),
+                    event.recorded_at.to_rfc3339(),
+                ],
+            )
+            .map(|_| ())
+            .map_err(query_error)
+    }
+
+    fn recent_events(&self, limit: usize) -> Result<Vec<StoredEvent>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare(
+                "SELECT event_id, event_type, payload, recorded_at FROM events
+                 WHERE room = ?1 ORDER BY seq DESC LIMIT ?2",
+            )
+            .map_err(query_error)?;
+        let events = statement
+            .query_map(
+                params![self.room, i64::try_from(limit).unwrap_or(i64::MAX)],
+                |row| {
+                    let event_id: i64 = row.get(0)?;
+                    let payload: String = row.get(2)?;
+                    let recorded_at: String = row.get(3)?;
+                    Ok(StoredEvent {
+                        event_id: u64::try_from(event_id).unwrap_or_default(),
+                        event_type: row.get(1)?,
+                        payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                        recorded_at: parse_time(&recorded_at),
+                    })
+                },
+            )
+            .map_err(query_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(query_error);
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str, skip_reason: Option<&str>) -> Call {
+        Call {
+            id: id.to_string(),
+            original_id: id.to_string(),
+            location: "2".to_string(),
+            timestamp: Utc::now(),
+            skip_reason: skip_reason.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn stores_unbounded_history_per_room() {
+        let storage = SqliteStorage::open(Path::new(":memory:"), "default").unwrap();
+        for index in 1..=30 {
+            storage
+                .record_call(
+                    HistoryStatus::Completed,
+                    &call(&format!("A{:02}", index), None),
+                )
+                .unwrap();
+        }
+        let skipped = call("B01", Some("no_show"));
+        storage
+            .record_call(HistoryStatus::Skipped, &skipped)
+            .unwrap();
+
+        let first = storage.call_history(None, 1, 10).unwrap();
+        assert_eq!(first.total, 31);
+        assert_eq!(first.items[0].call, skipped);
+        assert_eq!(first.items[1].call.id, "A30");
+        let last = storage
+            .call_history(Some(HistoryStatus::Completed), 3, 10)
+            .unwrap();
+        assert_eq!(last.total, 30);
+        assert_eq!(last.items.last().unwrap().call.id, "A01");
+
+        let event = StoredEvent {
+            event_id: 7,
+            event_type: "queue_update".to_string(),
+            payload: serde_json::json!({"type": "QueueUpdate"}),
+            recorded_at: Utc::now(),
+        };
+        storage.record_event(&event).unwrap();
+        assert_eq!(storage.recent_events(5).unwrap()[0].event_id, 7);
+    }
+}
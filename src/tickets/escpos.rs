@@ -0,0 +1,139 @@
+// src/tickets/escpos.rs
+
+//! ESC/POS rendering of printed tickets.
+//!
+//! Output uses only widely supported commands (text styles, the native QR code
+//! command `GS ( k` and a partial cut), so it works on common 58/80 mm thermal
+//! printers without printer-specific drivers. Text is printed as ASCII; other
+//! characters are replaced with `?` because code pages differ between models.
+
+use chrono::Local;
+
+use super::TicketView;
+
+const ESC: u8 = 0x1B;
+const GS: u8 = 0x1D;
+
+/// Longest QR payload accepted by the `GS ( k` store command used here.
+pub const MAX_QR_DATA_LENGTH: usize = 700;
+
+/// Renders a ticket as an ESC/POS byte stream.
+///
+/// `header` is printed above the service name when non-empty and `status_url`
+/// is encoded as a QR code when present.
+pub fn render_ticket(view: &TicketView, header: &str, status_url: Option<&str>) -> Vec<u8> {
+    let ticket = &view.ticket;
+    let mut out = Vec::with_capacity(256);
+
+    out.extend_from_slice(&[ESC, b'@']); // initialise
+    out.extend_from_slice(&[ESC, b'a', 1]); // centre
+
+    if !header.trim().is_empty() {
+        text_line(&mut out, header.trim());
+    }
+
+    out.extend_from_slice(&[ESC, b'E', 1]); // bold on
+    text_line(&mut out, &ticket.service_name);
+    out.extend_from_slice(&[ESC, b'E', 0]);
+
+    out.extend_from_slice(&[GS, b'!', 0x33]); // 4x width and height
+    text_line(&mut out, &ticket.number);
+    out.extend_from_slice(&[GS, b'!', 0x00]);
+
+    let issued_local = ticket.issued_at.with_timezone(&Local);
+    text_line(&mut out, &issued_local.format("%Y-%m-%d %H:%M").to_string());
+    if let Some(due_at) = ticket.due_at {
+        let due_local = due_at.with_timezone(&Local);
+        text_line(
+            &mut out,
+            &format!("Appointment: {}", due_local.format("%H:%M")),
+        );
+    }
+    text_line(&mut out, &format!("People ahead: {}", view.people_ahead));
+
+    if let Some(url) = status_url.filter(|url| !url.is_empty() && url.len() <= MAX_QR_DATA_LENGTH) {
+        out.push(b'\n');
+        qr_code(&mut out, url.as_bytes());
+        out.push(b'\n');
+    }
+
+    out.extend_from_slice(&[ESC, b'd', 4]); // feed 4 lines
+    out.extend_from_slice(&[GS, b'V', 66, 0]); // feed to cutter and partial cut
+    out
+}
+
+fn text_line(out: &mut Vec<u8>, text: &str) {
+    out.extend(text.chars().map(|c| {
+        if c.is_ascii() && !c.is_ascii_control() {
+            c as u8
+        } else {
+            b'?'
+        }
+    }));
+    out.push(b'\n');
+}
+
+fn qr_code(out: &mut Vec<u8>, data: &[u8]) {
+    // Model 2, module size 6, error correction level M.
+    out.extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, 6]);
+    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, 49]);
+    let length = data.len() + 3;
+    out.extend_from_slice(&[
+        GS,
+        b'(',
+        b'k',
+        (length % 256) as u8,
+        (length / 256) as u8,
+        49,
+        80,
+        48,
+    ]);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]); // print stored symbol
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tickets::{Ticket, TicketStatus};
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn renders_number_details_qr_and_cut() {
+        let view = TicketView {
+            ticket: Ticket {
+                number: "A12".to_string(),
+                service: "A".to_string(),
+                service_name: "Pharmacy ยา".to_string(),
+                issued_at: chrono::Utc::now(),
+                status: TicketStatus::Waiting,
+                called_at: None,
+                location: None,
+                appointment_id: None,
+                due_at: None,
+                contact: None,
+                notifications: Vec::new(),
+            },
+            people_ahead: 3,
+        };
+        let bytes = render_ticket(&view, "", Some("https://q.example/t/A12"));
+
+        assert!(bytes.starts_with(&[ESC, b'@']));
+        assert!(contains(&bytes, b"A12\n"));
+        assert!(contains(&bytes, b"Pharmacy ??\n"));
+        assert!(contains(&bytes, b"People ahead: 3\n"));
+        // QR store command: length = 23 bytes of data + 3.
+        assert!(contains(&bytes, &[GS, b'(', b'k', 26, 0, 49, 80, 48]));
+        assert!(bytes.ends_with(&[GS, b'V', 66, 0]));
+
+        let without_qr = render_ticket(&view, "Clinic", None);
+        assert!(!contains(&without_qr, &[GS, b'(', b'k']));
+        assert!(contains(&without_qr, b"Clinic\n"));
+    }
+}
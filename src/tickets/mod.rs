@@ -0,0 +1,588 @@
+// src/tickets/mod.rs
+
+//! Ticket dispenser: numbers handed out to patients before they are called.
+//!
+//! Each configured service (see `TICKET_SERVICES`) has its own letter prefix
+//! and number sequence. Issued tickets wait in issue order until a call with the
+//! same number is made at a counter, at which point they are marked as called.
+//! When identifiers are digits only, all services share one sequence.
+//!
+//! Tickets issued at appointment check-in carry a due time; when choosing the
+//! next ticket to call, due appointments are preferred over walk-ins.
+//!
+//! Tickets can carry contact details used for notifications (see
+//! [`crate::notifications`]); the book records each notification's delivery.
+
+pub mod escpos;
+pub mod printer;
+
+pub use printer::{PrintError, TicketPrinter};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use crate::config::TicketServiceDefinition;
+use crate::notifications::{NotificationKind, NotificationRecord, NotificationStatus};
+
+/// Number of called tickets kept so their status page keeps working.
+pub const MAX_CALLED_TICKETS: usize = 200;
+
+/// Where a ticket is in its lifecycle.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketStatus {
+    Waiting,
+    Called,
+}
+
+/// A ticket handed out by the dispenser.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Ticket {
+    /// Number printed on the ticket and used when calling it (e.g. "A12").
+    pub number: String,
+    /// Prefix of the service the ticket was issued for.
+    pub service: String,
+    /// Display name of the service.
+    pub service_name: String,
+    pub issued_at: DateTime<Utc>,
+    pub status: TicketStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub called_at: Option<DateTime<Utc>>,
+    /// Counter the ticket was called to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Appointment this ticket was issued for at check-in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appointment_id: Option<String>,
+    /// Appointment time; the ticket is preferred by call-next once it has passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+    /// Contact details for notifications. Never exposed through the API.
+    #[serde(skip)]
+    pub contact: Option<String>,
+    /// Notifications sent for this ticket and their delivery status.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<NotificationRecord>,
+}
+
+/// A ticket together with its live position in the queue.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TicketView {
+    #[serde(flatten)]
+    pub ticket: Ticket,
+    /// Waiting tickets of the same sequence issued before this one.
+    pub people_ahead: usize,
+}
+
+/// Errors raised by the ticket dispenser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TicketError {
+    /// The requested service is not configured.
+    UnknownService(String),
+    /// `TICKET_SERVICES` contains no valid service.
+    NoServices,
+}
+
+impl fmt::Display for TicketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TicketError::UnknownService(service) => {
+                write!(f, "Unknown ticket service '{}'", service)
+            }
+            TicketError::NoServices => write!(f, "No ticket services are configured"),
+        }
+    }
+}
+
+impl std::error::Error for TicketError {}
+
+/// Issued tickets of one room.
+#[derive(Debug)]
+pub struct TicketBook {
+    services: Vec<TicketServiceDefinition>,
+    /// When true, numbers are digits only and every service shares one sequence.
+    numeric: bool,
+    next_sequence: HashMap<String, u64>,
+    /// Waiting tickets, oldest first.
+    waiting: Vec<Ticket>,
+    /// Recently called tickets, oldest first.
+    called: VecDeque<Ticket>,
+}
+
+impl TicketBook {
+    pub fn new(services: Vec<TicketServiceDefinition>, identifier_prefix_required: bool) -> Self {
+        Self {
+            services,
+            numeric: !identifier_prefix_required,
+            next_sequence: HashMap::new(),
+            waiting: Vec::new(),
+            called: VecDeque::new(),
+        }
+    }
+
+    /// Configured services, in configuration order.
+    pub fn services(&self) -> &[TicketServiceDefinition] {
+        &self.services
+    }
+
+    /// Issue the next ticket for a service. `None` selects the first service.
+    pub fn issue(
+        &mut self,
+        service: Option<&str>,
+        contact: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<TicketView, TicketError> {
+        self.issue_ticket(service, contact, None, now)
+    }
+
+    /// Issue a ticket for a checked-in appointment that is due at `due_at`.
+    pub fn issue_for_appointment(
+        &mut self,
+        service: Option<&str>,
+        contact: Option<String>,
+        appointment_id: &str,
+        due_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<TicketView, TicketError> {
+        self.issue_ticket(
+            service,
+            contact,
+            Some((appointment_id.to_string(), due_at)),
+            now,
+        )
+    }
+
+    fn issue_ticket(
+        &mut self,
+        service: Option<&str>,
+        contact: Option<String>,
+        appointment: Option<(String, DateTime<Utc>)>,
+        now: DateTime<Utc>,
+    ) -> Result<TicketView, TicketError> {
+        let definition = match service.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(prefix) => self
+                .services
+                .iter()
+                .find(|definition| definition.prefix.eq_ignore_ascii_case(prefix))
+                .ok_or_else(|| TicketError::UnknownService(prefix.to_string()))?,
+            None => self.services.first().ok_or(TicketError::NoServices)?,
+        }
+        .clone();
+
+        let sequence_key = self.sequence_key(&definition.prefix).to_string();
+        let sequence = self.next_sequence.entry(sequence_key.clone()).or_insert(0);
+        *sequence += 1;
+        let number = format!("{}{}", sequence_key, sequence);
+
+        let ticket = Ticket {
+            number,
+            service: definition.prefix,
+            service_name: definition.name,
+            issued_at: now,
+            status: TicketStatus::Waiting,
+            called_at: None,
+            location: None,
+            appointment_id: appointment.as_ref().map(|(id, _)| id.clone()),
+            due_at: appointment.map(|(_, due_at)| due_at),
+            contact: contact
+                .map(|contact| contact.trim().to_string())
+                .filter(|contact| !contact.is_empty()),
+            notifications: Vec::new(),
+        };
+        self.waiting.push(ticket.clone());
+        let people_ahead = self.people_ahead(&ticket);
+        Ok(TicketView {
+            ticket,
+            people_ahead,
+        })
+    }
+
+    /// Mark the waiting ticket matching a call as called. Returns the ticket when
+    /// one matched.
+    pub fn mark_called(
+        &mut self,
+        original_id: &str,
+        location: &str,
+        now: DateTime<Utc>,
+    ) -> Option<Ticket> {
+        let index = self
+            .waiting
+            .iter()
+            .position(|ticket| same_number(&ticket.number, original_id))?;
+        let mut ticket = self.waiting.remove(index);
+        ticket.status = TicketStatus::Called;
+        ticket.called_at = Some(now);
+        ticket.location = Some(location.to_string());
+        if self.called.len() >= MAX_CALLED_TICKETS {
+            self.called.pop_front();
+        }
+        self.called.push_back(ticket.clone());
+        Some(ticket)
+    }
+
+    /// Mark the ticket [`TicketBook::next_waiting`] picks as called to
+    /// `location`. Returns the ticket, or `None` when none is waiting.
+    pub fn take_next(
+        &mut self,
+        service: Option<&str>,
+        location: &str,
+        now: DateTime<Utc>,
+    ) -> Option<Ticket> {
+        let number = self.next_waiting(service, now)?;
+        self.mark_called(&number, location, now)
+    }
+
+    /// Put a ticket taken by [`TicketBook::take_next`] back in its place in
+    /// the waiting list, e.g. when its call could not be made. Returns whether
+    /// the ticket was found.
+    pub fn return_to_waiting(&mut self, number: &str) -> bool {
+        let Some(index) = self
+            .called
+            .iter()
+            .rposition(|ticket| same_number(&ticket.number, number))
+        else {
+            return false;
+        };
+        let Some(mut ticket) = self.called.remove(index) else {
+            return false;
+        };
+        ticket.status = TicketStatus::Waiting;
+        ticket.called_at = None;
+        ticket.location = None;
+        let position = self
+            .waiting
+            .iter()
+            .position(|waiting| waiting.issued_at > ticket.issued_at)
+            .unwrap_or(self.waiting.len());
+        self.waiting.insert(position, ticket);
+        true
+    }
+
+    /// Look up a waiting or recently called ticket by number.
+    pub fn get(&self, number: &str) -> Option<TicketView> {
+        if let Some(ticket) = self
+            .waiting
+            .iter()
+            .find(|ticket| same_number(&ticket.number, number))
+        {
+            return Some(TicketView {
+                ticket: ticket.clone(),
+                people_ahead: self.people_ahead(ticket),
+            });
+        }
+        self.called
+            .iter()
+            .rev()
+            .find(|ticket| same_number(&ticket.number, number))
+            .map(|ticket| TicketView {
+                ticket: ticket.clone(),
+                people_ahead: 0,
+            })
+    }
+
+    /// Waiting tickets in issue order.
+    pub fn waiting(&self) -> Vec<TicketView> {
+        self.waiting
+            .iter()
+            .map(|ticket| TicketView {
+                ticket: ticket.clone(),
+                people_ahead: self.people_ahead(ticket),
+            })
+            .collect()
+    }
+
+    /// Number of the waiting ticket that should be called next, optionally
+    /// limited to one service.
+    ///
+    /// Appointments whose time has come are called first (earliest appointment
+    /// first), then walk-ins in issue order. Appointments that are not yet due
+    /// are only called when no walk-in is waiting.
+    pub fn next_waiting(&self, service: Option<&str>, now: DateTime<Utc>) -> Option<String> {
+        let candidates: Vec<&Ticket> = self
+            .waiting
+            .iter()
+            .filter(|ticket| {
+                service.is_none_or(|prefix| ticket.service.eq_ignore_ascii_case(prefix))
+            })
+            .collect();
+        let due = candidates
+            .iter()
+            .filter(|ticket| ticket.due_at.is_some_and(|due_at| due_at <= now))
+            .min_by_key(|ticket| ticket.due_at);
+        let walk_in = candidates.iter().find(|ticket| ticket.due_at.is_none());
+        let earliest = candidates.iter().min_by_key(|ticket| ticket.due_at);
+        due.or(walk_in)
+            .or(earliest)
+            .map(|ticket| ticket.number.clone())
+    }
+
+    /// Numbers of waiting tickets with contact details that have at most
+    /// `max_people_ahead` people ahead and have not been notified about it yet.
+    pub fn approaching(&self, max_people_ahead: usize) -> Vec<String> {
+        self.waiting
+            .iter()
+            .filter(|ticket| {
+                ticket.contact.is_some()
+                    && !has_notification(ticket, NotificationKind::Approaching)
+                    && self.people_ahead(ticket) <= max_people_ahead
+            })
+            .map(|ticket| ticket.number.clone())
+            .collect()
+    }
+
+    /// Records a pending notification of `kind` for a ticket with contact
+    /// details. Returns the ticket, or `None` when it has no contact details or
+    /// was already notified about this.
+    pub fn begin_notification(
+        &mut self,
+        number: &str,
+        kind: NotificationKind,
+        now: DateTime<Utc>,
+    ) -> Option<TicketView> {
+        let view = self.get(number)?;
+        if view.ticket.contact.is_none() || has_notification(&view.ticket, kind) {
+            return None;
+        }
+        let ticket = self.find_mut(number)?;
+        ticket.notifications.push(NotificationRecord {
+            kind,
+            status: NotificationStatus::Pending,
+            updated_at: now,
+            error: None,
+        });
+        Some(view)
+    }
+
+    /// Stores the delivery outcome of a notification started with
+    /// [`TicketBook::begin_notification`].
+    pub fn finish_notification(
+        &mut self,
+        number: &str,
+        kind: NotificationKind,
+        error: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        let Some(record) = self.find_mut(number).and_then(|ticket| {
+            ticket
+                .notifications
+                .iter_mut()
+                .rev()
+                .find(|record| record.kind == kind)
+        }) else {
+            return;
+        };
+        record.status = if error.is_none() {
+            NotificationStatus::Sent
+        } else {
+            NotificationStatus::Failed
+        };
+        record.error = error;
+        record.updated_at = now;
+    }
+
+    fn find_mut(&mut self, number: &str) -> Option<&mut Ticket> {
+        self.waiting
+            .iter_mut()
+            .chain(self.called.iter_mut().rev())
+            .find(|ticket| same_number(&ticket.number, number))
+    }
+
+    fn people_ahead(&self, ticket: &Ticket) -> usize {
+        self.waiting
+            .iter()
+            .take_while(|other| other.number != ticket.number)
+            .filter(|other| self.numeric || other.service == ticket.service)
+            .count()
+    }
+
+    fn sequence_key<'a>(&self, prefix: &'a str) -> &'a str {
+        if self.numeric {
+            ""
+        } else {
+            prefix
+        }
+    }
+}
+
+fn has_notification(ticket: &Ticket, kind: NotificationKind) -> bool {
+    ticket
+        .notifications
+        .iter()
+        .any(|record| record.kind == kind)
+}
+
+/// Compares ticket numbers ignoring case and leading zeros (`A07` == `a7`).
+pub fn same_number(left: &str, right: &str) -> bool {
+    fn split(number: &str) -> (String, Option<u64>) {
+        let number = number.trim();
+        let digits_start = number
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(number.len());
+        let (prefix, digits) = number.split_at(digits_start);
+        (prefix.to_ascii_uppercase(), digits.parse().ok())
+    }
+    let (left_prefix, left_digits) = split(left);
+    let (right_prefix, right_digits) = split(right);
+    left_digits.is_some() && left_prefix == right_prefix && left_digits == right_digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services() -> Vec<TicketServiceDefinition> {
+        vec![
+            TicketServiceDefinition {
+                prefix: "A".to_string(),
+                name: "General".to_string(),
+            },
+            TicketServiceDefinition {
+                prefix: "B".to_string(),
+                name: "Pharmacy".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn issues_numbers_per_service_and_tracks_people_ahead() {
+        let now = Utc::now();
+        let mut book = TicketBook::new(services(), true);
+        assert_eq!(book.issue(None, None, now).unwrap().ticket.number, "A1");
+        assert_eq!(
+            book.issue(Some("b"), None, now).unwrap().ticket.number,
+            "B1"
+        );
+        let third = book.issue(Some("A"), None, now).unwrap();
+        assert_eq!(third.ticket.number, "A2");
+        assert_eq!(
+            third.people_ahead, 1,
+            "only A1 is ahead; B1 is another line"
+        );
+        assert_eq!(
+            book.issue(Some("Z"), None, now),
+            Err(TicketError::UnknownService("Z".to_string()))
+        );
+
+        let called = book.mark_called("A01", "3", now).unwrap();
+        assert_eq!(called.number, "A1");
+        assert_eq!(book.get("A2").unwrap().people_ahead, 0);
+        assert_eq!(book.get("a1").unwrap().ticket.status, TicketStatus::Called);
+        assert!(book.mark_called("A1", "3", now).is_none());
+        assert_eq!(book.waiting().len(), 2);
+    }
+
+    #[test]
+    fn tracks_notifications_for_tickets_with_contact() {
+        let now = Utc::now();
+        let mut book = TicketBook::new(services(), true);
+        book.issue(None, None, now).unwrap();
+        book.issue(None, Some("+66811111111".to_string()), now)
+            .unwrap();
+        book.issue(None, Some(" ".to_string()), now).unwrap();
+
+        assert!(book.approaching(0).is_empty());
+        assert_eq!(book.approaching(1), vec!["A2".to_string()]);
+        assert!(book
+            .begin_notification("A1", NotificationKind::Approaching, now)
+            .is_none());
+        assert!(book
+            .begin_notification("A3", NotificationKind::Approaching, now)
+            .is_none());
+        let view = book
+            .begin_notification("A2", NotificationKind::Approaching, now)
+            .unwrap();
+        assert_eq!(view.people_ahead, 1);
+        assert!(book.approaching(1).is_empty());
+        book.finish_notification("A2", NotificationKind::Approaching, None, now);
+
+        book.mark_called("A2", "5", now);
+        assert!(book
+            .begin_notification("A2", NotificationKind::Called, now)
+            .is_some());
+        book.finish_notification(
+            "A2",
+            NotificationKind::Called,
+            Some("HTTP 500".to_string()),
+            now,
+        );
+        let statuses: Vec<_> = book
+            .get("A2")
+            .unwrap()
+            .ticket
+            .notifications
+            .iter()
+            .map(|record| (record.kind, record.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (NotificationKind::Approaching, NotificationStatus::Sent),
+                (NotificationKind::Called, NotificationStatus::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_waiting_prefers_due_appointments() {
+        let now = Utc::now();
+        let minutes = chrono::Duration::minutes;
+        let mut book = TicketBook::new(services(), true);
+        book.issue(None, None, now).unwrap(); // A1 walk-in
+        book.issue_for_appointment(None, None, "late", now + minutes(30), now)
+            .unwrap(); // A2, not due yet
+        book.issue_for_appointment(None, None, "due", now - minutes(5), now)
+            .unwrap(); // A3, due
+        book.issue(Some("B"), None, now).unwrap(); // B1 walk-in
+
+        assert_eq!(book.next_waiting(None, now).as_deref(), Some("A3"));
+        assert_eq!(book.next_waiting(Some("b"), now).as_deref(), Some("B1"));
+        book.mark_called("A3", "1", now);
+        assert_eq!(book.next_waiting(None, now).as_deref(), Some("A1"));
+        book.mark_called("A1", "1", now);
+        assert_eq!(book.next_waiting(Some("A"), now).as_deref(), Some("A2"));
+        assert_eq!(
+            book.next_waiting(None, now + minutes(31)).as_deref(),
+            Some("A2")
+        );
+        assert_eq!(
+            book.get("A2").unwrap().ticket.appointment_id.as_deref(),
+            Some("late")
+        );
+        assert!(book.next_waiting(Some("C"), now).is_none());
+    }
+
+    #[test]
+    fn take_next_marks_each_ticket_called_once() {
+        let now = Utc::now();
+        let mut book = TicketBook::new(services(), true);
+        book.issue(None, None, now).unwrap();
+        book.issue(Some("B"), None, now).unwrap();
+
+        let taken = book.take_next(Some("A"), "2", now).unwrap();
+        assert_eq!(taken.number, "A1");
+        assert_eq!(taken.status, TicketStatus::Called);
+        assert_eq!(taken.location.as_deref(), Some("2"));
+        assert!(book.take_next(Some("A"), "3", now).is_none());
+        assert_eq!(book.take_next(None, "3", now).unwrap().number, "B1");
+        assert!(book.take_next(None, "3", now).is_none());
+
+        assert!(book.return_to_waiting("A1"));
+        assert!(!book.return_to_waiting("A1"));
+        let returned = book.get("A1").unwrap();
+        assert_eq!(returned.ticket.status, TicketStatus::Waiting);
+        assert_eq!(returned.ticket.location, None);
+        assert_eq!(book.next_waiting(None, now).as_deref(), Some("A1"));
+    }
+
+    #[test]
+    fn numeric_mode_shares_one_sequence() {
+        let now = Utc::now();
+        let mut book = TicketBook::new(services(), false);
+        assert_eq!(book.issue(Some("A"), None, now).unwrap().ticket.number, "1");
+        let second = book.issue(Some("B"), None, now).unwrap();
+        assert_eq!(second.ticket.number, "2");
+        assert_eq!(second.people_ahead, 1);
+    }
+}
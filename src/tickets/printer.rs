@@ -0,0 +1,162 @@
+// src/tickets/printer.rs
+
+//! Destinations for rendered tickets.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// Default port of raw (JetDirect) network printers.
+pub const DEFAULT_PRINTER_PORT: u16 = 9100;
+/// Upper bound for connecting to and writing to a network printer.
+pub const PRINTER_TIMEOUT_SECONDS: u64 = 5;
+
+/// Where printed tickets are sent, parsed from `TICKET_PRINTER`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TicketPrinter {
+    /// Raw TCP printer, `tcp://host[:port]` (port defaults to 9100).
+    Tcp { host: String, port: u16 },
+    /// Byte streams appended to a file, `file:/path/to/tickets.bin`. Meant for testing.
+    File(PathBuf),
+}
+
+/// Errors raised while configuring or using the ticket printer.
+#[derive(Debug)]
+pub enum PrintError {
+    /// `TICKET_PRINTER` is not a recognised destination.
+    InvalidTarget(String),
+    /// The printer or file could not be written.
+    Io(std::io::Error),
+    /// The network printer did not accept the ticket in time.
+    Timeout,
+}
+
+impl fmt::Display for PrintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrintError::InvalidTarget(raw) => write!(
+                f,
+                "Invalid ticket printer '{}'. Use tcp://host[:port] or file:/path",
+                raw
+            ),
+            PrintError::Io(err) => write!(f, "Printer I/O error: {}", err),
+            PrintError::Timeout => write!(
+                f,
+                "Printer did not respond within {}s",
+                PRINTER_TIMEOUT_SECONDS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PrintError {}
+
+impl From<std::io::Error> for PrintError {
+    fn from(err: std::io::Error) -> Self {
+        PrintError::Io(err)
+    }
+}
+
+impl TicketPrinter {
+    /// Parses `tcp://host[:port]` or `file:/path`. Returns `Ok(None)` for an empty value.
+    pub fn parse(raw: &str) -> Result<Option<Self>, PrintError> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        if let Some(address) = raw.strip_prefix("tcp://") {
+            let address = address.trim_end_matches('/');
+            let (host, port) = match address.rsplit_once(':') {
+                Some((host, port)) => (
+                    host,
+                    port.parse()
+                        .map_err(|_| PrintError::InvalidTarget(raw.to_string()))?,
+                ),
+                None => (address, DEFAULT_PRINTER_PORT),
+            };
+            if host.is_empty() {
+                return Err(PrintError::InvalidTarget(raw.to_string()));
+            }
+            return Ok(Some(TicketPrinter::Tcp {
+                host: host.to_string(),
+                port,
+            }));
+        }
+        if let Some(path) = raw.strip_prefix("file:") {
+            let path = path.strip_prefix("//").unwrap_or(path);
+            if path.is_empty() {
+                return Err(PrintError::InvalidTarget(raw.to_string()));
+            }
+            return Ok(Some(TicketPrinter::File(PathBuf::from(path))));
+        }
+        Err(PrintError::InvalidTarget(raw.to_string()))
+    }
+
+    /// Sends a rendered ticket to the printer.
+    pub async fn print(&self, bytes: &[u8]) -> Result<(), PrintError> {
+        match self {
+            TicketPrinter::Tcp { host, port } => {
+                let send = async {
+                    let mut stream = TcpStream::connect((host.as_str(), *port)).await?;
+                    stream.write_all(bytes).await?;
+                    stream.shutdown().await
+                };
+                tokio::time::timeout(Duration::from_secs(PRINTER_TIMEOUT_SECONDS), send)
+                    .await
+                    .map_err(|_| PrintError::Timeout)??;
+            }
+            TicketPrinter::File(path) => {
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?;
+                file.write_all(bytes).await?;
+                file.flush().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TicketPrinter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TicketPrinter::Tcp { host, port } => write!(f, "tcp://{}:{}", host, port),
+            TicketPrinter::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_printer_targets() {
+        assert_eq!(TicketPrinter::parse("").unwrap(), None);
+        assert_eq!(
+            TicketPrinter::parse("tcp://10.0.0.9").unwrap(),
+            Some(TicketPrinter::Tcp {
+                host: "10.0.0.9".to_string(),
+                port: DEFAULT_PRINTER_PORT,
+            })
+        );
+        assert_eq!(
+            TicketPrinter::parse("tcp://printer.local:9101").unwrap(),
+            Some(TicketPrinter::Tcp {
+                host: "printer.local".to_string(),
+                port: 9101,
+            })
+        );
+        assert_eq!(
+            TicketPrinter::parse("file:///tmp/tickets.bin").unwrap(),
+            Some(TicketPrinter::File(PathBuf::from("/tmp/tickets.bin")))
+        );
+        assert!(TicketPrinter::parse("lpt1").is_err());
+        assert!(TicketPrinter::parse("tcp://host:port").is_err());
+    }
+}
@@ -13,10 +13,12 @@
 //! - Implementing retry logic for robust TTS generation with exponential backoff.
 //! - Fallback to offline stem audio files when online TTS fails or times out.
 
+use chrono::{DateTime, Utc};
 use fake_user_agent::get_rua;
 use regex::Regex;
 use reqwest::header::USER_AGENT;
 use reqwest::Client as ReqwestClient;
+use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
@@ -30,9 +32,11 @@ use urlencoding::encode as url_encode;
 
 use crate::{
     config::{
-        normalize_language_code, AppConfig, DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_EN,
+        normalize_language_code, AppConfig, SharedConfig, DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_EN,
         DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_TH,
     },
+    shutdown::InFlight,
+    tts::stems,
     AppEvent,
 };
 
@@ -54,6 +58,51 @@ const TTS_TEMPLATE_QUEUE_NUMBER_TOKEN: &str = "{Q_NUM}";
 /// Placeholder token for the destination/counter in configurable live TTS templates.
 const TTS_TEMPLATE_DESTINATION_TOKEN: &str = "{DEST_NUM}";
 
+/// Outcome of the most recent online TTS generations, reported by `/api/diagnostics`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OnlineTtsStatus {
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// Why the last failed generation fell back to stem audio.
+    pub last_failure: Option<String>,
+}
+
+/// Languages with their own live TTS template and audio stems. Every other
+/// configured language is announced with the English ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AnnouncementLanguage {
+    Thai,
+    English,
+}
+
+impl AnnouncementLanguage {
+    /// The language a configured code maps to, or `None` when it has no
+    /// template or stems of its own.
+    pub(crate) fn for_code(lang: &str) -> Option<Self> {
+        match normalize_language_code(lang).to_ascii_lowercase().as_str() {
+            "th" | "th-th" => Some(Self::Thai),
+            "en-uk" | "en-us" | "en-gb" | "en" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    /// Directory below `media/audio_stems` holding the language's stems.
+    pub(crate) fn stem_dir(self) -> &'static str {
+        match self {
+            Self::Thai => "th",
+            Self::English => "en-GB",
+        }
+    }
+
+    /// Environment variable holding the language's live TTS template.
+    pub(crate) fn template_variable(self) -> &'static str {
+        match self {
+            Self::Thai => "TTS_ANNOUNCEMENT_TEMPLATE_TH",
+            Self::English => "TTS_ANNOUNCEMENT_TEMPLATE_EN",
+        }
+    }
+}
+
 /// Manages Text-to-Speech (TTS) operations, including fetching audio from
 /// external services, caching, text tokenization, and fallback to stem audio.
 ///
@@ -61,17 +110,19 @@ const TTS_TEMPLATE_DESTINATION_TOKEN: &str = "{DEST_NUM}";
 /// and state for handling TTS requests and caching.
 #[derive(Debug)]
 pub struct TTSManager {
-    /// Application configuration, providing settings like cache paths and timeouts.
-    config: Arc<AppConfig>,
+    /// Live application configuration, providing settings like cache paths and templates.
+    config: SharedConfig,
     /// HTTP client used to make requests to the Google TTS API.
     http_client: ReqwestClient,
     /// Sender for the application-wide event bus, used to broadcast `TTSComplete` events.
     event_bus_sender: broadcast::Sender<AppEvent>,
-    /// A map of supported language codes to their display names, parsed from `AppConfig`.
-    supported_languages_map: HashMap<String, String>,
     /// A mutex-protected HashSet to store user agents recently used for TTS requests,
     /// to avoid immediate repetition and potential rate limiting.
     last_call_uas: Arc<Mutex<HashSet<String>>>,
+    /// Tracks generation tasks so a graceful shutdown waits for their cache writes.
+    in_flight: InFlight,
+    /// When online generation last succeeded and failed.
+    online_status: Arc<std::sync::Mutex<OnlineTtsStatus>>,
 }
 
 impl TTSManager {
@@ -82,14 +133,21 @@ impl TTSManager {
     /// directory exists asynchronously.
     ///
     /// # Arguments
-    /// - `config`: An `Arc` to the shared application configuration.
+    /// - `config`: The shared application configuration.
     /// - `event_bus_sender`: A `broadcast::Sender` for `AppEvent`s, used to notify
     ///   other parts of the application when TTS generation is complete.
+    /// - `in_flight`: Tracker a graceful shutdown waits on; every generation task
+    ///   is registered with it.
     ///
     /// # Returns
     /// A new `TTSManager` instance.
-    pub fn new(config: Arc<AppConfig>, event_bus_sender: broadcast::Sender<AppEvent>) -> Self {
+    pub fn new(
+        shared_config: SharedConfig,
+        event_bus_sender: broadcast::Sender<AppEvent>,
+        in_flight: InFlight,
+    ) -> Self {
         info!("Initializing TTSManager for Google TTS with stem audio fallback...");
+        let config = shared_config.get();
         debug!("TTSManager new: Using config: {:?}", config); // Debug config at init
 
         // Build the HTTP client with a configured timeout.
@@ -109,51 +167,22 @@ impl TTSManager {
             config.tts_external_service_timeout()
         );
 
-        // Parse supported languages from the configuration string into a HashMap.
-        // This allows quick lookup of language support.
-        let supported_languages_map: HashMap<String, String> = config
-            .tts_supported_languages
-            .split(',') // Split by comma-separated entries
-            .filter_map(|entry| {
-                let trimmed_entry = entry.trim();
-                if trimmed_entry.is_empty() {
-                    trace!("TTSManager new: Skipping empty language entry");
-                    return None;
-                }
-
-                let mut parts = trimmed_entry.splitn(2, ':');
-                let code_raw = parts.next().map(str::trim).unwrap_or("");
-                if code_raw.is_empty() {
-                    trace!(
-                        "TTSManager new: Skipping language entry with empty code: {:?}",
-                        entry
-                    );
-                    return None;
-                }
-
-                let normalized_code = normalize_language_code(code_raw);
-
-                let display_name = parts
-                    .next()
-                    .map(str::trim)
-                    .filter(|name| !name.is_empty())
-                    .map(str::to_string)
-                    .unwrap_or_else(|| code_raw.to_string());
-
-                trace!(
-                    "TTSManager new: Parsed language '{}' (display '{}')",
-                    normalized_code,
-                    display_name
-                );
-
-                Some((normalized_code, display_name))
-            })
-            .collect();
         info!(
             "Supported TTS languages (from config): {:?}",
-            supported_languages_map
+            Self::parse_supported_languages(&config.tts_supported_languages)
         );
 
+        for inventory in stems::inventory(&config) {
+            if !inventory.missing.is_empty() {
+                warn!(
+                    "Stem audio for '{}' in {:?} lacks {}; the stem fallback skips these segments.",
+                    inventory.language,
+                    inventory.directory,
+                    inventory.missing.join(", ")
+                );
+            }
+        }
+
         info!("User agents will be fetched using fake_user_agent::get_rua() with non-repetition logic.");
 
         // Ensure the TTS audio cache directory exists. This is done in a separate
@@ -179,11 +208,12 @@ impl TTSManager {
         });
 
         TTSManager {
-            config,
+            config: shared_config,
             http_client,
             event_bus_sender,
-            supported_languages_map,
             last_call_uas: Arc::new(Mutex::new(HashSet::new())), // Initialize set for tracking recent UAs
+            in_flight,
+            online_status: Arc::default(),
         }
     }
 
@@ -216,7 +246,8 @@ impl TTSManager {
         );
 
         // Validate if the requested language is supported.
-        if !self.supported_languages_map.contains_key(&lang) {
+        let config = self.config.get();
+        if !Self::parse_supported_languages(&config.tts_supported_languages).contains_key(&lang) {
             let error_msg = format!("Unsupported language for TTS: {}", lang);
             warn!("{}", error_msg);
             return Err(error_msg);
@@ -224,10 +255,13 @@ impl TTSManager {
         debug!("Trigger TTS Generation: Language '{}' is supported.", lang);
 
         // Clone necessary data for the spawned asynchronous task.
-        let config_clone = Arc::clone(&self.config);
+        let config_clone = config;
         let http_client_clone = self.http_client.clone();
         let sender_clone = self.event_bus_sender.clone();
         let last_call_uas_clone = Arc::clone(&self.last_call_uas);
+        let online_status_clone = Arc::clone(&self.online_status);
+        let zone = self.config.zone_for_location(&location);
+        let work = self.in_flight.start();
 
         // Capture parameters for the spawned task.
         let task_id = id;
@@ -238,13 +272,16 @@ impl TTSManager {
         // This prevents blocking the main thread/Axum handler.
         debug!("Trigger TTS Generation: Spawning async task for TTS generation.");
         task::spawn(async move {
+            let _work = work;
             Self::perform_tts_task_with_fallback(
                 config_clone,
                 http_client_clone,
                 sender_clone,
                 last_call_uas_clone,
+                online_status_clone,
                 task_id,
                 task_location,
+                zone,
                 task_lang,
             )
             .await;
@@ -410,16 +447,21 @@ impl TTSManager {
     /// - `http_client`: Reqwest HTTP client for API calls.
     /// - `sender`: Event bus sender to broadcast completion events.
     /// - `last_call_uas_lock`: Mutex-protected HashSet for recent user agents.
+    /// - `online_status`: Updated with the outcome of online generation.
     /// - `id`: Call ID.
     /// - `location`: Call location.
+    /// - `zone`: Display zone of `location`, attached to the completion event.
     /// - `lang`: Language code (used for event metadata; generation uses all configured languages).
+    #[allow(clippy::too_many_arguments)]
     async fn perform_tts_task_with_fallback(
         config: Arc<AppConfig>,
         http_client: ReqwestClient,
         sender: broadcast::Sender<AppEvent>,
         last_call_uas_lock: Arc<Mutex<HashSet<String>>>,
+        online_status: Arc<std::sync::Mutex<OnlineTtsStatus>>,
         id: String,
         location: String,
+        zone: Option<String>,
         lang: String,
     ) {
         debug!(
@@ -464,6 +506,7 @@ impl TTSManager {
                         location: location.clone(),
                         lang: lang.clone(),
                         audio_urls: vec![audio_url],
+                        zone,
                     };
                     if let Err(e) = sender.send(event) {
                         debug!("Failed to broadcast TTSComplete for pre-generated TTS audio (id: {}, lang: {}): {}", id, lang, e);
@@ -489,7 +532,7 @@ impl TTSManager {
         let ordered_lang_codes = config.ordered_supported_language_codes_raw();
         if ordered_lang_codes.is_empty() {
             warn!("No supported languages configured, falling back to stem audio");
-            Self::fallback_to_stem_audio(&config, &sender, &id, &location, &lang).await;
+            Self::fallback_to_stem_audio(&config, &sender, &id, &location, zone, &lang).await;
             return;
         }
 
@@ -529,6 +572,7 @@ impl TTSManager {
                     location: location.clone(),
                     lang: lang.clone(),
                     audio_urls: vec![audio_url],
+                    zone,
                 };
                 if let Err(e) = sender.send(event) {
                     debug!("Failed to broadcast TTSComplete for cached TTS audio (id: {}, lang: {}): {}", id, lang, e);
@@ -562,6 +606,24 @@ impl TTSManager {
         )
         .await;
 
+        {
+            let mut status = online_status
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            match &online_tts_result {
+                Ok(Ok(_)) => status.last_success_at = Some(Utc::now()),
+                Ok(Err(online_error)) => {
+                    status.last_failure_at = Some(Utc::now());
+                    status.last_failure = Some(online_error.clone());
+                }
+                Err(_) => {
+                    status.last_failure_at = Some(Utc::now());
+                    status.last_failure =
+                        Some(format!("timed out after {}s", ONLINE_TTS_TIMEOUT_SECONDS));
+                }
+            }
+        }
+
         match online_tts_result {
             Ok(Ok(audio_url)) => {
                 // Online TTS succeeded.
@@ -574,6 +636,7 @@ impl TTSManager {
                     location: location.clone(),
                     lang: lang.clone(),
                     audio_urls: vec![audio_url],
+                    zone,
                 };
                 if let Err(e) = sender.send(event) {
                     debug!("Failed to broadcast TTSComplete for online TTS audio (id: {}, lang: {}): {}", id, lang, e);
@@ -582,18 +645,19 @@ impl TTSManager {
             Ok(Err(online_error)) => {
                 // Online TTS failed.
                 warn!("Online multi-language TTS generation failed for call id '{}': {}. Falling back to stem audio.", id, online_error);
-                Self::fallback_to_stem_audio(&config, &sender, &id, &location, &lang).await;
+                Self::fallback_to_stem_audio(&config, &sender, &id, &location, zone, &lang).await;
             }
             Err(_timeout_error) => {
                 // Online TTS timed out.
                 warn!("Online multi-language TTS generation timed out after {}s for call id '{}'. Falling back to stem audio.", ONLINE_TTS_TIMEOUT_SECONDS, id);
-                Self::fallback_to_stem_audio(&config, &sender, &id, &location, &lang).await;
+                Self::fallback_to_stem_audio(&config, &sender, &id, &location, zone, &lang).await;
             }
         }
     }
 
     /// Performs online TTS generation for all supported languages.
     /// This generates TTS for each language in order and concatenates them into a single file.
+    #[allow(clippy::too_many_arguments)]
     async fn perform_online_multi_language_tts_generation(
         config: Arc<AppConfig>,
         http_client: ReqwestClient,
@@ -897,6 +961,7 @@ impl TTSManager {
         sender: &broadcast::Sender<AppEvent>,
         id: &str,
         location: &str,
+        zone: Option<String>,
         lang: &str,
     ) {
         info!(
@@ -904,7 +969,11 @@ impl TTSManager {
             id, location, lang
         );
 
-        let audio_urls = Self::build_stem_audio_urls_for_all_languages(config, id, location);
+        let audio_urls = Self::existing_stem_audio_urls(
+            &config.serve_dir_path,
+            Self::build_stem_audio_urls_for_all_languages(config, id, location),
+        )
+        .await;
 
         if audio_urls.is_empty() {
             error!(
@@ -925,6 +994,7 @@ impl TTSManager {
             location: location.to_string(),
             lang: lang.to_string(),
             audio_urls,
+            zone,
         };
 
         if let Err(e) = sender.send(event) {
@@ -935,6 +1005,31 @@ impl TTSManager {
         }
     }
 
+    /// Keeps the stem URLs whose files exist below `serve_dir`, so a missing
+    /// stem is skipped instead of stalling the display's playlist on a 404.
+    async fn existing_stem_audio_urls(serve_dir: &Path, audio_urls: Vec<String>) -> Vec<String> {
+        let mut existing = Vec::with_capacity(audio_urls.len());
+        let mut missing = Vec::new();
+        for url in audio_urls {
+            if tokio_fs::metadata(stems::url_to_path(serve_dir, &url))
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+            {
+                existing.push(url);
+            } else {
+                missing.push(url);
+            }
+        }
+        if !missing.is_empty() {
+            warn!(
+                "Skipping {} missing stem audio file(s): {:?}",
+                missing.len(),
+                missing
+            );
+        }
+        existing
+    }
+
     /// Builds stem audio URLs for a given call across ALL supported languages.
     ///
     /// For example, call "A01" to counter "4" with supported languages "th,en-uk" would generate:
@@ -1000,15 +1095,14 @@ impl TTSManager {
         let mut audio_urls = Vec::new();
 
         // Map language codes to stem directory names
-        let stem_lang_dir = match lang.to_lowercase().as_str() {
-            "th" | "th-th" => "th",
-            "en-uk" | "en-us" | "en-gb" | "en" => "en-GB",
-            _ => {
+        let stem_lang_dir = match AnnouncementLanguage::for_code(lang) {
+            Some(language) => language.stem_dir(),
+            None => {
                 warn!(
                     "Unsupported language '{}' for stem audio, defaulting to 'en-GB'",
                     lang
                 );
-                "en-GB"
+                AnnouncementLanguage::English.stem_dir()
             }
         };
 
@@ -1039,13 +1133,22 @@ impl TTSManager {
                 }
             }
         } else {
-            warn!("Could not parse call ID '{}' for stem audio generation", id);
-            // Fallback: try to use the ID as-is
-            audio_urls.push(format!(
-                "{}/fallback_{}.mp3",
-                base_stem_path,
-                id.to_lowercase()
-            ));
+            warn!(
+                "Could not parse call ID '{}' for stem audio generation; spelling it out",
+                id
+            );
+            // Spell out the letters and digits; other characters have no stem.
+            for c in id.chars() {
+                if let Some(digit_value) = c.to_digit(10) {
+                    audio_urls.push(format!("{}/number_{:03}.mp3", base_stem_path, digit_value));
+                } else if c.is_ascii_alphabetic() {
+                    audio_urls.push(format!(
+                        "{}/char_{}.mp3",
+                        base_stem_path,
+                        c.to_ascii_lowercase()
+                    ));
+                }
+            }
         }
 
         // Add "phrase_to_counter.mp3" (e.g., "to counter" in English, "เชิญช่อง" in Thai)
@@ -1148,31 +1251,24 @@ impl TTSManager {
             "Build Speak Text: For ID: '{}', Location: '{}', Lang: '{}'",
             id, location, lang
         );
-        let normalized_lang = normalize_language_code(lang);
-        let (configured_template, default_template, template_env_var) =
-            match normalized_lang.to_ascii_lowercase().as_str() {
-                "th" | "th-th" => (
-                    config.tts_announcement_template_th.as_str(),
-                    DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_TH,
-                    "TTS_ANNOUNCEMENT_TEMPLATE_TH",
-                ),
-                "en-uk" | "en-us" | "en-gb" | "en" => (
-                    config.tts_announcement_template_en.as_str(),
-                    DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_EN,
-                    "TTS_ANNOUNCEMENT_TEMPLATE_EN",
-                ),
-                _ => {
-                    warn!(
-                        "Using English live TTS template for unmapped lang code '{}'.",
-                        lang
-                    );
-                    (
-                        config.tts_announcement_template_en.as_str(),
-                        DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_EN,
-                        "TTS_ANNOUNCEMENT_TEMPLATE_EN",
-                    )
-                }
-            };
+        let language = AnnouncementLanguage::for_code(lang).unwrap_or_else(|| {
+            warn!(
+                "Using English live TTS template for unmapped lang code '{}'.",
+                lang
+            );
+            AnnouncementLanguage::English
+        });
+        let (configured_template, default_template) = match language {
+            AnnouncementLanguage::Thai => (
+                config.tts_announcement_template_th.as_str(),
+                DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_TH,
+            ),
+            AnnouncementLanguage::English => (
+                config.tts_announcement_template_en.as_str(),
+                DEFAULT_TTS_ANNOUNCEMENT_TEMPLATE_EN,
+            ),
+        };
+        let template_env_var = language.template_variable();
 
         match Self::render_announcement_template(configured_template, id, location) {
             Ok(text) => {
@@ -1196,7 +1292,7 @@ impl TTSManager {
         }
     }
 
-    fn render_announcement_template(
+    pub(crate) fn render_announcement_template(
         template: &str,
         id: &str,
         location: &str,
@@ -1228,6 +1324,10 @@ impl TTSManager {
 
     /// Asynchronously writes a byte slice to a file at the specified path.
     ///
+    /// The bytes go to a `.part` file that is renamed into place once complete,
+    /// so an interrupted write never leaves a truncated file that later counts
+    /// as a cache hit.
+    ///
     /// # Arguments
     /// - `file_path`: The `Path` to the file to write.
     /// - `bytes`: The slice of bytes to write to the file.
@@ -1240,8 +1340,14 @@ impl TTSManager {
             bytes.len(),
             file_path
         );
-        let mut file = tokio_fs::File::create(file_path).await?;
+        let mut partial_name = file_path.file_name().unwrap_or_default().to_os_string();
+        partial_name.push(".part");
+        let partial_path = file_path.with_file_name(partial_name);
+        let mut file = tokio_fs::File::create(&partial_path).await?;
         file.write_all(bytes).await?;
+        file.flush().await?;
+        drop(file);
+        tokio_fs::rename(&partial_path, file_path).await?;
         trace!("Write Cache File: Successfully wrote file: {:?}", file_path);
         Ok(())
     }
@@ -1377,18 +1483,12 @@ impl TTSManager {
         );
 
         // Remove the oldest files.
-        for i in 0..num_to_remove {
-            debug!(
-                "Prune Cache: Attempting to remove file: {:?}",
-                files_with_time[i].1
-            );
-            if let Err(e) = tokio_fs::remove_file(&files_with_time[i].1).await {
-                error!(
-                    "Failed to remove old cache file {:?}: {}",
-                    files_with_time[i].1, e
-                );
+        for (_, file_path) in files_with_time.iter().take(num_to_remove) {
+            debug!("Prune Cache: Attempting to remove file: {:?}", file_path);
+            if let Err(e) = tokio_fs::remove_file(file_path).await {
+                error!("Failed to remove old cache file {:?}: {}", file_path, e);
             } else {
-                debug!("Removed old cache file: {:?}", files_with_time[i].1);
+                debug!("Removed old cache file: {:?}", file_path);
             }
         }
         debug!("Prune Cache: Pruning complete.");
@@ -1449,16 +1549,62 @@ impl TTSManager {
         Some(final_url)
     }
 
-    /// Returns a reference to the `HashMap` of supported languages.
+    /// Returns when online TTS generation last succeeded and last failed.
+    pub fn online_status(&self) -> OnlineTtsStatus {
+        self.online_status
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the `HashMap` of supported languages under the current configuration.
     ///
     /// The map keys are language codes (e.g., "th", "en-uk") and values are
     /// their corresponding display names (e.g., "Thai", "British English").
-    ///
-    /// # Returns
-    /// A reference to the internal `supported_languages_map`.
-    pub fn get_supported_languages(&self) -> &HashMap<String, String> {
+    pub fn get_supported_languages(&self) -> HashMap<String, String> {
         debug!("Get Supported Languages: Returning map of supported languages.");
-        &self.supported_languages_map
+        Self::parse_supported_languages(&self.config.get().tts_supported_languages)
+    }
+
+    /// Parses `TTS_SUPPORTED_LANGUAGES` (`code:Name` entries) into a map of
+    /// normalized language codes to display names.
+    fn parse_supported_languages(raw: &str) -> HashMap<String, String> {
+        raw.split(',') // Split by comma-separated entries
+            .filter_map(|entry| {
+                let trimmed_entry = entry.trim();
+                if trimmed_entry.is_empty() {
+                    trace!("Parse Supported Languages: Skipping empty language entry");
+                    return None;
+                }
+
+                let mut parts = trimmed_entry.splitn(2, ':');
+                let code_raw = parts.next().map(str::trim).unwrap_or("");
+                if code_raw.is_empty() {
+                    trace!(
+                        "Parse Supported Languages: Skipping language entry with empty code: {:?}",
+                        entry
+                    );
+                    return None;
+                }
+
+                let normalized_code = normalize_language_code(code_raw);
+
+                let display_name = parts
+                    .next()
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| code_raw.to_string());
+
+                trace!(
+                    "Parse Supported Languages: Parsed language '{}' (display '{}')",
+                    normalized_code,
+                    display_name
+                );
+
+                Some((normalized_code, display_name))
+            })
+            .collect()
     }
 }
 
@@ -1492,6 +1638,28 @@ mod tests {
             sse_keep_alive_interval_seconds: 15,
             sse_event_buffer_size: 200,
             tts_cache_web_path: "/tts_cache".to_string(),
+            display_disconnect_alert_seconds: 120,
+            display_zones: String::new(),
+            rooms: String::new(),
+            event_replay_buffer_size: 500,
+            webhooks_file: String::new(),
+            mqtt_broker_url: String::new(),
+            mqtt_topic_prefix: "queue".to_string(),
+            mqtt_commands_enabled: false,
+            keypad_tcp_port: 0,
+            ticket_services: "A:General".to_string(),
+            ticket_printer: String::new(),
+            ticket_header: String::new(),
+            ticket_status_url_template: String::new(),
+            ticket_notifier: String::new(),
+            ticket_notify_people_ahead: 3,
+            no_show_timeout_seconds: 0,
+            no_show_auto_recalls: 0,
+            history_database_path: String::new(),
+            settings_overrides_path: String::new(),
+            shutdown_timeout_seconds: 8,
+            announcement_schedules: String::new(),
+            trusted_proxies: String::new(),
         }
     }
 
@@ -1562,4 +1730,39 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn build_stem_audio_urls_spells_out_unparseable_identifiers() {
+        assert_eq!(
+            TTSManager::build_stem_audio_urls_for_language("AB-1", "2", "en-GB"),
+            vec![
+                "/media/audio_stems/en-GB/phrase_number.mp3".to_string(),
+                "/media/audio_stems/en-GB/char_a.mp3".to_string(),
+                "/media/audio_stems/en-GB/char_b.mp3".to_string(),
+                "/media/audio_stems/en-GB/number_001.mp3".to_string(),
+                "/media/audio_stems/en-GB/phrase_to_counter.mp3".to_string(),
+                "/media/audio_stems/en-GB/number_002.mp3".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_stem_audio_urls_skips_missing_files() {
+        let serve_dir =
+            std::env::temp_dir().join(format!("queue-stem-urls-{}", std::process::id()));
+        let stem_dir = serve_dir.join("media/audio_stems/th");
+        std::fs::create_dir_all(&stem_dir).unwrap();
+        std::fs::write(stem_dir.join("phrase_number.mp3"), b"").unwrap();
+        std::fs::write(stem_dir.join("number_001.mp3"), b"").unwrap();
+
+        let urls = TTSManager::build_stem_audio_urls_for_language("Q1", "7", "th");
+        assert_eq!(
+            TTSManager::existing_stem_audio_urls(&serve_dir, urls).await,
+            vec![
+                "/media/audio_stems/th/phrase_number.mp3".to_string(),
+                "/media/audio_stems/th/number_001.mp3".to_string(),
+            ]
+        );
+        std::fs::remove_dir_all(&serve_dir).unwrap();
+    }
 }
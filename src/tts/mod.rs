@@ -10,6 +10,7 @@
 // This makes the code within `src/tts/manager.rs` available to this module
 // and potentially to other parts of the crate if re-exported.
 pub mod manager;
+pub mod stems;
 
 // Re-export the `TTSManager` struct from the `manager` submodule.
 // This allows other modules to import `TTSManager` directly from `crate::tts::TTSManager`
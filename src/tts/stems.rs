@@ -0,0 +1,123 @@
+// src/tts/stems.rs
+
+//! Pre-recorded audio stems the TTS fallback assembles announcements from,
+//! e.g. `phrase_number.mp3`, `char_a.mp3` and `number_004.mp3`.
+//!
+//! Stem sets are scanned at startup and on request so missing files are
+//! reported instead of turning into silent 404s on the displays.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::tts::manager::AnnouncementLanguage;
+
+/// Directory below `SERVE_DIR_PATH` holding one stem directory per language.
+pub const STEMS_SUB_PATH: &str = "media/audio_stems";
+
+/// File names a language's stem directory needs so that any call can be
+/// announced: the two phrases, one stem per digit and one per letter.
+pub fn required_stems() -> Vec<String> {
+    let mut stems = vec![
+        "phrase_number.mp3".to_string(),
+        "phrase_to_counter.mp3".to_string(),
+    ];
+    stems.extend((0..10).map(|digit| format!("number_{:03}.mp3", digit)));
+    stems.extend(('a'..='z').map(|letter| format!("char_{}.mp3", letter)));
+    stems
+}
+
+/// Stem directory of `language` on disk.
+pub(crate) fn stem_dir(config: &AppConfig, language: AnnouncementLanguage) -> PathBuf {
+    config
+        .serve_dir_path
+        .join(STEMS_SUB_PATH)
+        .join(language.stem_dir())
+}
+
+/// The stem directory of a configured language and what it holds.
+#[derive(Debug, Clone, Serialize)]
+pub struct StemInventory {
+    /// First configured language code announced with these stems.
+    pub language: String,
+    pub directory: PathBuf,
+    /// Audio files present, sorted by name.
+    pub available: Vec<String>,
+    /// Required stems that are absent; empty when the set is complete.
+    pub missing: Vec<String>,
+}
+
+/// Scans the stem directory of every configured language. Languages announced
+/// with the same stems share one entry.
+pub fn inventory(config: &AppConfig) -> Vec<StemInventory> {
+    let mut checked = Vec::new();
+    let mut inventories = Vec::new();
+    for code in config.ordered_supported_language_codes_raw() {
+        let language =
+            AnnouncementLanguage::for_code(&code).unwrap_or(AnnouncementLanguage::English);
+        if checked.contains(&language) {
+            continue;
+        }
+        checked.push(language);
+        let directory = stem_dir(config, language);
+        let mut available: Vec<String> = std::fs::read_dir(&directory)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_file())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        available.sort();
+        inventories.push(StemInventory {
+            language: code,
+            missing: missing_stems(&directory),
+            directory,
+            available,
+        });
+    }
+    inventories
+}
+
+/// Path on disk of a stem URL such as `/media/audio_stems/th/char_a.mp3`.
+pub fn url_to_path(serve_dir: &Path, url: &str) -> PathBuf {
+    serve_dir.join(url.trim_start_matches('/'))
+}
+
+/// Required stems absent from `dir`, in the order of [`required_stems`].
+pub fn missing_stems(dir: &Path) -> Vec<String> {
+    required_stems()
+        .into_iter()
+        .filter(|stem| !dir.join(stem).is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_stems_lists_absent_files() {
+        let dir = std::env::temp_dir().join(format!("queue-stems-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for stem in required_stems() {
+            if stem != "char_q.mp3" && stem != "number_007.mp3" {
+                std::fs::write(dir.join(stem), b"").unwrap();
+            }
+        }
+        assert_eq!(missing_stems(&dir), ["number_007.mp3", "char_q.mp3"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(missing_stems(&dir).len(), 38);
+    }
+
+    #[test]
+    fn test_url_to_path() {
+        assert_eq!(
+            url_to_path(Path::new("./public"), "/media/audio_stems/th/char_a.mp3"),
+            Path::new("./public/media/audio_stems/th/char_a.mp3")
+        );
+    }
+}
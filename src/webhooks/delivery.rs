@@ -0,0 +1,141 @@
+// src/webhooks/delivery.rs
+
+//! Bounded record of webhook delivery attempts and dead letters.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Number of recent deliveries kept for the status endpoint.
+pub const MAX_RECENT_DELIVERIES: usize = 100;
+/// Number of dead letters kept for the status endpoint.
+pub const MAX_DEAD_LETTERS: usize = 100;
+
+/// Lifecycle of one event delivery to one subscriber.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Queued, not attempted yet.
+    Pending,
+    /// At least one attempt failed; another one is scheduled.
+    Retrying,
+    /// The subscriber answered with a 2xx status.
+    Delivered,
+    /// Every attempt failed (or the subscriber's queue was full).
+    DeadLettered,
+}
+
+/// Progress of delivering one event to one subscriber.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DeliveryRecord {
+    /// Stable id sent in the `X-Queue-Delivery` header, `<subscription>-<event id>`.
+    pub delivery_id: String,
+    pub subscription_id: String,
+    pub event_id: u64,
+    pub event_type: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl DeliveryRecord {
+    pub fn new(subscription_id: &str, event_id: u64, event_type: &str, now: DateTime<Utc>) -> Self {
+        Self {
+            delivery_id: format!("{}-{}", subscription_id, event_id),
+            subscription_id: subscription_id.to_string(),
+            event_id,
+            event_type: event_type.to_string(),
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+            next_attempt_at: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Recent deliveries (newest last) plus the dead-letter list.
+#[derive(Debug, Default)]
+pub struct DeliveryLog {
+    recent: VecDeque<DeliveryRecord>,
+    dead_letters: VecDeque<DeliveryRecord>,
+}
+
+impl DeliveryLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or replace the record with the same delivery id. Dead-lettered
+    /// records are also copied to the dead-letter list.
+    pub fn upsert(&mut self, record: DeliveryRecord) {
+        if record.status == DeliveryStatus::DeadLettered {
+            if self.dead_letters.len() >= MAX_DEAD_LETTERS {
+                self.dead_letters.pop_front();
+            }
+            self.dead_letters.push_back(record.clone());
+        }
+
+        if let Some(existing) = self
+            .recent
+            .iter_mut()
+            .find(|r| r.delivery_id == record.delivery_id)
+        {
+            *existing = record;
+            return;
+        }
+        if self.recent.len() >= MAX_RECENT_DELIVERIES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(record);
+    }
+
+    /// Recent deliveries, newest first.
+    pub fn recent(&self) -> Vec<DeliveryRecord> {
+        self.recent.iter().rev().cloned().collect()
+    }
+
+    /// Dead letters, newest first.
+    pub fn dead_letters(&self) -> Vec<DeliveryRecord> {
+        self.dead_letters.iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_records_in_place_and_keeps_dead_letters() {
+        let now = Utc::now();
+        let mut log = DeliveryLog::new();
+        let mut record = DeliveryRecord::new("his", 7, "queue_update", now);
+        log.upsert(record.clone());
+
+        record.status = DeliveryStatus::Retrying;
+        record.attempts = 1;
+        log.upsert(record.clone());
+        assert_eq!(log.recent().len(), 1);
+        assert_eq!(log.recent()[0].status, DeliveryStatus::Retrying);
+        assert!(log.dead_letters().is_empty());
+
+        record.status = DeliveryStatus::DeadLettered;
+        record.attempts = 5;
+        log.upsert(record);
+        assert_eq!(log.recent().len(), 1);
+        assert_eq!(log.dead_letters().len(), 1);
+        assert_eq!(log.dead_letters()[0].delivery_id, "his-7");
+
+        for id in 0..(MAX_RECENT_DELIVERIES as u64 + 5) {
+            log.upsert(DeliveryRecord::new("his", 100 + id, "queue_update", now));
+        }
+        assert_eq!(log.recent().len(), MAX_RECENT_DELIVERIES);
+        assert_eq!(log.dead_letters().len(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+// src/webhooks/mod.rs
+
+//! Outbound webhook subscriptions.
+//!
+//! Subscriptions are read from the JSON file named by `WEBHOOKS_FILE`. Every
+//! matching [`crate::AppEvent`] is POSTed to the subscriber as JSON, signed with
+//! HMAC-SHA256 using the subscription's secret. Delivery progress is tracked in
+//! a [`DeliveryLog`] so operators can see retries and dead letters.
+
+pub mod delivery;
+
+pub use delivery::{DeliveryLog, DeliveryRecord, DeliveryStatus};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::AppEvent;
+
+/// Header carrying the `sha256=<hex>` signature of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Queue-Signature";
+/// Header carrying the event name (e.g. `queue_update`).
+pub const EVENT_HEADER: &str = "X-Queue-Event";
+/// Header carrying the delivery id, stable across retries.
+pub const DELIVERY_HEADER: &str = "X-Queue-Delivery";
+
+/// Upper bound for the delay between two delivery attempts.
+pub const MAX_RETRY_DELAY_SECONDS: u64 = 300;
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_retry_base_seconds() -> u64 {
+    2
+}
+
+/// One webhook subscriber as configured in `WEBHOOKS_FILE`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct WebhookSubscription {
+    /// Short identifier used in delivery ids and logs.
+    pub id: String,
+    /// Endpoint receiving the POST requests.
+    pub url: String,
+    /// Event names to deliver (see [`AppEvent::EVENT_NAMES`]); empty means all.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Shared secret used to sign request bodies.
+    pub secret: String,
+    /// Attempts per event before it is moved to the dead-letter list.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Per-request timeout.
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Delay before the first retry; doubled after every failed attempt.
+    #[serde(default = "default_retry_base_seconds")]
+    pub retry_base_seconds: u64,
+}
+
+impl WebhookSubscription {
+    /// Returns true when the subscriber wants this event.
+    pub fn wants(&self, event: &AppEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|name| name == event.event_name())
+    }
+
+    /// Delay before retrying after the given (1-based) failed attempt.
+    pub fn retry_delay(&self, failed_attempt: u32) -> Duration {
+        let exponent = failed_attempt.saturating_sub(1).min(16);
+        let seconds = self
+            .retry_base_seconds
+            .saturating_mul(1u64 << exponent)
+            .min(MAX_RETRY_DELAY_SECONDS);
+        Duration::from_secs(seconds)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("subscription id must not be empty".to_string());
+        }
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(format!(
+                "url '{}' must start with http:// or https://",
+                self.url
+            ));
+        }
+        if self.secret.is_empty() {
+            return Err("secret must not be empty".to_string());
+        }
+        if self.max_attempts == 0 {
+            return Err("max_attempts must be at least 1".to_string());
+        }
+        if let Some(unknown) = self
+            .events
+            .iter()
+            .find(|name| !AppEvent::EVENT_NAMES.contains(&name.as_str()))
+        {
+            return Err(format!(
+                "unknown event type '{}'. Supported types: {}",
+                unknown,
+                AppEvent::EVENT_NAMES.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Public view of a subscription, without its secret.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WebhookSubscriptionSummary {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub max_attempts: u32,
+}
+
+impl From<&WebhookSubscription> for WebhookSubscriptionSummary {
+    fn from(subscription: &WebhookSubscription) -> Self {
+        Self {
+            id: subscription.id.clone(),
+            url: subscription.url.clone(),
+            events: subscription.events.clone(),
+            max_attempts: subscription.max_attempts,
+        }
+    }
+}
+
+/// Errors that can occur while loading the webhook configuration file.
+#[derive(Debug)]
+pub enum WebhookConfigError {
+    /// The file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The file is not a JSON array of subscriptions.
+    Parse(PathBuf, serde_json::Error),
+    /// A subscription failed validation.
+    Invalid { id: String, reason: String },
+}
+
+impl fmt::Display for WebhookConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookConfigError::Io(path, err) => {
+                write!(f, "Failed to read webhook file {:?}: {}", path, err)
+            }
+            WebhookConfigError::Parse(path, err) => {
+                write!(f, "Failed to parse webhook file {:?}: {}", path, err)
+            }
+            WebhookConfigError::Invalid { id, reason } => {
+                write!(f, "Invalid webhook subscription '{}': {}", id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WebhookConfigError {}
+
+/// Reads and validates the subscriptions listed in a JSON file.
+pub fn load_subscriptions(path: &Path) -> Result<Vec<WebhookSubscription>, WebhookConfigError> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|err| WebhookConfigError::Io(path.to_path_buf(), err))?;
+    parse_subscriptions(&raw).map_err(|err| match err {
+        WebhookConfigError::Parse(_, inner) => WebhookConfigError::Parse(path.to_path_buf(), inner),
+        other => other,
+    })
+}
+
+/// Parses and validates subscriptions from a JSON array.
+pub fn parse_subscriptions(raw: &str) -> Result<Vec<WebhookSubscription>, WebhookConfigError> {
+    let subscriptions: Vec<WebhookSubscription> =
+        serde_json::from_str(raw).map_err(|err| WebhookConfigError::Parse(PathBuf::new(), err))?;
+    for (index, subscription) in subscriptions.iter().enumerate() {
+        subscription
+            .validate()
+            .map_err(|reason| WebhookConfigError::Invalid {
+                id: subscription.id.clone(),
+                reason,
+            })?;
+        if subscriptions[..index]
+            .iter()
+            .any(|other| other.id == subscription.id)
+        {
+            return Err(WebhookConfigError::Invalid {
+                id: subscription.id.clone(),
+                reason: "duplicate subscription id".to_string(),
+            });
+        }
+    }
+    Ok(subscriptions)
+}
+
+/// Computes the `sha256=<hex>` HMAC signature of a request body.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // Test vector from RFC 4231, test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn parses_and_validates_subscriptions() {
+        let subscriptions = parse_subscriptions(
+            r#"[{"id":"his","url":"https://his.example/hook","events":["queue_update"],"secret":"s"}]"#,
+        )
+        .unwrap();
+        assert_eq!(subscriptions[0].max_attempts, 5);
+        assert_eq!(subscriptions[0].retry_delay(1), Duration::from_secs(2));
+        assert_eq!(subscriptions[0].retry_delay(3), Duration::from_secs(8));
+        assert_eq!(
+            subscriptions[0].retry_delay(30),
+            Duration::from_secs(MAX_RETRY_DELAY_SECONDS)
+        );
+
+        let err = parse_subscriptions(
+            r#"[{"id":"his","url":"https://his.example/hook","events":["bogus"],"secret":"s"}]"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("bogus"));
+    }
+}